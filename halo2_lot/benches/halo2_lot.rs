@@ -0,0 +1,109 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use halo2_proofs::arithmetic::Field;
+use halo2_we_kzg::{
+    all_openings_fk_padded, kzg_open, Choice, Halo2Params, LaconicOTRecv, LaconicOTSender,
+    LaconicParams, Msg, MSG_SIZE,
+};
+use halo2curves::bn256::Fr;
+use rand::rngs::OsRng;
+
+fn bench_setup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("halo2_setup");
+    group.sample_size(10);
+
+    for k in 10..=14 {
+        group.bench_with_input(BenchmarkId::from_parameter(k), &k, |b, &k| {
+            b.iter(|| Halo2Params::setup(&mut OsRng, k).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_openings(c: &mut Criterion) {
+    let mut group = c.benchmark_group("halo2_openings");
+    group.sample_size(10);
+
+    for k in [4, 6, 8] {
+        let halo2params = Halo2Params::setup(&mut OsRng, k).unwrap();
+        let domain_size = 1 << k;
+        let elems: Vec<Fr> = (0..domain_size).map(|i| Fr::from(i as u64 % 2)).collect();
+
+        group.bench_with_input(BenchmarkId::new("fk", k), &k, |b, _| {
+            b.iter(|| {
+                all_openings_fk_padded(
+                    &halo2params.precomputed_y,
+                    &halo2params.domain,
+                    &elems,
+                    None,
+                )
+                .unwrap()
+            })
+        });
+
+        // One independent opening per evaluation point
+        group.bench_with_input(BenchmarkId::new("naive", k), &k, |b, _| {
+            let omega = halo2params.domain.get_omega();
+            b.iter(|| {
+                (0..domain_size)
+                    .map(|i| {
+                        let z = omega.pow_vartime([i as u64]);
+                        kzg_open(z, halo2params.clone(), elems.clone())
+                    })
+                    .collect::<Vec<_>>()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_recv(c: &mut Criterion) {
+    let mut group = c.benchmark_group("halo2_recv");
+    group.sample_size(10);
+
+    let halo2params = Halo2Params::setup(&mut OsRng, 9).unwrap();
+    let laconic_params = LaconicParams::from(&halo2params);
+    let bits: Vec<Choice> = (0..512)
+        .map(|i| {
+            if i % 2 == 0 {
+                Choice::Zero
+            } else {
+                Choice::One
+            }
+        })
+        .collect();
+    let receiver = LaconicOTRecv::new(halo2params, &bits).unwrap();
+    let sender = LaconicOTSender::new_from(
+        laconic_params,
+        receiver.commitment(),
+        receiver.domain_fingerprint(),
+    );
+    let items: Vec<(usize, Msg)> = (0..bits.len())
+        .map(|i| {
+            (
+                i,
+                sender
+                    .send(&mut OsRng, i, [0u8; MSG_SIZE], [1u8; MSG_SIZE])
+                    .unwrap(),
+            )
+        })
+        .collect();
+
+    group.bench_function("recv_512", |b| {
+        b.iter(|| {
+            items
+                .iter()
+                .map(|(i, msg)| receiver.recv(*i, *msg).unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("recv_many_512", |b| {
+        b.iter(|| receiver.recv_many(&items).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_setup, bench_openings, bench_recv);
+criterion_main!(benches);
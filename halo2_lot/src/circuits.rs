@@ -1,12 +1,12 @@
 use rand::rngs::OsRng;
-use std::io::{Cursor, Error};
+use std::io::{Cursor, Error, ErrorKind};
 
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner, Value},
-    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    halo2curves::bn256::{Bn256, Fr, G1Affine, G1},
     plonk::{
-        create_proof, keygen_pk, keygen_vk, verify_proof_multi, Circuit, ConstraintSystem,
-        ErrorFront, Expression, Selector,
+        create_proof, keygen_pk, keygen_vk, verify_proof_multi, vk_read, Advice, Circuit, Column,
+        ConstraintSystem, ErrorFront, Expression, Instance, Selector, VerifyingKey,
     },
     poly::{
         commitment::CommitmentScheme,
@@ -21,6 +21,7 @@ use halo2_proofs::{
         Blake2bRead, Blake2bWrite, Challenge255, TranscriptRead, TranscriptReadBuffer,
         TranscriptWriterBuffer,
     },
+    SerdeFormat,
 };
 
 #[derive()]
@@ -55,7 +56,7 @@ fn extract_commitments<C: CommitmentScheme>(
 /// A simple configuration struct that holds one Advice column.
 #[derive(Clone, Debug)]
 pub struct MyConfig {
-    advice_col: halo2_proofs::plonk::Column<halo2_proofs::plonk::Advice>,
+    advice_col: Column<Advice>,
     q_bit: Selector,
 }
 
@@ -113,6 +114,227 @@ impl Circuit<Fr> for BitvectorCommitmentCircuit {
     }
 }
 
+/// Bits used to range check `threshold - weight`, enough for any `u64`
+/// threshold
+const THRESHOLD_RANGE_BITS: usize = 64;
+
+#[derive(Clone, Debug)]
+pub struct ThresholdConfig {
+    bits: MyConfig,
+    sum_col: Column<Advice>,
+    range_col: Column<Advice>,
+    acc_col: Column<Advice>,
+    threshold: Column<Instance>,
+    q_sum_first: Selector,
+    q_sum: Selector,
+    q_range_first: Selector,
+    q_range: Selector,
+    q_link: Selector,
+}
+
+/// `BitvectorCommitmentCircuit` that additionally proves the number of set
+/// bits is at most the public input `threshold`.
+///
+/// The bits sit in the same first unblinded advice column, so the advice
+/// commitment is still the plain KZG commitment of the bitvector. A running
+/// sum column accumulates the weight, and `threshold - weight` is decomposed
+/// into `THRESHOLD_RANGE_BITS` bits to show it is not negative.
+#[derive(Clone, Debug)]
+pub struct ThresholdBitvectorCircuit {
+    pub(crate) bitvector: Vec<Fr>,
+    pub(crate) threshold: u64,
+}
+
+impl ThresholdBitvectorCircuit {
+    pub fn new(bitvector: Vec<Fr>, threshold: u64) -> Self {
+        Self {
+            bitvector,
+            threshold,
+        }
+    }
+
+    /// Public inputs to pass to the prover and verifier
+    pub fn instances(&self) -> Vec<Vec<Fr>> {
+        vec![vec![Fr::from(self.threshold)]]
+    }
+}
+
+impl Circuit<Fr> for ThresholdBitvectorCircuit {
+    type Config = ThresholdConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            bitvector: vec![Fr::zero(); self.bitvector.len()],
+            threshold: 0,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> ThresholdConfig {
+        // Allocated first so the bits stay in the committed advice column
+        let bits = BitvectorCommitmentCircuit::configure(meta);
+
+        let sum_col = meta.advice_column();
+        let range_col = meta.advice_column();
+        let acc_col = meta.advice_column();
+        let threshold = meta.instance_column();
+        meta.enable_equality(sum_col);
+        meta.enable_equality(range_col);
+        meta.enable_equality(acc_col);
+        meta.enable_equality(threshold);
+
+        let q_sum_first = meta.selector();
+        let q_sum = meta.selector();
+        let q_range_first = meta.selector();
+        let q_range = meta.selector();
+        let q_link = meta.selector();
+
+        // sum[0] = bit[0], sum[i] = sum[i - 1] + bit[i]
+        meta.create_gate("running sum", |meta| {
+            let first = meta.query_selector(q_sum_first);
+            let step = meta.query_selector(q_sum);
+            let bit = meta.query_advice(bits.advice_col, Rotation::cur());
+            let sum = meta.query_advice(sum_col, Rotation::cur());
+            let prev = meta.query_advice(sum_col, Rotation::prev());
+
+            vec![
+                first * (sum.clone() - bit.clone()),
+                step * (sum - prev - bit),
+            ]
+        });
+
+        // MSB first: acc[0] = r[0], acc[j] = 2 * acc[j - 1] + r[j]
+        meta.create_gate("range decomposition", |meta| {
+            let first = meta.query_selector(q_range_first);
+            let step = meta.query_selector(q_range);
+            let r = meta.query_advice(range_col, Rotation::cur());
+            let acc = meta.query_advice(acc_col, Rotation::cur());
+            let prev = meta.query_advice(acc_col, Rotation::prev());
+            let one = Expression::Constant(Fr::from(1u64));
+            let two = Expression::Constant(Fr::from(2u64));
+
+            vec![
+                (first.clone() + step.clone()) * r.clone() * (r.clone() - one),
+                first * (acc.clone() - r.clone()),
+                step * (acc - two * prev - r),
+            ]
+        });
+
+        // weight + (threshold - weight) = threshold
+        meta.create_gate("threshold link", |meta| {
+            let s = meta.query_selector(q_link);
+            let weight = meta.query_advice(sum_col, Rotation::cur());
+            let slack = meta.query_advice(acc_col, Rotation::cur());
+            let total = meta.query_advice(range_col, Rotation::cur());
+
+            vec![s * (total - weight - slack)]
+        });
+
+        ThresholdConfig {
+            bits,
+            sum_col,
+            range_col,
+            acc_col,
+            threshold,
+            q_sum_first,
+            q_sum,
+            q_range_first,
+            q_range,
+            q_link,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: ThresholdConfig,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), ErrorFront> {
+        if self.bitvector.is_empty() {
+            return Err(ErrorFront::Synthesis);
+        }
+
+        let weight_cell = layouter.assign_region(
+            || "assign bits",
+            |mut region| {
+                let mut sum = Fr::zero();
+                let mut sum_cell = None;
+                for (i, bit) in self.bitvector.iter().enumerate() {
+                    config.bits.q_bit.enable(&mut region, i)?;
+                    if i == 0 {
+                        config.q_sum_first.enable(&mut region, i)?;
+                    } else {
+                        config.q_sum.enable(&mut region, i)?;
+                    }
+                    region.assign_advice(
+                        || "bit",
+                        config.bits.advice_col,
+                        i,
+                        || Value::known(*bit),
+                    )?;
+                    sum += bit;
+                    sum_cell = Some(region.assign_advice(
+                        || "sum",
+                        config.sum_col,
+                        i,
+                        || Value::known(sum),
+                    )?);
+                }
+                Ok(sum_cell.expect("bitvector is not empty"))
+            },
+        )?;
+
+        // An over-threshold weight wraps around and fails the link gate
+        let weight = self.bitvector.iter().filter(|b| **b == Fr::one()).count() as u64;
+        let slack = self.threshold.wrapping_sub(weight);
+        let slack_cell = layouter.assign_region(
+            || "range check slack",
+            |mut region| {
+                let mut acc = Fr::zero();
+                let mut acc_cell = None;
+                for j in 0..THRESHOLD_RANGE_BITS {
+                    if j == 0 {
+                        config.q_range_first.enable(&mut region, j)?;
+                    } else {
+                        config.q_range.enable(&mut region, j)?;
+                    }
+                    let r = Fr::from((slack >> (THRESHOLD_RANGE_BITS - 1 - j)) & 1);
+                    region.assign_advice(
+                        || "slack bit",
+                        config.range_col,
+                        j,
+                        || Value::known(r),
+                    )?;
+                    acc = acc + acc + r;
+                    acc_cell = Some(region.assign_advice(
+                        || "slack",
+                        config.acc_col,
+                        j,
+                        || Value::known(acc),
+                    )?);
+                }
+                Ok(acc_cell.expect("range has at least one bit"))
+            },
+        )?;
+
+        let total_cell = layouter.assign_region(
+            || "threshold link",
+            |mut region| {
+                config.q_link.enable(&mut region, 0)?;
+                weight_cell.copy_advice(|| "weight", &mut region, config.sum_col, 0)?;
+                slack_cell.copy_advice(|| "slack", &mut region, config.acc_col, 0)?;
+                region.assign_advice(
+                    || "threshold",
+                    config.range_col,
+                    0,
+                    || Value::known(Fr::from(self.threshold)),
+                )
+            },
+        )?;
+
+        layouter.constrain_instance(total_cell.cell(), config.threshold, 0)
+    }
+}
+
 pub fn kzg_commitment_with_halo2_proof(
     prover_params: ParamsKZG<Bn256>,
     bitvector: Vec<Fr>,
@@ -141,17 +363,9 @@ pub fn kzg_commitment_with_halo2_proof(
     let proof = proof_transcript.finalize();
 
     // Verify the proof
-    let mut verifier_transcript =
-        Blake2bRead::<_, G1Affine, Challenge255<_>>::init(proof.as_slice());
     let verifier_params = prover_params.verifier_params();
-
     assert!(
-        verify_proof_multi::<KZGCommitmentScheme<Bn256>, VerifierGWC<Bn256>, _, _, SingleStrategy<_>>(
-            &verifier_params,
-            &pk.get_vk(),
-            &[(&[]).to_vec()],
-            &mut verifier_transcript,
-        ),
+        verify_bitvector_proof(&verifier_params, pk.get_vk(), &proof),
         "failed to verify proof"
     );
 
@@ -169,6 +383,86 @@ pub fn kzg_commitment_with_halo2_proof(
     })
 }
 
+/// Run the Halo2 verifier on a bit-constraint proof for the circuit `vk` was
+/// generated from. This only checks the proof itself, use
+/// `verify_kzg_commitment_with_halo2_proof` to also bind it to a commitment.
+pub fn verify_bitvector_proof(
+    verifier_params: &<KZGCommitmentScheme<Bn256> as CommitmentScheme>::ParamsVerifier,
+    vk: &VerifyingKey<G1Affine>,
+    proof: &[u8],
+) -> bool {
+    let mut verifier_transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(proof);
+
+    verify_proof_multi::<KZGCommitmentScheme<Bn256>, VerifierGWC<Bn256>, _, _, SingleStrategy<_>>(
+        verifier_params,
+        vk,
+        &[(&[]).to_vec()],
+        &mut verifier_transcript,
+    )
+}
+
+/// Verifying key of the bit-constraint circuit for a vector of `num_bits`
+/// bits. It only depends on how many rows carry the constraint, but building
+/// it takes the full params.
+pub fn bitvector_vk(
+    params: &ParamsKZG<Bn256>,
+    num_bits: usize,
+) -> Result<VerifyingKey<G1Affine>, Error> {
+    let circuit = BitvectorCommitmentCircuit {
+        bitvector: vec![Fr::zero(); num_bits],
+    };
+    keygen_vk(params, &circuit).map_err(|_| Error::new(ErrorKind::Other, "keygen_vk failed"))
+}
+
+/// Read a key written by `VerifyingKey::write` in `RawBytes` format for
+/// params of degree `k`
+pub fn read_bitvector_vk(bytes: &[u8], k: u32) -> Result<VerifyingKey<G1Affine>, Error> {
+    // Only the constraint system is taken from the circuit, not the witness
+    let circuit = BitvectorCommitmentCircuit {
+        bitvector: Vec::new(),
+    };
+    vk_read(&mut &bytes[..], SerdeFormat::RawBytes, k, &circuit, true)
+}
+
+/// Verify a proof produced by `kzg_commitment_with_halo2_proof` for a vector
+/// of `num_bits` bits, and check that the proven advice column commitment is
+/// `commitment`. The verifying key is rebuilt here from `num_bits`.
+pub fn verify_kzg_commitment_with_halo2_proof(
+    params: &ParamsKZG<Bn256>,
+    num_bits: usize,
+    commitment: &G1,
+    proof: &[u8],
+) -> Result<(), Error> {
+    let vk = bitvector_vk(params, num_bits)?;
+    verify_kzg_commitment_with_vk(params, &vk, commitment, proof)
+}
+
+/// Same as `verify_kzg_commitment_with_halo2_proof` with a verifying key from
+/// `bitvector_vk`. The GWC verifier only reads `g[0]`, `g2` and `s_g2` from
+/// `params`, see `Halo2VerifierParams`.
+pub fn verify_kzg_commitment_with_vk(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    commitment: &G1,
+    proof: &[u8],
+) -> Result<(), Error> {
+    let verifier_params = params.verifier_params();
+    if !verify_bitvector_proof(&verifier_params, vk, proof) {
+        return Err(Error::new(ErrorKind::InvalidData, "failed to verify proof"));
+    }
+
+    // The proof must be about the commitment we were given
+    let commitments = extract_commitments::<KZGCommitmentScheme<Bn256>>(proof, 1);
+    if G1::from(commitments[0]) != *commitment {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "proof does not match the commitment",
+        ));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -210,6 +504,35 @@ mod tests {
         // println!("Printed Column: {:?}", &advice_vals);
     }
 
+    #[test]
+    fn test_threshold_circuit() {
+        let bits = |v: &[u64]| v.iter().map(|b| Fr::from(*b)).collect::<Vec<_>>();
+        let k = 7;
+
+        for (bitvector, threshold, ok) in [
+            (bits(&[1, 0, 1, 0]), 2, true),
+            (bits(&[1, 0, 1, 0]), 3, true),
+            (bits(&[0, 0, 0, 0]), 0, true),
+            (bits(&[1, 1, 1, 0]), 2, false),
+            (bits(&[1, 1, 1, 1]), 0, false),
+        ] {
+            let circuit = ThresholdBitvectorCircuit {
+                bitvector,
+                threshold,
+            };
+            let prover = MockProver::run(k, &circuit, circuit.instances()).unwrap();
+            assert_eq!(prover.verify().is_ok(), ok, "threshold {}", threshold);
+        }
+
+        // The public threshold must match the one the witness was built for
+        let circuit = ThresholdBitvectorCircuit {
+            bitvector: bits(&[1, 1, 0, 0]),
+            threshold: 3,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(2)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
     #[test]
     fn test_circuit_commitment() {
         // Circuit setup
@@ -288,4 +611,64 @@ mod tests {
         // Assert commitments match
         assert_eq!(halo2_commitment, advice_commitments_affine[0]);
     }
+
+    #[test]
+    fn test_verify_bitvector_proof() {
+        let k = 4;
+        let bitvector = vec![Fr::one(), Fr::zero(), Fr::one()];
+        let params: ParamsKZG<Bn256> = ParamsKZG::setup(k, &mut OsRng);
+
+        let output = kzg_commitment_with_halo2_proof(params.clone(), bitvector.clone()).unwrap();
+
+        let circuit = BitvectorCommitmentCircuit {
+            bitvector: vec![Fr::zero(); bitvector.len()],
+        };
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let verifier_params = params.verifier_params();
+        assert!(verify_bitvector_proof(&verifier_params, &vk, &output.proof));
+
+        let mut tampered = output.proof.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(!verify_bitvector_proof(&verifier_params, &vk, &tampered));
+    }
+
+    #[test]
+    fn test_verify_commitment_proof() {
+        let k = 4;
+        let bitvector = vec![Fr::zero(), Fr::one(), Fr::one(), Fr::zero()];
+        let params: ParamsKZG<Bn256> = ParamsKZG::setup(k, &mut OsRng);
+
+        let output = kzg_commitment_with_halo2_proof(params.clone(), bitvector.clone()).unwrap();
+
+        verify_kzg_commitment_with_halo2_proof(
+            &params,
+            bitvector.len(),
+            &output.commitment.into(),
+            &output.proof,
+        )
+        .expect("valid proof should verify");
+
+        // Flip a byte past the advice commitment
+        let mut tampered = output.proof.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(verify_kzg_commitment_with_halo2_proof(
+            &params,
+            bitvector.len(),
+            &output.commitment.into(),
+            &tampered
+        )
+        .is_err());
+
+        // A valid proof for a different commitment is rejected too
+        let other = G1Affine::generator().into();
+        assert!(verify_kzg_commitment_with_halo2_proof(
+            &params,
+            bitvector.len(),
+            &other,
+            &output.proof
+        )
+        .is_err());
+    }
 }
@@ -1,24 +1,90 @@
 use crate::{
     kzg_commitment_with_halo2_proof,
-    params::LaconicParams,
-    poly_op::{kzg_open, serialize_cubic_ext_field},
+    params::{LaconicParams, G1_RAW_BYTES, G2_RAW_BYTES},
+    poly_op::{all_openings_fk_padded, serialize_cubic_ext_field},
     Halo2Params,
 };
 use halo2_proofs::{
     arithmetic::Field,
     halo2curves::{
         bn256::{Bn256, Fq, Fr, G1Affine, G2Affine, G1, G2},
+        ff::PrimeField,
         ff_ext::{cubic::CubicExtField, quadratic::QuadExtField},
-        group::Curve,
+        group::{cofactor::CofactorCurveAffine, Curve},
         pairing::Engine,
     },
-    poly::{commitment::Params, kzg::commitment::ParamsKZG, EvaluationDomain},
+    poly::{kzg::commitment::ParamsKZG, EvaluationDomain},
 };
 use halo2curves::{bn256::Gt, serde::SerdeObject};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
-const MSG_SIZE: usize = 16;
+/// Default message size, matching the 16-byte wire labels used for garbling
+pub const MSG_SIZE: usize = 16;
+
+/// Compare two recovered messages in constant time.
+///
+/// Payloads returned by `LaconicOTRecv::recv` are secret (e.g. wire labels),
+/// so protocols built on top should use this instead of `==`.
+pub fn ct_eq<const N: usize>(a: &[u8; N], b: &[u8; N]) -> subtle::Choice {
+    subtle::ConstantTimeEq::ct_eq(&a[..], &b[..])
+}
+
+/// Error returned by `LaconicOTRecv::recv`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvError {
+    /// The message index is past the end of the committed bits
+    IndexOutOfRange { index: usize, len: usize },
+}
+
+impl std::fmt::Display for RecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecvError::IndexOutOfRange { index, len } => {
+                write!(
+                    f,
+                    "OT index {} out of range for {} committed bits",
+                    index, len
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+/// Error returned by the `LaconicOTSender` send methods
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendError {
+    /// The receiver committed over another evaluation domain (a different
+    /// `k`), so it could not decrypt anything sent on ours
+    DomainMismatch,
+}
+
+impl std::fmt::Display for SendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendError::DomainMismatch => write!(
+                f,
+                "Receiver committed over a different evaluation domain than the sender's"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SendError {}
+
+/// Identifies an evaluation domain by its size and generator `omega`. The
+/// sender evaluates at `omega^i`, so both sides must have the same one.
+/// Hashes the same bytes as the plain backend: the size as a little-endian
+/// `u64`, then the canonical little-endian encoding of the generator.
+pub fn domain_fingerprint(domain: &EvaluationDomain<Fr>) -> [u8; 32] {
+    let mut hsh = blake3::Hasher::new();
+    hsh.update(&(1u64 << domain.k()).to_le_bytes());
+    hsh.update(domain.get_omega().to_repr().as_ref());
+    *hsh.finalize().as_bytes()
+}
 
 fn fq12_to_bytes(gt: Gt) -> Vec<u8> {
     // Here gt.get_base() returns an Fq12‑like type that has methods c0() and c1(),
@@ -33,45 +99,62 @@ fn fq12_to_bytes(gt: Gt) -> Vec<u8> {
     out
 }
 
+/// Laconic OT message carrying two `N`-byte ciphertexts, one per choice bit
 #[derive(Clone, Copy, Debug)]
-pub struct Msg {
-    pub h: [(G2Affine, [u8; MSG_SIZE]); 2],
+pub struct Msg<const N: usize = MSG_SIZE> {
+    pub h: [(G2Affine, [u8; N]); 2],
 }
 
+/// Serializable form of `Msg`, the ciphertext length is carried by the data
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct SerializableMsg {
-    pub h: [(Vec<u8>, [u8; MSG_SIZE]); 2],
+    pub h: [(Vec<u8>, Vec<u8>); 2],
 }
 
 // Implement From trait to convert Msg to SerializableMsg
-impl From<Msg> for SerializableMsg {
-    fn from(msg: Msg) -> Self {
+impl<const N: usize> From<Msg<N>> for SerializableMsg {
+    fn from(msg: Msg<N>) -> Self {
         SerializableMsg {
             h: [
-                (msg.h[0].0.to_raw_bytes(), msg.h[0].1),
-                (msg.h[1].0.to_raw_bytes(), msg.h[1].1),
+                (msg.h[0].0.to_raw_bytes(), msg.h[0].1.to_vec()),
+                (msg.h[1].0.to_raw_bytes(), msg.h[1].1.to_vec()),
             ],
         }
     }
 }
 
 // Implement TryFrom trait to convert SerializableMsg to Msg
-impl TryFrom<SerializableMsg> for Msg {
+impl<const N: usize> TryFrom<SerializableMsg> for Msg<N> {
     type Error = &'static str;
 
     fn try_from(s: SerializableMsg) -> Result<Self, Self::Error> {
+        // Raw G2 points are two Fq2 coordinates, reject anything else before
+        // it reaches the curve code
+        if s.h.iter().any(|(h, _)| h.len() != G2_RAW_BYTES) {
+            return Err("Invalid length for G2Affine");
+        }
         let g2_0 =
             G2Affine::from_raw_bytes(&s.h[0].0).ok_or("Failed to deserialize first G2Affine")?;
         let g2_1 =
             G2Affine::from_raw_bytes(&s.h[1].0).ok_or("Failed to deserialize second G2Affine")?;
+        let ct_0: [u8; N] = s.h[0]
+            .1
+            .as_slice()
+            .try_into()
+            .map_err(|_| "Invalid length for first ciphertext")?;
+        let ct_1: [u8; N] = s.h[1]
+            .1
+            .as_slice()
+            .try_into()
+            .map_err(|_| "Invalid length for second ciphertext")?;
 
         Ok(Msg {
-            h: [(g2_0, s.h[0].1), (g2_1, s.h[1].1)],
+            h: [(g2_0, ct_0), (g2_1, ct_1)],
         })
     }
 }
 
-impl AsMut<[u8]> for Msg {
+impl<const N: usize> AsMut<[u8]> for Msg<N> {
     fn as_mut(&mut self) -> &mut [u8] {
         &mut self.h[0].1
     }
@@ -79,12 +162,15 @@ impl AsMut<[u8]> for Msg {
 
 pub type Com = G1;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
 pub enum Choice {
+    #[default]
     Zero,
     One,
 }
 
+impl zeroize::DefaultIsZeroes for Choice {}
+
 impl Choice {
     pub fn to_fr<F: Field>(&self) -> Fr {
         match self {
@@ -101,6 +187,106 @@ pub struct LaconicOTRecv {
     bits: Vec<Choice>,
     pub halo2params: Halo2Params,
     pub proof: Vec<u8>,
+    /// Mixed into every keystream, see `with_session_id`
+    session: [u8; 32],
+}
+
+/// A receiver whose commitment and proof are computed but whose openings are
+/// not, from `LaconicOTRecv::commitment_only`. The commitment can be
+/// published right away; `prepare_openings` runs the expensive FK step later.
+/// The choice bits are wiped when dropped.
+#[derive(Debug, Clone)]
+pub struct PendingRecv {
+    com: Com,
+    bits: Zeroizing<Vec<Choice>>,
+    pub halo2params: Halo2Params,
+    pub proof: Vec<u8>,
+    session: [u8; 32],
+}
+
+impl PendingRecv {
+    pub fn commitment(&self) -> Com {
+        self.com
+    }
+
+    /// See `LaconicOTRecv::domain_fingerprint`
+    pub fn domain_fingerprint(&self) -> [u8; 32] {
+        domain_fingerprint(&self.halo2params.domain)
+    }
+
+    /// Session id the receiver will decrypt under, see
+    /// `LaconicOTRecv::with_session_id`
+    pub fn with_session_id(mut self, id: [u8; 32]) -> Self {
+        self.session = id;
+        self
+    }
+
+    /// Fails when the bits don't fit the params' domain
+    pub fn prepare_openings(self) -> Result<LaconicOTRecv, String> {
+        self.prepare_openings_with_progress(None)
+    }
+
+    /// Like `prepare_openings`, reporting the completed fraction to `progress`
+    pub fn prepare_openings_with_progress(
+        mut self,
+        progress: Option<&dyn Fn(f32)>,
+    ) -> Result<LaconicOTRecv, String> {
+        // Openings of the zero-padded vector at the first elems.len() points
+        let qs: Vec<G1> = all_openings_fk_padded(
+            &self.halo2params.precomputed_y,
+            &self.halo2params.domain,
+            &bit_elems(&self.bits),
+            progress,
+        )?;
+
+        Ok(LaconicOTRecv {
+            qs,
+            com: self.com,
+            bits: std::mem::take(&mut *self.bits),
+            halo2params: self.halo2params,
+            proof: self.proof,
+            session: self.session,
+        })
+    }
+}
+
+/// Wipes the committed choices, the receiver's private input. Runs on drop;
+/// a wiped receiver rejects every index like an empty one.
+impl Zeroize for LaconicOTRecv {
+    fn zeroize(&mut self) {
+        self.bits.zeroize();
+    }
+}
+
+impl Drop for LaconicOTRecv {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for LaconicOTRecv {}
+
+/// Map choice bits to the 0/1 field elements the receiver commits to
+fn bit_elems(bits: &[Choice]) -> Vec<Fr> {
+    bits.iter()
+        .map(|b| {
+            if *b == Choice::One {
+                Fr::from(1)
+            } else {
+                Fr::from(0)
+            }
+        })
+        .collect()
+}
+
+/// Wire form of `LaconicOTRecv` without the shared `Halo2Params`. Points are
+/// stored as concatenated raw affine bytes, bits as 0/1 bytes.
+#[derive(Serialize, Deserialize)]
+struct SerializableLaconicOTRecv {
+    qs: Vec<u8>,
+    com: Vec<u8>,
+    bits: Vec<u8>,
+    proof: Vec<u8>,
 }
 
 #[derive(Debug, Clone)]
@@ -108,150 +294,484 @@ pub struct LaconicOTSender {
     params: LaconicParams,
     com: Com,
     domain: EvaluationDomain<Fr>,
+    /// `domain_fingerprint` of the receiver, checked before every send
+    receiver_domain: [u8; 32],
+    /// Mixed into every keystream, see `with_session_id`
+    session: [u8; 32],
 }
 
 impl LaconicOTRecv {
-    pub fn new(halo2params: Halo2Params, bits: &[Choice]) -> Self {
-        let elems: Vec<_> = bits
-            .iter()
-            .map(|b| {
-                if *b == Choice::One {
-                    Fr::from(1)
-                } else {
-                    Fr::from(0)
-                }
-            })
-            .collect();
+    /// Fails when the bits don't fit the params' domain, see
+    /// `PendingRecv::prepare_openings`
+    pub fn new(halo2params: Halo2Params, bits: &[Choice]) -> Result<Self, String> {
+        Self::new_with_progress(halo2params, bits, None)
+    }
 
+    /// Like `new`, but reports the completed fraction (0.0..=1.0) to
+    /// `progress`. The Halo2 proof accounts for the first half, the FK
+    /// openings for the second.
+    pub fn new_with_progress(
+        halo2params: Halo2Params,
+        bits: &[Choice],
+        progress: Option<&dyn Fn(f32)>,
+    ) -> Result<Self, String> {
+        let pending = Self::commitment_only(halo2params, bits);
+        if let Some(progress) = progress {
+            progress(0.5);
+        }
+
+        let fk_progress =
+            progress.map(|progress| move |fraction: f32| progress(0.5 + fraction / 2.0));
+        pending.prepare_openings_with_progress(fk_progress.as_ref().map(|f| f as &dyn Fn(f32)))
+    }
+
+    /// Compute only the commitment and its Halo2 proof, and defer the FK
+    /// openings to `PendingRecv::prepare_openings`
+    pub fn commitment_only(halo2params: Halo2Params, bits: &[Choice]) -> PendingRecv {
         let circuit_params = halo2params.params.clone();
-        let circuit_output = kzg_commitment_with_halo2_proof(circuit_params, elems.clone())
+        let circuit_output = kzg_commitment_with_halo2_proof(circuit_params, bit_elems(bits))
             .expect("kzg_commitment_with_halo2_proof failed");
 
-        let domain_size = 1 << halo2params.k;
-        let mut elems_padded = elems.clone();
-        if elems_padded.len() < domain_size {
-            elems_padded.resize(domain_size, Fr::zero());
+        PendingRecv {
+            com: circuit_output.commitment.into(),
+            bits: Zeroizing::new(bits.to_vec()),
+            halo2params,
+            proof: circuit_output.proof,
+            session: [0; 32],
         }
+    }
 
-        let n = elems.clone().len();
-        let points: Vec<Fr> = (0..n)
-            .map(|i| halo2params.domain.get_omega().pow(&[i as u64]))
-            .collect();
-        let qs: Vec<G1> = points
-            .iter()
-            .map(|&z| kzg_open(z, halo2params.clone(), elems.clone()))
-            .collect();
+    /// Assemble a receiver from openings computed elsewhere, e.g. by a
+    /// helper that ran the FK step with `qs_bytes`/`qs_from_bytes` as the
+    /// wire format. Like `from_bytes`, the openings are not checked against
+    /// `com` or `halo2params`.
+    ///
+    /// The helper does learn the bits: computing the openings needs the
+    /// committed evaluations, and anyone holding `com` can test each opening
+    /// against both bit values. Only offload to a party trusted with them.
+    pub fn from_parts(
+        halo2params: Halo2Params,
+        qs: Vec<G1>,
+        com: Com,
+        bits: &[Choice],
+        proof: Vec<u8>,
+    ) -> Result<Self, &'static str> {
+        if qs.len() != bits.len() {
+            return Err("Number of openings does not match number of bits");
+        }
 
-        Self {
+        Ok(Self {
             qs,
-            com: circuit_output.commitment.into(),
+            com,
             bits: bits.to_vec(),
             halo2params,
-            proof: circuit_output.proof,
+            proof,
+            session: [0; 32],
+        })
+    }
+
+    /// The openings alone, as concatenated raw affine points (64 bytes each)
+    pub fn qs_bytes(&self) -> Vec<u8> {
+        let mut qs_affine = vec![G1Affine::identity(); self.qs.len()];
+        G1::batch_normalize(&self.qs, &mut qs_affine);
+        qs_affine.iter().flat_map(|q| q.to_raw_bytes()).collect()
+    }
+
+    /// Parse openings written by `qs_bytes`
+    pub fn qs_from_bytes(bytes: &[u8]) -> Result<Vec<G1>, &'static str> {
+        if bytes.len() % G1_RAW_BYTES != 0 {
+            return Err("Invalid length for qs bytes");
+        }
+        bytes
+            .chunks_exact(G1_RAW_BYTES)
+            .map(|chunk| G1Affine::from_raw_bytes(chunk).map(G1::from))
+            .collect::<Option<Vec<G1>>>()
+            .ok_or("Failed to deserialize a G1Affine point in qs")
+    }
+
+    /// Serialize the receiver state computed by `new` so it can be cached and
+    /// restored with `from_bytes`. `halo2params` is left out since it is large
+    /// and shared; the output holds the choice bits, so store it privately.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let serializable = SerializableLaconicOTRecv {
+            qs: self.qs_bytes(),
+            com: self.com.to_affine().to_raw_bytes(),
+            bits: self
+                .bits
+                .iter()
+                .map(|b| (*b == Choice::One) as u8)
+                .collect(),
+            proof: self.proof.clone(),
+        };
+
+        bincode::serialize(&serializable).unwrap_or_default()
+    }
+
+    /// Restore a receiver written by `to_bytes`. `halo2params` must be the
+    /// params it was created with, this is not checked.
+    pub fn from_bytes(bytes: &[u8], halo2params: Halo2Params) -> Result<Self, &'static str> {
+        let s: SerializableLaconicOTRecv =
+            bincode::deserialize(bytes).map_err(|_| "Failed to deserialize LaconicOTRecv")?;
+
+        let qs = Self::qs_from_bytes(&s.qs)?;
+        if s.com.len() != G1_RAW_BYTES {
+            return Err("Invalid length for commitment");
         }
+        let com = G1Affine::from_raw_bytes(&s.com)
+            .ok_or("Failed to deserialize commitment")?
+            .into();
+        let bits = s
+            .bits
+            .iter()
+            .map(|b| match b {
+                0 => Ok(Choice::Zero),
+                1 => Ok(Choice::One),
+                _ => Err("Invalid choice bit"),
+            })
+            .collect::<Result<Vec<Choice>, _>>()?;
+        if qs.len() != bits.len() {
+            return Err("Number of openings does not match number of bits");
+        }
+
+        Ok(Self {
+            qs,
+            com,
+            bits,
+            halo2params,
+            proof: s.proof,
+            session: [0; 32],
+        })
+    }
+
+    /// The committed choice at position `i`, i.e. the branch `recv` opens
+    /// there. Meant for debugging a failed transfer, the receiver knows its
+    /// bits anyway.
+    pub fn selected_branch(&self, i: usize) -> Result<Choice, RecvError> {
+        self.bits.get(i).copied().ok_or(RecvError::IndexOutOfRange {
+            index: i,
+            len: self.bits.len(),
+        })
     }
 
-    pub fn recv(&self, i: usize, msg: Msg) -> [u8; MSG_SIZE] {
-        let j: usize = if self.bits[i] == Choice::One { 1 } else { 0 };
+    /// Decrypt the `i`-th message. Compare results with [`ct_eq`].
+    ///
+    /// `i` comes from the sender's message stream, so it is checked against
+    /// the committed bits instead of panicking.
+    pub fn recv<const N: usize>(&self, i: usize, msg: Msg<N>) -> Result<[u8; N], RecvError> {
+        let j = usize::from(self.selected_branch(i)? == Choice::One);
         let h = msg.h[j].0;
         let c = msg.h[j].1;
         let q_affine: G1Affine = self.qs[i].to_affine();
         let m: Gt = <Bn256 as Engine>::pairing(&q_affine, &h);
-        decrypt::<MSG_SIZE>(m, &c)
+        Ok(decrypt::<N>(m, &self.session, i, j as u8, &c))
+    }
+
+    /// `len` bytes of keystream for position `i` of `msg`, on the committed
+    /// branch. `recv` XORs the first `N` of them into the ciphertext, callers
+    /// packing larger payloads can draw as many as they need from the same
+    /// pad.
+    pub fn keystream<const N: usize>(
+        &self,
+        i: usize,
+        msg: &Msg<N>,
+        len: usize,
+    ) -> Result<Vec<u8>, RecvError> {
+        let j = usize::from(self.selected_branch(i)? == Choice::One);
+        let q_affine: G1Affine = self.qs[i].to_affine();
+        let m: Gt = <Bn256 as Engine>::pairing(&q_affine, &msg.h[j].0);
+        let mut stream = vec![0u8; len];
+        pad_hasher(m, &self.session, i, j as u8)
+            .finalize_xof()
+            .fill(&mut stream);
+        Ok(stream)
+    }
+
+    /// Decrypt many positions at once, in the order of `items`.
+    ///
+    /// Every output depends on its own pairing, so the Miller loops and final
+    /// exponentiations cannot be shared. The openings are however moved to
+    /// affine form with one batched inversion instead of one per call.
+    pub fn recv_many<const N: usize>(
+        &self,
+        items: &[(usize, Msg<N>)],
+    ) -> Result<Vec<[u8; N]>, RecvError> {
+        let mut branches = Vec::with_capacity(items.len());
+        let mut qs = Vec::with_capacity(items.len());
+        for (i, _) in items {
+            let bit = self.bits.get(*i).ok_or(RecvError::IndexOutOfRange {
+                index: *i,
+                len: self.bits.len(),
+            })?;
+            branches.push(if *bit == Choice::One { 1 } else { 0 });
+            qs.push(self.qs[*i]);
+        }
+
+        let mut qs_affine = vec![G1Affine::identity(); qs.len()];
+        G1::batch_normalize(&qs, &mut qs_affine);
+
+        Ok(items
+            .iter()
+            .zip(branches.into_iter().zip(qs_affine))
+            .map(|((i, msg), (j, q_affine))| {
+                let (h, c) = msg.h[j];
+                let m: Gt = <Bn256 as Engine>::pairing(&q_affine, &h);
+                decrypt::<N>(m, &self.session, *i, j as u8, &c)
+            })
+            .collect())
+    }
+
+    /// Decrypt a multi-block payload produced by `LaconicOTSender::send_blocks`
+    pub fn recv_blocks(&self, i: usize, msgs: &[Msg]) -> Result<Vec<[u8; MSG_SIZE]>, RecvError> {
+        let bit = self.bits.get(i).ok_or(RecvError::IndexOutOfRange {
+            index: i,
+            len: self.bits.len(),
+        })?;
+        let j: usize = if *bit == Choice::One { 1 } else { 0 };
+        let Some(first) = msgs.first() else {
+            return Ok(Vec::new());
+        };
+
+        // Every block carries the same h, one pairing covers the payload
+        let q_affine: G1Affine = self.qs[i].to_affine();
+        let m: Gt = <Bn256 as Engine>::pairing(&q_affine, &first.h[j].0);
+        let pad = pad_hasher(m, &self.session, i, j as u8);
+
+        Ok(msgs
+            .iter()
+            .enumerate()
+            .map(|(k, msg)| encrypt_block(&pad, k, &msg.h[j].1))
+            .collect())
     }
 
     pub fn commitment(&self) -> Com {
         self.com
     }
+
+    /// Fingerprint of the domain the commitment was made over, to send along
+    /// with the commitment and hand to `LaconicOTSender::new`
+    pub fn domain_fingerprint(&self) -> [u8; 32] {
+        domain_fingerprint(&self.halo2params.domain)
+    }
+
+    /// Decrypt under session `id`, which the sender must also use, see
+    /// `LaconicOTSender::with_session_id`. Defaults to all zeros, and is not
+    /// part of `to_bytes`.
+    pub fn with_session_id(mut self, id: [u8; 32]) -> Self {
+        self.session = id;
+        self
+    }
+}
+
+/// Encrypt the `branch` (0 or 1) message at OT position `i`
+fn encrypt<const N: usize>(
+    pad: Gt,
+    session: &[u8; 32],
+    i: usize,
+    branch: u8,
+    msg: &[u8; N],
+) -> [u8; N] {
+    xor_keystream(pad_hasher(pad, session, i, branch), msg)
 }
 
-fn encrypt<const N: usize>(pad: Gt, msg: &[u8; N]) -> [u8; N] {
+fn decrypt<const N: usize>(
+    pad: Gt,
+    session: &[u8; 32],
+    i: usize,
+    branch: u8,
+    ct: &[u8; N],
+) -> [u8; N] {
+    encrypt::<N>(pad, session, i, branch, ct)
+}
+
+/// Blake3 state after absorbing the pad, cloned to derive per-block keystreams.
+/// The session, position and branch are absorbed too, so a keystream is bound
+/// to the slot it encrypts even if two pads ever coincide.
+fn pad_hasher(pad: Gt, session: &[u8; 32], i: usize, branch: u8) -> blake3::Hasher {
     let pad_bytes = fq12_to_bytes(pad);
     // Hash the pad, converting it to bytes with to_bytes()
     let mut hasher = blake3::Hasher::new();
     hasher.update(&pad_bytes);
+    hasher.update(session);
+    hasher.update(&(i as u64).to_le_bytes());
+    hasher.update(&[branch]);
+    hasher
+}
+
+/// Encrypt block `block_index` of a multi-block payload. All blocks share one
+/// pad, the keystream of each block is H(pad || block_index), so no two
+/// blocks of a transfer reuse keystream.
+fn encrypt_block(
+    hasher: &blake3::Hasher,
+    block_index: usize,
+    block: &[u8; MSG_SIZE],
+) -> [u8; MSG_SIZE] {
+    let mut hasher = hasher.clone();
+    hasher.update(&(block_index as u64).to_le_bytes());
+    xor_keystream(hasher, block)
+}
 
+fn xor_keystream<const N: usize>(hasher: blake3::Hasher, msg: &[u8; N]) -> [u8; N] {
     // Finalize as an XOF and fill a buffer
-    let mut xof = hasher.finalize_xof();
     let mut res = [0u8; N];
-    xof.fill(&mut res);
+    hasher.finalize_xof().fill(&mut res);
 
     // XOR the generated bytes with the message to encrypt/decrypt.
-    for i in 0..N {
-        res[i] ^= msg[i];
+    for (r, m) in res.iter_mut().zip(msg) {
+        *r ^= m;
     }
     res
 }
 
-fn decrypt<const N: usize>(pad: Gt, ct: &[u8; N]) -> [u8; N] {
-    encrypt::<N>(pad, ct)
-}
-
 impl LaconicOTSender {
-    pub fn new(_params: ParamsKZG<Bn256>, com: Com) -> Self {
-        let verifier_params = _params.verifier_params();
-        let domain = EvaluationDomain::new(1, verifier_params.k());
-        let params = LaconicParams {
-            k: verifier_params.k(),
-            g0: _params.g[0],
-            g2: _params.g2,
-            s_g2: _params.s_g2,
-        };
+    /// Sender for the commitment `com` of a receiver whose
+    /// `LaconicOTRecv::domain_fingerprint` is `receiver_domain`
+    pub fn new(params: ParamsKZG<Bn256>, com: Com, receiver_domain: [u8; 32]) -> Self {
+        Self::new_from(LaconicParams::from(&params), com, receiver_domain)
+    }
 
+    /// Like `new`, from the sender-only params. Sending fails if the receiver
+    /// used a different `k`, instead of producing messages `recv` silently
+    /// decrypts to garbage.
+    pub fn new_from(params: LaconicParams, com: Com, receiver_domain: [u8; 32]) -> Self {
+        let domain = EvaluationDomain::new(1, params.k);
         Self {
             params,
             com,
             domain,
+            receiver_domain,
+            session: [0; 32],
         }
     }
 
-    pub fn new_from(params: LaconicParams, com: Com) -> Self {
-        let domain = EvaluationDomain::new(1, params.k);
-        Self {
-            params,
-            com,
-            domain,
+    /// Encrypt under session `id`, mixed into every keystream. Concurrent
+    /// sessions with distinct ids never share a pad, even for equal
+    /// commitments and messages. The receiver must use the same id, see
+    /// `LaconicOTRecv::with_session_id`. Defaults to all zeros.
+    pub fn with_session_id(mut self, id: [u8; 32]) -> Self {
+        self.session = id;
+        self
+    }
+
+    /// Whether the receiver committed over the domain we send on
+    pub fn check_domain(&self) -> Result<(), SendError> {
+        if self.receiver_domain != domain_fingerprint(&self.domain) {
+            return Err(SendError::DomainMismatch);
         }
+        Ok(())
     }
 
-    pub fn send<R: Rng>(
+    /// Errors if the receiver domain differs, see `check_domain`
+    pub fn send<R: Rng, const N: usize>(
         &self,
         rng: &mut R,
         i: usize,
-        m0: [u8; MSG_SIZE],
-        m1: [u8; MSG_SIZE],
-    ) -> Msg {
-        let x = self.domain.get_omega().pow_vartime([i as u64]);
-        let r0 = Fr::random(&mut *rng);
-        let r1 = Fr::random(&mut *rng);
+        m0: [u8; N],
+        m1: [u8; N],
+    ) -> Result<Msg<N>, SendError> {
+        Ok(self.send_batch(rng, &[i], &[(m0, m1)])?.remove(0))
+    }
+
+    /// Send one message pair per index, returned in the same order as `indices`.
+    /// Randomness is drawn from `rng` in the same order as repeated calls to `send`.
+    pub fn send_batch<R: Rng, const N: usize>(
+        &self,
+        rng: &mut R,
+        indices: &[usize],
+        msgs: &[([u8; N], [u8; N])],
+    ) -> Result<Vec<Msg<N>>, SendError> {
+        assert_eq!(
+            indices.len(),
+            msgs.len(),
+            "indices and messages must have the same length"
+        );
+        self.check_domain()?;
+
+        // c - [1] is shared by every one-branch
+        let com_minus_g1 = self.com - self.params.g0;
+
+        Ok(indices
+            .iter()
+            .zip(msgs)
+            .map(|(&i, (m0, m1))| {
+                let [(h0, msk0), (h1, msk1)] = self.keys(&mut *rng, i, com_minus_g1);
+
+                // encapsulate the messages
+                Msg {
+                    h: [
+                        (h0, encrypt::<N>(msk0, &self.session, i, 0, m0)),
+                        (h1, encrypt::<N>(msk1, &self.session, i, 1, m1)),
+                    ],
+                }
+            })
+            .collect())
+    }
+
+    /// Transfer a payload of several `MSG_SIZE` blocks at position `i`,
+    /// returning one message per block.
+    ///
+    /// All blocks reuse the pairing for `i`, so the receiver does a single
+    /// pairing. The keystream of block `k` is derived from `(pad || k)`, which
+    /// keeps blocks independent without fresh randomness per block.
+    pub fn send_blocks<R: Rng>(
+        &self,
+        rng: &mut R,
+        i: usize,
+        m0: &[[u8; MSG_SIZE]],
+        m1: &[[u8; MSG_SIZE]],
+    ) -> Result<Vec<Msg>, SendError> {
+        assert_eq!(
+            m0.len(),
+            m1.len(),
+            "both payloads must have the same number of blocks"
+        );
+        self.check_domain()?;
 
-        let g1 = self.params.g0;
+        let com_minus_g1 = self.com - self.params.g0;
+        let [(h0, msk0), (h1, msk1)] = self.keys(rng, i, com_minus_g1);
+        let pad0 = pad_hasher(msk0, &self.session, i, 0);
+        let pad1 = pad_hasher(msk1, &self.session, i, 1);
+
+        Ok(m0
+            .iter()
+            .zip(m1)
+            .enumerate()
+            .map(|(k, (b0, b1))| Msg {
+                h: [
+                    (h0, encrypt_block(&pad0, k, b0)),
+                    (h1, encrypt_block(&pad1, k, b1)),
+                ],
+            })
+            .collect())
+    }
+
+    /// Key material for position `i`: per branch, the G2 element sent to the
+    /// receiver and the pairing value it lets the receiver recompute
+    fn keys<R: Rng>(&self, rng: &mut R, i: usize, com_minus_g1: G1) -> [(G2Affine, Gt); 2] {
+        let omega = self.domain.get_omega();
         let g2 = self.params.g2;
-        let tau = self.params.s_g2;
+        let tau = Into::<G2>::into(self.params.s_g2);
+
+        let x = omega.pow_vartime([i as u64]);
+        let r0 = Fr::random(&mut *rng);
+        let r1 = Fr::random(&mut *rng);
 
         // y = 0/1
         let l0 = self.com * r0; // r * (c - [y])
-        let l1 = (self.com - g1) * r1; // r * (c - [y])
+        let l1 = com_minus_g1 * r1; // r * (c - [y])
 
         let l0_affine = l0.to_affine();
         let l1_affine = l1.to_affine();
 
         // m0, m1
-        let msk0 = <Bn256 as Engine>::pairing(&l0_affine, &self.params.g2);
-        let msk1 = <Bn256 as Engine>::pairing(&l1_affine, &self.params.g2);
+        let msk0 = <Bn256 as Engine>::pairing(&l0_affine, &g2);
+        let msk1 = <Bn256 as Engine>::pairing(&l1_affine, &g2);
 
         // h0, h1
-        let cm = Into::<G2>::into(tau) - g2 * x;
+        let cm = tau - g2 * x;
         let h0: G2 = cm * r0;
         let h1: G2 = cm * r1;
 
-        // encapsulate the messages
-        Msg {
-            h: [
-                (h0.into(), encrypt::<MSG_SIZE>(msk0, &m0)),
-                (h1.into(), encrypt::<MSG_SIZE>(msk1, &m1)),
-            ],
-        }
+        [(h0.into(), msk0), (h1.into(), msk1)]
     }
 }
 
@@ -269,7 +789,7 @@ mod tests {
     use halo2_proofs::{
         arithmetic::Field,
         halo2curves::bn256::{Fr, G1Affine, G1},
-        poly::commitment::{Blind, ParamsProver},
+        poly::commitment::{Blind, Params, ParamsProver},
     };
     use rand::{rngs::OsRng, Rng};
     use std::io::{self, Write};
@@ -314,17 +834,446 @@ mod tests {
         let halo2params = Halo2Params::setup(rng, degree).unwrap();
         let laconic_params = LaconicParams::from(&halo2params);
 
-        let receiver = LaconicOTRecv::new(halo2params, &bitvector);
+        let receiver = LaconicOTRecv::new(halo2params, &bitvector).unwrap();
 
-        let sender = LaconicOTSender::new_from(laconic_params, receiver.commitment());
+        let sender = LaconicOTSender::new_from(
+            laconic_params,
+            receiver.commitment(),
+            receiver.domain_fingerprint(),
+        );
 
         let m0 = [0u8; MSG_SIZE];
         let m1 = [1u8; MSG_SIZE];
-        let msg = sender.send(rng, 0, m0, m1);
-        let res = receiver.recv(0, msg);
+        let msg = sender.send(rng, 0, m0, m1).unwrap();
+        let res = receiver.recv(0, msg).unwrap();
         assert_eq!(res, m0);
     }
 
+    #[test]
+    fn test_receiver_zeroize_wipes_choices() {
+        let rng = &mut OsRng;
+
+        let halo2params = Halo2Params::setup(rng, 4).unwrap();
+        let laconic_params = LaconicParams::from(&halo2params);
+        let mut receiver = LaconicOTRecv::new(halo2params, &[Choice::One, Choice::Zero]).unwrap();
+        let sender = LaconicOTSender::new_from(
+            laconic_params,
+            receiver.commitment(),
+            receiver.domain_fingerprint(),
+        );
+        assert_eq!(receiver.selected_branch(0), Ok(Choice::One));
+
+        receiver.zeroize();
+        let msg = sender
+            .send(rng, 0, [0u8; MSG_SIZE], [1u8; MSG_SIZE])
+            .unwrap();
+        assert_eq!(
+            receiver.recv(0, msg),
+            Err(RecvError::IndexOutOfRange { index: 0, len: 0 })
+        );
+        assert_eq!(
+            receiver.selected_branch(0),
+            Err(RecvError::IndexOutOfRange { index: 0, len: 0 })
+        );
+    }
+
+    #[test]
+    fn test_laconic_ot_64_byte_payload_as_blocks() {
+        let rng = &mut OsRng;
+
+        let bitvector = [Choice::Zero, Choice::One, Choice::Zero, Choice::One];
+        let halo2params = Halo2Params::setup(rng, 4).unwrap();
+        let laconic_params = LaconicParams::from(&halo2params);
+
+        let receiver = LaconicOTRecv::new(halo2params, &bitvector).unwrap();
+        let sender = LaconicOTSender::new_from(
+            laconic_params,
+            receiver.commitment(),
+            receiver.domain_fingerprint(),
+        );
+
+        // 64-byte payloads, four distinct blocks each
+        let m0: Vec<[u8; MSG_SIZE]> = (0..4u8).map(|k| [k; MSG_SIZE]).collect();
+        let m1: Vec<[u8; MSG_SIZE]> = (0..4u8).map(|k| [0x80 | k; MSG_SIZE]).collect();
+
+        for i in 0..bitvector.len() {
+            let msgs = sender.send_blocks(rng, i, &m0, &m1).unwrap();
+            assert_eq!(msgs.len(), 4);
+
+            let expected = if bitvector[i] == Choice::One {
+                &m1
+            } else {
+                &m0
+            };
+            assert_eq!(&receiver.recv_blocks(i, &msgs).unwrap(), expected);
+        }
+
+        // Equal plaintext blocks still get distinct keystreams
+        let same = vec![[7u8; MSG_SIZE]; 4];
+        let msgs = sender.send_blocks(rng, 0, &same, &same).unwrap();
+        assert_ne!(msgs[0].h[0].1, msgs[1].h[0].1);
+        assert_ne!(msgs[0].h[1].1, msgs[1].h[1].1);
+    }
+
+    #[test]
+    fn test_keystream_bound_to_position_and_branch() {
+        let rng = &mut OsRng;
+
+        let halo2params = Halo2Params::setup(rng, 4).unwrap();
+        let laconic_params = LaconicParams::from(&halo2params);
+        let receiver = LaconicOTRecv::new(halo2params, &[Choice::Zero, Choice::One]).unwrap();
+        let sender = LaconicOTSender::new_from(
+            laconic_params,
+            receiver.commitment(),
+            receiver.domain_fingerprint(),
+        );
+
+        let com_minus_g1 = sender.com - sender.params.g0;
+        let [(_, pad), _] = sender.keys(rng, 0, com_minus_g1);
+
+        // Same pad, same plaintext: only the slot differs
+        let zero = [0u8; MSG_SIZE];
+        let session = [0; 32];
+        let slot_0_0 = encrypt(pad, &session, 0, 0, &zero);
+        assert_ne!(slot_0_0, encrypt(pad, &session, 1, 0, &zero));
+        assert_ne!(slot_0_0, encrypt(pad, &session, 0, 1, &zero));
+        assert_ne!(slot_0_0, encrypt(pad, &[1; 32], 0, 0, &zero));
+        assert_eq!(decrypt(pad, &session, 0, 0, &slot_0_0), zero);
+
+        // Longer keystreams extend the one `recv` XORs into the ciphertext
+        let m1 = [5u8; MSG_SIZE];
+        let msg = sender.send(rng, 1, zero, m1).unwrap();
+        let stream = receiver.keystream(1, &msg, 64).unwrap();
+        assert_eq!(stream.len(), 64);
+        let opened: Vec<u8> = msg.h[1].1.iter().zip(&stream).map(|(c, k)| c ^ k).collect();
+        assert_eq!(opened, m1);
+        assert_eq!(
+            receiver.keystream(2, &msg, 64),
+            Err(RecvError::IndexOutOfRange { index: 2, len: 2 })
+        );
+    }
+
+    #[test]
+    fn test_recv_index_out_of_range() {
+        let rng = &mut OsRng;
+
+        let bitvector = [Choice::Zero, Choice::One, Choice::Zero, Choice::One];
+        let halo2params = Halo2Params::setup(rng, 4).unwrap();
+        let laconic_params = LaconicParams::from(&halo2params);
+
+        let receiver = LaconicOTRecv::new(halo2params, &bitvector).unwrap();
+        let sender = LaconicOTSender::new_from(
+            laconic_params,
+            receiver.commitment(),
+            receiver.domain_fingerprint(),
+        );
+
+        let msg = sender
+            .send(rng, 0, [0u8; MSG_SIZE], [1u8; MSG_SIZE])
+            .unwrap();
+        assert_eq!(
+            receiver.recv(bitvector.len(), msg),
+            Err(RecvError::IndexOutOfRange { index: 4, len: 4 })
+        );
+
+        for (i, &bit) in bitvector.iter().enumerate() {
+            assert_eq!(receiver.selected_branch(i), Ok(bit));
+        }
+        assert_eq!(
+            receiver.selected_branch(4),
+            Err(RecvError::IndexOutOfRange { index: 4, len: 4 })
+        );
+    }
+
+    #[test]
+    fn test_send_batch_matches_send() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let rng = &mut OsRng;
+
+        let degree = 4;
+        let bitvector = [Choice::Zero, Choice::One, Choice::Zero, Choice::One];
+
+        let halo2params = Halo2Params::setup(rng, degree).unwrap();
+        let laconic_params = LaconicParams::from(&halo2params);
+
+        let receiver = LaconicOTRecv::new(halo2params, &bitvector).unwrap();
+        let sender = LaconicOTSender::new_from(
+            laconic_params,
+            receiver.commitment(),
+            receiver.domain_fingerprint(),
+        );
+
+        let indices = [3, 0, 2, 1];
+        let msgs: Vec<_> = (0..4u8)
+            .map(|i| ([i; MSG_SIZE], [i + 10; MSG_SIZE]))
+            .collect();
+
+        // Same rng stream for both paths
+        let mut rng_single = StdRng::seed_from_u64(7);
+        let mut rng_batch = StdRng::seed_from_u64(7);
+        let single: Vec<_> = indices
+            .iter()
+            .zip(&msgs)
+            .map(|(&i, &(m0, m1))| sender.send(&mut rng_single, i, m0, m1).unwrap())
+            .collect();
+        let batch = sender.send_batch(&mut rng_batch, &indices, &msgs).unwrap();
+
+        assert_eq!(single.len(), batch.len());
+        for (a, b) in single.iter().zip(&batch) {
+            assert_eq!(a.h[0].0, b.h[0].0);
+            assert_eq!(a.h[1].0, b.h[1].0);
+            assert_eq!(a.h[0].1, b.h[0].1);
+            assert_eq!(a.h[1].1, b.h[1].1);
+        }
+
+        // Output order follows the input order
+        for ((&i, &(m0, m1)), msg) in indices.iter().zip(&msgs).zip(batch) {
+            let expected = if bitvector[i] == Choice::One { m1 } else { m0 };
+            assert_eq!(receiver.recv(i, msg).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_recv_state_roundtrip() {
+        let rng = &mut OsRng;
+
+        let bitvector = [Choice::One, Choice::Zero, Choice::Zero, Choice::One];
+        let halo2params = Halo2Params::setup(rng, 4).unwrap();
+        let laconic_params = LaconicParams::from(&halo2params);
+
+        let receiver = LaconicOTRecv::new(halo2params.clone(), &bitvector).unwrap();
+        let restored =
+            LaconicOTRecv::from_bytes(&receiver.to_bytes(), halo2params.clone()).unwrap();
+        assert_eq!(restored.commitment(), receiver.commitment());
+        assert_eq!(restored.proof, receiver.proof);
+
+        let sender = LaconicOTSender::new_from(
+            laconic_params,
+            restored.commitment(),
+            restored.domain_fingerprint(),
+        );
+        for i in 0..bitvector.len() {
+            let msg = sender
+                .send(rng, i, [i as u8; MSG_SIZE], [0xaa; MSG_SIZE])
+                .unwrap();
+            assert_eq!(restored.recv(i, msg), receiver.recv(i, msg));
+        }
+
+        assert!(LaconicOTRecv::from_bytes(&[1, 2, 3], halo2params.clone()).is_err());
+
+        // A commitment of the wrong length is rejected, not passed on
+        let mut state: SerializableLaconicOTRecv =
+            bincode::deserialize(&receiver.to_bytes()).unwrap();
+        state.com.pop();
+        let short_com = bincode::serialize(&state).unwrap();
+        assert_eq!(
+            LaconicOTRecv::from_bytes(&short_com, halo2params.clone()).err(),
+            Some("Invalid length for commitment")
+        );
+        let bytes = receiver.to_bytes();
+        for len in 0..bytes.len() {
+            assert!(LaconicOTRecv::from_bytes(&bytes[..len], halo2params.clone()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_receiver_from_offloaded_qs() {
+        let rng = &mut OsRng;
+
+        let bitvector = [Choice::One, Choice::Zero, Choice::One];
+        let halo2params = Halo2Params::setup(rng, 4).unwrap();
+
+        // Helper side: run the expensive setup and ship the openings
+        let helper = LaconicOTRecv::new(halo2params.clone(), &bitvector).unwrap();
+        let qs_bytes = helper.qs_bytes();
+        assert_eq!(qs_bytes.len(), 64 * bitvector.len());
+
+        // Client side: rebuild the receiver with its own bits
+        let qs = LaconicOTRecv::qs_from_bytes(&qs_bytes).unwrap();
+        let receiver = LaconicOTRecv::from_parts(
+            halo2params.clone(),
+            qs.clone(),
+            helper.commitment(),
+            &bitvector,
+            helper.proof.clone(),
+        )
+        .unwrap();
+
+        let sender = LaconicOTSender::new_from(
+            LaconicParams::from(&halo2params),
+            receiver.commitment(),
+            receiver.domain_fingerprint(),
+        );
+        for (i, bit) in bitvector.iter().enumerate() {
+            let m0 = [i as u8; MSG_SIZE];
+            let m1 = [i as u8 + 100; MSG_SIZE];
+            let msg = sender.send(rng, i, m0, m1).unwrap();
+            let expected = if *bit == Choice::One { m1 } else { m0 };
+            assert_eq!(receiver.recv(i, msg), Ok(expected));
+        }
+
+        assert!(LaconicOTRecv::from_parts(
+            halo2params,
+            qs,
+            helper.commitment(),
+            &bitvector[..2],
+            helper.proof.clone(),
+        )
+        .is_err());
+        assert!(LaconicOTRecv::qs_from_bytes(&qs_bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn test_prepare_openings_rejects_short_precomputed_y() {
+        let mut halo2params = Halo2Params::setup(&mut OsRng, 4).unwrap();
+        halo2params.precomputed_y.pop();
+
+        let pending = LaconicOTRecv::commitment_only(halo2params.clone(), &[Choice::One]);
+        assert!(pending.prepare_openings().is_err());
+        assert!(LaconicOTRecv::new(halo2params, &[Choice::One]).is_err());
+    }
+
+    #[test]
+    fn test_commitment_only_matches_new() {
+        let rng = &mut OsRng;
+
+        let bitvector = [Choice::One, Choice::Zero, Choice::One];
+        let halo2params = Halo2Params::setup(rng, 4).unwrap();
+
+        let pending = LaconicOTRecv::commitment_only(halo2params.clone(), &bitvector);
+        let full = LaconicOTRecv::new(halo2params.clone(), &bitvector).unwrap();
+        assert_eq!(pending.commitment(), full.commitment());
+
+        let receiver = pending.prepare_openings().unwrap();
+        assert_eq!(receiver.qs_bytes(), full.qs_bytes());
+        let sender = LaconicOTSender::new_from(
+            LaconicParams::from(&halo2params),
+            receiver.commitment(),
+            receiver.domain_fingerprint(),
+        );
+        let msg = sender
+            .send(rng, 2, [0u8; MSG_SIZE], [1u8; MSG_SIZE])
+            .unwrap();
+        assert_eq!(receiver.recv(2, msg), Ok([1u8; MSG_SIZE]));
+    }
+
+    #[test]
+    fn test_sender_rejects_receiver_domain() {
+        let rng = &mut OsRng;
+
+        let bitvector = [Choice::One, Choice::Zero];
+        let halo2params = Halo2Params::setup(rng, 4).unwrap();
+        let receiver = LaconicOTRecv::new(halo2params.clone(), &bitvector).unwrap();
+
+        let sender = LaconicOTSender::new_from(
+            LaconicParams::from(&halo2params),
+            receiver.commitment(),
+            receiver.domain_fingerprint(),
+        );
+        let msg = sender
+            .send(rng, 0, [0u8; MSG_SIZE], [1u8; MSG_SIZE])
+            .unwrap();
+        assert_eq!(receiver.recv(0, msg), Ok([1u8; MSG_SIZE]));
+
+        // Params for a larger domain do not pair with the receiver's
+        let other = Halo2Params::setup(rng, 5).unwrap();
+        let sender = LaconicOTSender::new_from(
+            LaconicParams::from(&other),
+            receiver.commitment(),
+            receiver.domain_fingerprint(),
+        );
+        assert_eq!(sender.check_domain(), Err(SendError::DomainMismatch));
+        assert_eq!(
+            sender.send(rng, 0, [0u8; MSG_SIZE], [1u8; MSG_SIZE]).err(),
+            Some(SendError::DomainMismatch)
+        );
+        assert_eq!(
+            sender
+                .send_batch(rng, &[0], &[([0u8; MSG_SIZE], [1u8; MSG_SIZE])])
+                .err(),
+            Some(SendError::DomainMismatch)
+        );
+        assert_eq!(
+            sender
+                .send_blocks(rng, 0, &[[0u8; MSG_SIZE]], &[[1u8; MSG_SIZE]])
+                .err(),
+            Some(SendError::DomainMismatch)
+        );
+    }
+
+    #[test]
+    fn test_recv_many_matches_recv() {
+        let rng = &mut OsRng;
+
+        let bitvector: Vec<Choice> = (0..16)
+            .map(|i| {
+                if i % 3 == 0 {
+                    Choice::One
+                } else {
+                    Choice::Zero
+                }
+            })
+            .collect();
+        let halo2params = Halo2Params::setup(rng, 4).unwrap();
+        let laconic_params = LaconicParams::from(&halo2params);
+
+        let receiver = LaconicOTRecv::new(halo2params, &bitvector).unwrap();
+        let sender = LaconicOTSender::new_from(
+            laconic_params,
+            receiver.commitment(),
+            receiver.domain_fingerprint(),
+        );
+
+        // Out of order and with a repeated index
+        let items: Vec<(usize, Msg)> = [5, 0, 15, 3, 5]
+            .into_iter()
+            .map(|i| {
+                let m0 = [i as u8; MSG_SIZE];
+                let m1 = [i as u8 + 100; MSG_SIZE];
+                (i, sender.send(rng, i, m0, m1).unwrap())
+            })
+            .collect();
+
+        let many = receiver.recv_many(&items).unwrap();
+        let single: Vec<_> = items
+            .iter()
+            .map(|(i, msg)| receiver.recv(*i, *msg).unwrap())
+            .collect();
+        assert_eq!(many, single);
+
+        let mut bad = items.clone();
+        bad[2].0 = 16;
+        assert_eq!(
+            receiver.recv_many(&bad),
+            Err(RecvError::IndexOutOfRange { index: 16, len: 16 })
+        );
+    }
+
+    #[test]
+    fn test_laconic_ot_32_byte_messages() {
+        let rng = &mut OsRng;
+
+        let degree = 4;
+        let bitvector = [Choice::Zero, Choice::One, Choice::Zero, Choice::One];
+
+        let halo2params = Halo2Params::setup(rng, degree).unwrap();
+        let laconic_params = LaconicParams::from(&halo2params);
+
+        let receiver = LaconicOTRecv::new(halo2params, &bitvector).unwrap();
+        let sender = LaconicOTSender::new_from(
+            laconic_params,
+            receiver.commitment(),
+            receiver.domain_fingerprint(),
+        );
+
+        let m0 = [0u8; 32];
+        let m1 = [1u8; 32];
+        let msg = sender.send(rng, 1, m0, m1).unwrap();
+        let res = receiver.recv(1, msg).unwrap();
+        assert_eq!(res, m1);
+    }
+
     #[test]
     fn test_msg_halo2_serialization() {
         use halo2_proofs::halo2curves::bn256::G2Affine;
@@ -366,6 +1315,11 @@ mod tests {
 
         assert_eq!(original_msg.h[0].0, from_json_msg.h[0].0);
         assert_eq!(original_msg.h[1].0, from_json_msg.h[1].0);
+
+        // Truncated points are rejected, not handed to the curve code
+        let mut truncated = SerializableMsg::from(original_msg);
+        truncated.h[1].0.pop();
+        assert!(Msg::<MSG_SIZE>::try_from(truncated).is_err());
     }
 
     #[test]
@@ -471,7 +1425,7 @@ mod tests {
 
         // FK-style all-openings
         let fk_start = Instant::now();
-        let fk_qs = crate::poly_op::all_openings_fk(&y, &halo2params.domain, &elems)
+        let fk_qs = crate::poly_op::all_openings_fk(&y, &halo2params.domain, &elems, None)
             .expect("Failed to compute all openings FK");
         println!("all_openings_fk openings took: {:?}", fk_start.elapsed());
 
@@ -483,4 +1437,14 @@ mod tests {
         assert_eq!(fk_qs.len(), size);
         io::stdout().flush().unwrap();
     }
+
+    #[test]
+    fn test_ct_eq_on_recovered_payloads() {
+        let a = [7u8; MSG_SIZE];
+        let mut b = a;
+        assert!(bool::from(ct_eq(&a, &b)));
+
+        b[MSG_SIZE - 1] ^= 1;
+        assert!(!bool::from(ct_eq(&a, &b)));
+    }
 }
@@ -4,8 +4,19 @@ pub mod params;
 mod poly_op;
 
 pub use crate::poly_op::{
-    eval_polynomial, poly_divide, serialize_cubic_ext_field, serialize_quad_ext_field,
+    all_openings_fk, all_openings_fk_padded, compare_fk_vs_kzg, eval_polynomial, kzg_open,
+    poly_divide, serialize_cubic_ext_field, serialize_quad_ext_field, FkKzgError, FkKzgMismatch,
+};
+pub use circuits::{
+    bitvector_vk, kzg_commitment_with_halo2_proof, read_bitvector_vk, verify_bitvector_proof,
+    verify_kzg_commitment_with_halo2_proof, verify_kzg_commitment_with_vk,
+    ThresholdBitvectorCircuit,
+};
+pub use laconic_ot::{
+    ct_eq, domain_fingerprint, Choice, Com, LaconicOTRecv, LaconicOTSender, Msg, PendingRecv,
+    RecvError, SendError, MSG_SIZE,
+};
+pub use params::{
+    estimate_params_bytes, precompute_y_for, Halo2Params, Halo2VerifierParams, LaconicParams,
+    SerializableLaconicParams,
 };
-pub use circuits::kzg_commitment_with_halo2_proof;
-pub use laconic_ot::{Choice, Com, LaconicOTRecv, LaconicOTSender, Msg};
-pub use params::{Halo2Params, LaconicParams, SerializableLaconicParams};
@@ -1,14 +1,54 @@
 use halo2_proofs::{
-    poly::{kzg::commitment::ParamsKZG, EvaluationDomain},
+    arithmetic::Field,
+    plonk::VerifyingKey,
+    poly::{commitment::Params, kzg::commitment::ParamsKZG, EvaluationDomain},
     SerdeFormat,
 };
 use halo2curves::{
-    bn256::{Bn256, Fr, G1Affine, G2Affine},
+    bn256::{Bn256, Fr, G1Affine, G2Affine, G1},
+    ff::PrimeField,
+    fft::best_fft,
+    group::{cofactor::CofactorCurveAffine, Curve},
+    pairing::Engine,
     serde::SerdeObject,
 };
 use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{self, BufReader, Read},
+    path::Path,
+};
+
+use crate::{
+    circuits::{bitvector_vk, read_bitvector_vk},
+    poly_op::precompute_y,
+};
 
-use crate::poly_op::precompute_y;
+/// Length of a raw (`SerdeObject::to_raw_bytes`) G1 point
+pub const G1_RAW_BYTES: usize = 64;
+/// Length of a raw G2 point, two `Fq2` coordinates
+pub const G2_RAW_BYTES: usize = 128;
+
+/// Reject a `k` read from untrusted bytes before it reaches `1 << k` or
+/// `EvaluationDomain::new`, which only support `1..=Fr::S`
+fn check_k(k: u32) -> Result<(), &'static str> {
+    if k == 0 || k > Fr::S {
+        return Err("k out of range");
+    }
+    Ok(())
+}
+
+/// `from_raw_bytes` assumes the input has the point's length
+fn point_from_raw_bytes<P: SerdeObject>(
+    bytes: &[u8],
+    len: usize,
+    err: &'static str,
+) -> Result<P, &'static str> {
+    if bytes.len() != len {
+        return Err(err);
+    }
+    P::from_raw_bytes(bytes).ok_or(err)
+}
 
 #[derive(Debug, Clone)]
 pub struct Halo2Params {
@@ -37,10 +77,29 @@ impl Halo2Params {
     pub fn setup<R: rand::Rng>(rng: &mut R, k: usize) -> Result<Halo2Params, ()> {
         let params: ParamsKZG<Bn256> = ParamsKZG::setup(k as u32, rng);
         let domain = EvaluationDomain::new(1, k as u32);
+        let precomputed_y = precompute_y_for(&params, &domain);
+
+        Ok(Halo2Params {
+            k,
+            domain,
+            params,
+            precomputed_y,
+        })
+    }
 
-        let size = 1 << k;
-        let powers = &params.g[..size];
-        let precomputed_y = precompute_y(powers, &domain);
+    /// Build params around an existing `ParamsKZG` and a `y` previously obtained
+    /// from `precompute_y_for` on those same params, skipping the FFT.
+    /// `y` depends on the secret powers, so it can only be reused with the
+    /// exact `ParamsKZG` it was computed from, never with freshly sampled ones.
+    pub fn setup_with_precomputed(
+        params: ParamsKZG<Bn256>,
+        precomputed_y: Vec<G1Affine>,
+    ) -> Result<Halo2Params, &'static str> {
+        let k = params.k() as usize;
+        if precomputed_y.len() != 2 << k {
+            return Err("Invalid length for precomputed_y");
+        }
+        let domain = EvaluationDomain::new(1, k as u32);
 
         Ok(Halo2Params {
             k,
@@ -95,12 +154,112 @@ impl Halo2Params {
         let serializable: SerializableHalo2Params =
             bincode::deserialize(bytes).map_err(|_| "Failed to deserialize Halo2Params")?;
 
+        Self::from_serializable(serializable)
+    }
+
+    /// Stream-deserialize params written by `to_bytes` without first
+    /// buffering the whole input
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, &'static str> {
+        let serializable: SerializableHalo2Params =
+            bincode::deserialize_from(reader).map_err(|_| "Failed to deserialize Halo2Params")?;
+
+        Self::from_serializable(serializable)
+    }
+
+    /// Load params from a file written by `to_bytes`, e.g. `halo2params.bin`
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, &'static str> {
+        let file = File::open(path).map_err(|_| "Failed to open Halo2Params file")?;
+        Self::from_reader(BufReader::new(file))
+    }
+
+    /// Build params for `1 << k` OT positions from the powers of tau of a
+    /// snarkjs / perpetual powers of tau `.ptau` ceremony file over BN254,
+    /// instead of the toy setup of `setup` whose secret is known to whoever
+    /// ran it.
+    ///
+    /// Expected format, all integers little-endian: the magic `ptau`, a `u32`
+    /// version and a `u32` section count, followed by sections made of a
+    /// `u32` type, a `u64` byte length and the data. Section 1 holds
+    /// `n8: u32` (32 for BN254), the `n8`-byte base field modulus and
+    /// `power: u32`, which must be at least `k`. Section 2 holds the
+    /// `tau^i * G1` points and section 3 the `tau^i * G2` points, as affine
+    /// coordinates in Montgomery form (`x || y`, `Fq2` as `c0 || c1`). Only
+    /// the first `1 << k` G1 points and the first two G2 points are read,
+    /// every other section is skipped.
+    pub fn from_ptau<R: Read>(mut reader: R, k: usize) -> Result<Self, &'static str> {
+        if k == 0 {
+            return Err("k must be at least 1");
+        }
+        let mut magic = [0u8; 4];
+        reader
+            .read_exact(&mut magic)
+            .map_err(|_| "Failed to read ptau header")?;
+        if &magic != b"ptau" {
+            return Err("Not a ptau file");
+        }
+        let _version = read_u32(&mut reader)?;
+        let num_sections = read_u32(&mut reader)?;
+
+        let mut power = None;
+        let mut g = None;
+        let mut g2 = None;
+        for _ in 0..num_sections {
+            let section = read_u32(&mut reader)?;
+            let size = read_u64(&mut reader)?;
+            let mut data = (&mut reader).take(size);
+
+            match section {
+                1 => {
+                    if read_u32(&mut data)? != 32 {
+                        return Err("ptau file is not over BN254");
+                    }
+                    let mut modulus = [0u8; 32];
+                    data.read_exact(&mut modulus)
+                        .map_err(|_| "Truncated ptau header section")?;
+                    power = Some(read_u32(&mut data)? as usize);
+                }
+                2 => {
+                    match power {
+                        Some(power) if power >= k => {}
+                        Some(_) => return Err("ptau file has too few powers for k"),
+                        None => return Err("ptau points come before the header section"),
+                    }
+                    g = Some(read_ptau_points::<G1Affine, _>(&mut data, 1 << k, 64)?);
+                }
+                3 => {
+                    g2 = Some(read_ptau_points::<G2Affine, _>(&mut data, 2, 128)?);
+                }
+                _ => {}
+            }
+
+            // Skip whatever is left of the section
+            io::copy(&mut data, &mut io::sink()).map_err(|_| "Failed to read ptau section")?;
+        }
+
+        let g = g.ok_or("ptau file has no tau G1 section")?;
+        let g2 = g2.ok_or("ptau file has no tau G2 section")?;
+        let params = params_from_powers(k as u32, g, g2[0], g2[1])?;
+
+        let domain = EvaluationDomain::new(1, k as u32);
+        let precomputed_y = precompute_y_for(&params, &domain);
+
+        Ok(Halo2Params {
+            k,
+            domain,
+            params,
+            precomputed_y,
+        })
+    }
+
+    fn from_serializable(serializable: SerializableHalo2Params) -> Result<Self, &'static str> {
+        check_k(serializable.k)?;
         let k = serializable.k as usize;
         let domain = EvaluationDomain::new(1, serializable.k);
 
-        let params =
-            ParamsKZG::<Bn256>::read_custom(&mut &serializable.params[..], SerdeFormat::RawBytes)
-                .map_err(|_| "Failed to deserialize ParamsKZG")?;
+        let params = read_params_kzg(&serializable.params)?;
+        if params.k() != serializable.k {
+            return Err("ParamsKZG do not match k");
+        }
 
         if serializable.precomputed_y.len() % 64 != 0 {
             return Err("Invalid length for precomputed_y bytes");
@@ -111,6 +270,9 @@ impl Halo2Params {
             .map(|chunk| G1Affine::from_raw_bytes(chunk.try_into().unwrap()))
             .collect::<Option<Vec<G1Affine>>>()
             .ok_or("Failed to deserialize a G1Affine point in precomputed_y")?;
+        if precomputed_y.len() != 2 << k {
+            return Err("Invalid length for precomputed_y");
+        }
 
         Ok(Halo2Params {
             k,
@@ -121,8 +283,199 @@ impl Halo2Params {
     }
 }
 
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, &'static str> {
+    let mut bytes = [0u8; 4];
+    reader
+        .read_exact(&mut bytes)
+        .map_err(|_| "Truncated ptau file")?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, &'static str> {
+    let mut bytes = [0u8; 8];
+    reader
+        .read_exact(&mut bytes)
+        .map_err(|_| "Truncated ptau file")?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_ptau_points<P: SerdeObject, R: Read>(
+    reader: &mut R,
+    count: usize,
+    size: usize,
+) -> Result<Vec<P>, &'static str> {
+    let mut bytes = vec![0u8; size];
+    (0..count)
+        .map(|_| {
+            reader
+                .read_exact(&mut bytes)
+                .map_err(|_| "Truncated ptau points section")?;
+            P::from_raw_bytes(&bytes).ok_or("Invalid point in ptau file")
+        })
+        .collect()
+}
+
+/// Assemble `ParamsKZG` from the powers `g[i] = tau^i * G1`, `g2` and
+/// `s_g2 = tau * G2`, computing the Lagrange basis with an inverse FFT
+fn params_from_powers(
+    k: u32,
+    g: Vec<G1Affine>,
+    g2: G2Affine,
+    s_g2: G2Affine,
+) -> Result<ParamsKZG<Bn256>, &'static str> {
+    // g and s_g2 must use the same tau
+    if Bn256::pairing(&g[1], &g2) != Bn256::pairing(&g[0], &s_g2) {
+        return Err("ptau G1 and G2 powers do not match");
+    }
+
+    let domain: EvaluationDomain<Fr> = EvaluationDomain::new(1, k);
+    let n = g.len();
+    let n_inv = Fr::from(n as u64).invert().unwrap();
+    let mut g_lagrange: Vec<G1> = g.iter().map(G1::from).collect();
+    best_fft(&mut g_lagrange, domain.get_omega_inv(), k);
+    g_lagrange.iter_mut().for_each(|p| *p *= n_inv);
+    let mut g_lagrange_affine = vec![G1Affine::identity(); n];
+    G1::batch_normalize(&g_lagrange, &mut g_lagrange_affine);
+
+    // Same layout `ParamsKZG::write_custom` produces for `RawBytes`
+    let mut bytes = k.to_le_bytes().to_vec();
+    for p in g.iter().chain(&g_lagrange_affine) {
+        bytes.extend(p.to_raw_bytes());
+    }
+    bytes.extend(g2.to_raw_bytes());
+    bytes.extend(s_g2.to_raw_bytes());
+
+    ParamsKZG::<Bn256>::read_custom(&mut &bytes[..], SerdeFormat::RawBytes)
+        .map_err(|_| "Failed to build ParamsKZG from ptau powers")
+}
+
+/// Compute the FK `y` vector for the given params, so it can be shared
+/// across several `Halo2Params` built with `setup_with_precomputed`
+pub fn precompute_y_for(params: &ParamsKZG<Bn256>, domain: &EvaluationDomain<Fr>) -> Vec<G1Affine> {
+    let size = 1 << domain.k();
+    let powers = &params.g[..size];
+    precompute_y(powers, domain)
+}
+
+/// Length of `Halo2Params::to_bytes` for params of degree `k`, computed
+/// without generating them: the raw `ParamsKZG` (`2^k` monomial and `2^k`
+/// Lagrange G1 points, two G2 points) and `2^(k+1)` points of
+/// `precomputed_y`, at 64 bytes per G1 and 128 per G2 point, plus the bincode
+/// framing.
+pub fn estimate_params_bytes(k: usize) -> usize {
+    let n = 1usize << k;
+    let params = 4 + 2 * n * 64 + 2 * 128;
+    let precomputed_y = 2 * n * 64;
+
+    // u32 k, then both byte vectors behind a u64 length prefix
+    4 + 8 + params + 8 + precomputed_y
+}
+
+/// Parameters for checking evaluator commitment proofs over `num_bits` bits:
+/// the KZG verifier elements `g[0]`, `g2` and `s_g2` and the verifying key of
+/// the bit circuit. Without the powers `g` they cannot compute the openings
+/// an OT receiver needs, see `precompute_y_for`.
+#[derive(Debug, Clone)]
+pub struct Halo2VerifierParams {
+    pub k: usize,
+    pub num_bits: usize,
+    pub g0: G1Affine,
+    pub g2: G2Affine,
+    pub s_g2: G2Affine,
+    pub vk: VerifyingKey<G1Affine>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SerializableHalo2VerifierParams {
+    pub k: u32,
+    pub num_bits: u64,
+    pub g0: Vec<u8>,
+    pub g2: Vec<u8>,
+    pub s_g2: Vec<u8>,
+    pub vk: Vec<u8>,
+}
+
+impl Halo2VerifierParams {
+    /// Verifier params for proofs over `num_bits` bits, the verifying key is
+    /// built from the full `params` once here
+    pub fn new(params: &Halo2Params, num_bits: usize) -> Result<Self, &'static str> {
+        let vk = bitvector_vk(&params.params, num_bits).map_err(|_| "keygen_vk failed")?;
+
+        Ok(Halo2VerifierParams {
+            k: params.k,
+            num_bits,
+            g0: params.params.g[0],
+            g2: params.params.g2,
+            s_g2: params.params.s_g2,
+            vk,
+        })
+    }
+
+    /// The verifier elements as degree 0 `ParamsKZG`, i.e. with `g[0]` as the
+    /// only power, which is all `verify_kzg_commitment_with_vk` reads
+    pub fn kzg_params(&self) -> ParamsKZG<Bn256> {
+        // `RawBytes` layout: k, the powers, their Lagrange form, g2, s_g2.
+        // Over a single point both forms are `g[0]`.
+        let mut bytes = 0u32.to_le_bytes().to_vec();
+        bytes.extend(self.g0.to_raw_bytes());
+        bytes.extend(self.g0.to_raw_bytes());
+        bytes.extend(self.g2.to_raw_bytes());
+        bytes.extend(self.s_g2.to_raw_bytes());
+        ParamsKZG::<Bn256>::read_custom(&mut &bytes[..], SerdeFormat::RawBytes)
+            .expect("points were already checked")
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut vk = Vec::new();
+        self.vk
+            .write(&mut vk, SerdeFormat::RawBytes)
+            .expect("Failed to serialize VerifyingKey");
+        let serializable = SerializableHalo2VerifierParams {
+            k: self.k as u32,
+            num_bits: self.num_bits as u64,
+            g0: self.g0.to_raw_bytes(),
+            g2: self.g2.to_raw_bytes(),
+            s_g2: self.s_g2.to_raw_bytes(),
+            vk,
+        };
+
+        bincode::serialize(&serializable).unwrap_or_default()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        let s: SerializableHalo2VerifierParams =
+            bincode::deserialize(bytes).map_err(|_| "Failed to deserialize Halo2VerifierParams")?;
+        check_k(s.k)?;
+        let num_bits = usize::try_from(s.num_bits).map_err(|_| "num_bits out of range")?;
+        let g0 = point_from_raw_bytes(&s.g0, G1_RAW_BYTES, "Failed to deserialize g0")?;
+        let g2 = point_from_raw_bytes(&s.g2, G2_RAW_BYTES, "Failed to deserialize g2")?;
+        let s_g2 = point_from_raw_bytes(&s.s_g2, G2_RAW_BYTES, "Failed to deserialize s_g2")?;
+        let vk = read_bitvector_vk(&s.vk, s.k).map_err(|_| "Failed to deserialize VerifyingKey")?;
+
+        Ok(Halo2VerifierParams {
+            k: s.k as usize,
+            num_bits,
+            g0,
+            g2,
+            s_g2,
+            vk,
+        })
+    }
+}
+
+/// Read `ParamsKZG` in `RawBytes` format, checking the leading `k` first so
+/// that a corrupt one is rejected instead of sizing the point vectors
+fn read_params_kzg(bytes: &[u8]) -> Result<ParamsKZG<Bn256>, &'static str> {
+    let k = bytes
+        .first_chunk::<4>()
+        .ok_or("Failed to deserialize ParamsKZG")?;
+    check_k(u32::from_le_bytes(*k))?;
+    ParamsKZG::<Bn256>::read_custom(&mut &bytes[..], SerdeFormat::RawBytes)
+        .map_err(|_| "Failed to deserialize ParamsKZG")
+}
+
 /// Minimal parameters needed for LaconicOT protocols
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LaconicParams {
     pub k: u32,
     pub g0: G1Affine,   // Just the first generator point
@@ -138,14 +491,34 @@ pub struct SerializableLaconicParams {
     pub s_g2: Vec<u8>,
 }
 
+// Conversion from ParamsKZG to LaconicParams, the only place the sender
+// params are extracted
+impl From<&ParamsKZG<Bn256>> for LaconicParams {
+    fn from(params: &ParamsKZG<Bn256>) -> Self {
+        LaconicParams {
+            k: params.k(),
+            g0: params.g[0],
+            g2: params.g2,
+            s_g2: params.s_g2,
+        }
+    }
+}
+
 // Conversion from Halo2Params to LaconicParams
 impl From<&Halo2Params> for LaconicParams {
     fn from(params: &Halo2Params) -> Self {
+        LaconicParams::from(&params.params)
+    }
+}
+
+// Conversion from Halo2VerifierParams to LaconicParams
+impl From<&Halo2VerifierParams> for LaconicParams {
+    fn from(params: &Halo2VerifierParams) -> Self {
         LaconicParams {
             k: params.k as u32,
-            g0: params.params.g[0],
-            g2: params.params.g2,
-            s_g2: params.params.s_g2,
+            g0: params.g0,
+            g2: params.g2,
+            s_g2: params.s_g2,
         }
     }
 }
@@ -167,9 +540,10 @@ impl TryFrom<SerializableLaconicParams> for LaconicParams {
     type Error = &'static str;
 
     fn try_from(s: SerializableLaconicParams) -> Result<Self, Self::Error> {
-        let g0 = G1Affine::from_raw_bytes(&s.g0).ok_or("Failed to deserialize g0")?;
-        let g2 = G2Affine::from_raw_bytes(&s.g2).ok_or("Failed to deserialize g2")?;
-        let s_g2 = G2Affine::from_raw_bytes(&s.s_g2).ok_or("Failed to deserialize s_g2")?;
+        check_k(s.k)?;
+        let g0 = point_from_raw_bytes(&s.g0, G1_RAW_BYTES, "Failed to deserialize g0")?;
+        let g2 = point_from_raw_bytes(&s.g2, G2_RAW_BYTES, "Failed to deserialize g2")?;
+        let s_g2 = point_from_raw_bytes(&s.s_g2, G2_RAW_BYTES, "Failed to deserialize s_g2")?;
 
         Ok(LaconicParams {
             k: s.k,
@@ -193,3 +567,214 @@ impl LaconicParams {
         LaconicParams::try_from(serializable)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    /// Minimal `.ptau` file for a known `tau`, laid out like snarkjs writes
+    /// it, with an extra section the reader has to skip
+    fn ptau_fixture(tau: Fr, power: u32) -> Vec<u8> {
+        use halo2curves::bn256::G2;
+        use halo2curves::group::Group;
+
+        let section = |out: &mut Vec<u8>, kind: u32, data: Vec<u8>| {
+            out.extend(kind.to_le_bytes());
+            out.extend((data.len() as u64).to_le_bytes());
+            out.extend(data);
+        };
+        let tau_powers = |count: usize| {
+            std::iter::successors(Some(Fr::one()), move |t| Some(*t * tau)).take(count)
+        };
+
+        let mut header = 32u32.to_le_bytes().to_vec();
+        header.extend([0u8; 32]);
+        header.extend(power.to_le_bytes());
+        header.extend(power.to_le_bytes());
+
+        let n = 1usize << power;
+        let tau_g1 = tau_powers(2 * n - 1)
+            .flat_map(|t| (G1::generator() * t).to_affine().to_raw_bytes())
+            .collect();
+        let tau_g2 = tau_powers(n)
+            .flat_map(|t| (G2::generator() * t).to_affine().to_raw_bytes())
+            .collect();
+
+        let mut out = b"ptau".to_vec();
+        out.extend(1u32.to_le_bytes());
+        out.extend(4u32.to_le_bytes());
+        section(&mut out, 1, header);
+        section(&mut out, 2, tau_g1);
+        section(&mut out, 3, tau_g2);
+        section(&mut out, 4, vec![0xab; 10]);
+        out
+    }
+
+    #[test]
+    fn test_estimate_params_bytes() {
+        let k = 10;
+        let halo2params = Halo2Params::setup(&mut OsRng, k).unwrap();
+        assert_eq!(estimate_params_bytes(k), halo2params.to_bytes().len());
+    }
+
+    #[test]
+    fn test_from_ptau_fixture() {
+        use crate::laconic_ot::{Choice, LaconicOTRecv, LaconicOTSender, MSG_SIZE};
+
+        let tau = Fr::from(7);
+        let bytes = ptau_fixture(tau, 5);
+
+        let params = Halo2Params::from_ptau(&bytes[..], 4).unwrap();
+        assert_eq!(params.k, 4);
+        assert_eq!(params.params.g.len(), 16);
+        assert_eq!(
+            params.params.g[3],
+            (G1Affine::generator() * tau.pow_vartime([3])).to_affine()
+        );
+
+        let bits = [Choice::One, Choice::Zero, Choice::One];
+        let receiver = LaconicOTRecv::new(params.clone(), &bits).unwrap();
+        let sender = LaconicOTSender::new_from(
+            LaconicParams::from(&params),
+            receiver.commitment(),
+            receiver.domain_fingerprint(),
+        );
+        for (i, bit) in bits.iter().enumerate() {
+            let msg = sender
+                .send(&mut OsRng, i, [0u8; MSG_SIZE], [1u8; MSG_SIZE])
+                .unwrap();
+            let expected = if *bit == Choice::One { 1 } else { 0 };
+            assert_eq!(receiver.recv(i, msg).unwrap(), [expected; MSG_SIZE]);
+        }
+
+        // Not enough powers, or not a ptau file at all
+        assert!(Halo2Params::from_ptau(&bytes[..], 6).is_err());
+        assert!(Halo2Params::from_ptau(&bytes[4..], 4).is_err());
+    }
+
+    #[test]
+    fn test_deserializers_reject_malformed_input() {
+        let params = Halo2Params::setup(&mut OsRng, 4).unwrap();
+
+        // Truncated at every length, including inside the points
+        let laconic = LaconicParams::from(&params).to_bytes();
+        for len in 0..laconic.len() {
+            assert!(LaconicParams::from_bytes(&laconic[..len]).is_err());
+        }
+        let verifier = Halo2VerifierParams::new(&params, 8).unwrap().to_bytes();
+        for len in [0, 3, 4, 100, verifier.len() - 1] {
+            assert!(Halo2VerifierParams::from_bytes(&verifier[..len]).is_err());
+        }
+        let full = params.to_bytes();
+        assert!(Halo2Params::from_bytes(&full[..full.len() - 1]).is_err());
+
+        // Whole points, but not as many as the FK step needs
+        let mut short_y: SerializableHalo2Params = bincode::deserialize(&full).unwrap();
+        short_y
+            .precomputed_y
+            .truncate(short_y.precomputed_y.len() - 64);
+        assert_eq!(
+            Halo2Params::from_bytes(&bincode::serialize(&short_y).unwrap()).err(),
+            Some("Invalid length for precomputed_y")
+        );
+
+        // Points of the wrong length
+        let mut short_g2 = SerializableLaconicParams::from(&LaconicParams::from(&params));
+        short_g2.g2.truncate(G2_RAW_BYTES - 1);
+        assert_eq!(
+            LaconicParams::try_from(short_g2),
+            Err("Failed to deserialize g2")
+        );
+
+        // k that would overflow `1 << k` or the evaluation domain
+        for k in [0, Fr::S + 1, u32::MAX] {
+            let mut bad_k = SerializableLaconicParams::from(&LaconicParams::from(&params));
+            bad_k.k = k;
+            assert_eq!(LaconicParams::try_from(bad_k), Err("k out of range"));
+
+            let mut bad_k = verifier.clone();
+            bad_k[..4].copy_from_slice(&k.to_le_bytes());
+            assert!(Halo2VerifierParams::from_bytes(&bad_k).is_err());
+
+            let mut bad_k = full.clone();
+            bad_k[..4].copy_from_slice(&k.to_le_bytes());
+            assert!(Halo2Params::from_bytes(&bad_k).is_err());
+        }
+    }
+
+    #[test]
+    fn test_verifier_params_carry_no_powers() {
+        let small = Halo2Params::setup(&mut OsRng, 4).unwrap();
+        let large = Halo2Params::setup(&mut OsRng, 6).unwrap();
+
+        let verifier = Halo2VerifierParams::new(&small, 8).unwrap();
+        let bytes = verifier.to_bytes();
+        let g1 = small.params.g[1].to_raw_bytes();
+        assert!(!bytes.windows(g1.len()).any(|w| w == g1.as_slice()));
+        assert_eq!(verifier.kzg_params().g.len(), 1);
+
+        // Only the selectors in the verifying key grow with k, one bit per row
+        let large_bytes = Halo2VerifierParams::new(&large, 8).unwrap().to_bytes();
+        assert!(large_bytes.len() < large.to_bytes().len() / 4);
+
+        let decoded = Halo2VerifierParams::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert_eq!(decoded.num_bits, 8);
+    }
+
+    #[test]
+    fn test_laconic_params_from_params_kzg() {
+        let params = Halo2Params::setup(&mut OsRng, 4).unwrap();
+
+        let inline = LaconicParams {
+            k: params.params.k(),
+            g0: params.params.g[0],
+            g2: params.params.g2,
+            s_g2: params.params.s_g2,
+        };
+        assert_eq!(LaconicParams::from(&params.params), inline);
+        assert_eq!(LaconicParams::from(&params), inline);
+    }
+
+    #[test]
+    fn test_halo2_params_file_roundtrip() {
+        let params = Halo2Params::setup(&mut OsRng, 4).unwrap();
+        let bytes = params.to_bytes();
+
+        let path =
+            std::env::temp_dir().join(format!("halo2params_roundtrip_{}.bin", std::process::id()));
+        std::fs::write(&path, &bytes).unwrap();
+
+        let loaded = Halo2Params::from_path(&path);
+        std::fs::remove_file(&path).unwrap();
+        let loaded = loaded.expect("Failed to load Halo2Params from file");
+
+        assert_eq!(loaded.k, params.k);
+        assert_eq!(loaded.precomputed_y, params.precomputed_y);
+        assert_eq!(loaded.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_reused_precomputed_y_matches_fk_openings() {
+        let k = 4;
+        let params: ParamsKZG<Bn256> = ParamsKZG::setup(k, &mut OsRng);
+        let domain = EvaluationDomain::new(1, k);
+        let y = precompute_y_for(&params, &domain);
+
+        let elems = vec![Fr::from(0), Fr::from(1), Fr::from(0), Fr::from(1)];
+
+        // Compute y once, clone it into several params
+        for _ in 0..2 {
+            let halo2params = Halo2Params::setup_with_precomputed(params.clone(), y.clone())
+                .expect("Failed to build Halo2Params");
+            assert_eq!(
+                halo2params.precomputed_y,
+                precompute_y_for(&halo2params.params, &halo2params.domain)
+            );
+            crate::poly_op::compare_fk_vs_kzg(&halo2params, &elems, false).unwrap();
+        }
+
+        assert!(Halo2Params::setup_with_precomputed(params, y[1..].to_vec()).is_err());
+    }
+}
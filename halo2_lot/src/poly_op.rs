@@ -16,6 +16,8 @@ use halo2curves::{
     group::{cofactor::CofactorCurveAffine, Curve, Group},
 };
 
+use serde::{Deserialize, Serialize};
+
 use crate::Halo2Params;
 
 pub fn poly_divide<Fr: Field>(poly: &[Fr], c: Fr, f_c: Fr) -> Vec<Fr> {
@@ -118,6 +120,17 @@ pub fn kzg_open(point: Fr, halo2params: Halo2Params, elems: Vec<Fr>) -> G1 {
         .commit(&engine.msm_backend, &quotient_poly, alpha)
 }
 
+/// Number of points normalized per step when turning the FFT output of
+/// `precompute_y` into affine form.
+const NORMALIZE_CHUNK: usize = 1 << 12;
+
+/// Computes y = DFT(hat_s) over the extended domain of size 2d + 2.
+///
+/// Memory: the projective FFT buffer (2d + 2 points) is the peak. It is
+/// normalized to affine from the back in `NORMALIZE_CHUNK` steps, shrinking
+/// the buffer as the affine output grows, so the two full-size vectors never
+/// coexist. For k = 16 this saves roughly the size of the affine output,
+/// which matters on 32-bit WASM.
 pub fn precompute_y(
     powers: &[G1Affine],
     domain: &halo2_proofs::poly::EvaluationDomain<Fr>,
@@ -148,11 +161,21 @@ pub fn precompute_y(
         domain2.extended_k(),
     );
 
-    // Return normalized y
-    let mut y_affine = vec![G1Affine::identity(); domain2_size];
-    G1::batch_normalize(&hat_s, &mut y_affine);
+    // Normalize chunks off the end of hat_s, releasing each one before the
+    // next, then flip the reversed output back into order
+    let mut y_rev: Vec<G1Affine> = Vec::new();
+    let mut chunk = vec![G1Affine::identity(); NORMALIZE_CHUNK.min(domain2_size)];
+    while !hat_s.is_empty() {
+        let start = hat_s.len().saturating_sub(NORMALIZE_CHUNK);
+        let out = &mut chunk[..hat_s.len() - start];
+        G1::batch_normalize(&hat_s[start..], out);
+        hat_s.truncate(start);
+        hat_s.shrink_to_fit();
+        y_rev.extend(out.iter().rev());
+    }
+    y_rev.reverse();
 
-    y_affine
+    y_rev
 }
 
 /// Fast amortized computation of all KZG openings using the FK technique.
@@ -160,32 +183,41 @@ pub fn precompute_y(
 /// - `y`: precomputed vector y = DFT(hat_s)
 /// - `domain`: the evaluation domain
 /// - `evals`: evaluations of the polynomial over the domain (length = 1 << domain.k())
+/// - `progress`: called with the completed fraction (0.0..=1.0) after each major step
 pub fn all_openings_fk(
     y: &[G1Affine],
     domain: &halo2_proofs::poly::EvaluationDomain<Fr>,
     evals: &[Fr],
+    progress: Option<&dyn Fn(f32)>,
 ) -> Result<Vec<G1>, String> {
+    let report = |fraction: f32| {
+        if let Some(progress) = progress {
+            progress(fraction);
+        }
+    };
+
     let domain_size = 1 << domain.k();
     let d = domain_size - 1;
 
     let domain2 = EvaluationDomain::new(1, domain.k() + 1);
     let domain2_size = 1 << domain2.k();
 
-    assert_eq!(
-        evals.len(),
-        domain_size,
-        "Error: evals length ({}) != domain size ({})",
-        evals.len(),
-        domain_size
-    );
+    if evals.len() != domain_size {
+        return Err(format!(
+            "Error: evals length ({}) != domain size ({})",
+            evals.len(),
+            domain_size
+        ));
+    }
 
-    assert_eq!(
-        y.len(),
-        domain2_size,
-        "Error: precomputed y length ({}) != domain2 size ({})",
-        y.len(),
-        domain2_size
-    );
+    // y comes from serialized params, a corrupted file must not abort
+    if y.len() != domain2_size {
+        return Err(format!(
+            "Error: precomputed y length ({}) != domain2 size ({})",
+            y.len(),
+            domain2_size
+        ));
+    }
 
     // Step 1: Convert evals to coefficients
     let coeff_poly = domain.lagrange_to_coeff(Polynomial {
@@ -193,8 +225,15 @@ pub fn all_openings_fk(
         _marker: PhantomData,
     });
     let coeffs = coeff_poly.values;
-
-    assert_eq!(coeffs.len(), d + 1, "coeffs should be of length d+1");
+    report(0.2);
+
+    if coeffs.len() != d + 1 {
+        return Err(format!(
+            "Error: coeffs length ({}) != d + 1 ({})",
+            coeffs.len(),
+            d + 1
+        ));
+    }
 
     // Step 2: Construct hat_c
     let mut hat_c = vec![Fr::zero(); 2 * d + 2];
@@ -206,6 +245,7 @@ pub fn all_openings_fk(
         domain2.get_extended_omega(),
         domain2.extended_k(),
     );
+    report(0.4);
 
     // Step 3: Component-wise multiplication u = y * hat_c
     let mut u: Vec<G1> = y
@@ -213,6 +253,7 @@ pub fn all_openings_fk(
         .zip(hat_c.iter())
         .map(|(y_elem, v_elem)| *y_elem * v_elem)
         .collect();
+    report(0.6);
 
     // Step 4: Inverse FFT and scaling
     best_fft(
@@ -222,6 +263,7 @@ pub fn all_openings_fk(
     );
     u.iter_mut()
         .for_each(|x| *x *= domain2.get_extended_ifft_divisor());
+    report(0.8);
 
     // Step 5: Normalize and truncate to domain size
     let domain_size = 1 << domain.k();
@@ -237,11 +279,92 @@ pub fn all_openings_fk(
 
     let mut out_affine = vec![G1Affine::identity(); domain_size];
     G1::batch_normalize(&h, &mut out_affine);
+    report(1.0);
     Ok(out_affine.iter().map(|p| G1::from(*p)).collect())
 }
 
-/// Compare FK20 openings with direct KZG openings
-pub fn compare_fk_vs_kzg(halo2params: &Halo2Params, elems: &[Fr]) -> Result<(), String> {
+/// Same as `all_openings_fk`, but accepts fewer than `1 << domain.k()`
+/// evaluations. The vector is zero-padded to the domain size internally and
+/// only the first `evals.len()` openings are returned. `progress` is passed
+/// through unchanged.
+pub fn all_openings_fk_padded(
+    y: &[G1Affine],
+    domain: &halo2_proofs::poly::EvaluationDomain<Fr>,
+    evals: &[Fr],
+    progress: Option<&dyn Fn(f32)>,
+) -> Result<Vec<G1>, String> {
+    let domain_size = 1 << domain.k();
+    if evals.len() > domain_size {
+        return Err(format!(
+            "Error: evals length ({}) exceeds domain size ({})",
+            evals.len(),
+            domain_size
+        ));
+    }
+
+    let mut padded = evals.to_vec();
+    padded.resize(domain_size, Fr::zero());
+
+    let mut openings = all_openings_fk(y, domain, &padded, progress)?;
+    openings.truncate(evals.len());
+    Ok(openings)
+}
+
+/// An evaluation point where the FK20 opening differs from the direct KZG
+/// opening, as reported by `compare_fk_vs_kzg`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FkKzgMismatch {
+    /// Opening point is omega^index
+    pub index: usize,
+    pub kzg: G1Affine,
+    pub fk: G1Affine,
+}
+
+impl std::fmt::Display for FkKzgMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Mismatch at i = {}: \n  kzg = {:?}\n  fk  = {:?}",
+            self.index, self.kzg, self.fk
+        )
+    }
+}
+
+/// Why `compare_fk_vs_kzg` failed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FkKzgError {
+    /// The FK openings could not be computed, e.g. because `precomputed_y`
+    /// does not fit the domain
+    Openings(String),
+    /// The points where the FK and KZG openings differ
+    Mismatches(Vec<FkKzgMismatch>),
+}
+
+impl std::fmt::Display for FkKzgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FkKzgError::Openings(e) => write!(f, "FK openings failed: {}", e),
+            FkKzgError::Mismatches(mismatches) => {
+                write!(
+                    f,
+                    "FK and KZG openings differ at {} points",
+                    mismatches.len()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for FkKzgError {}
+
+/// Compare FK20 openings with direct KZG openings at every point of the
+/// domain, returning all the points where they differ. With `verbose` every
+/// opening is printed as well.
+pub fn compare_fk_vs_kzg(
+    halo2params: &Halo2Params,
+    elems: &[Fr],
+    verbose: bool,
+) -> Result<(), FkKzgError> {
     let domain = &halo2params.domain;
     let domain_size = 1 << domain.k();
 
@@ -252,36 +375,41 @@ pub fn compare_fk_vs_kzg(halo2params: &Halo2Params, elems: &[Fr]) -> Result<(),
     }
 
     // Compute FK openings
-    let fk_openings = all_openings_fk(&halo2params.precomputed_y, domain, &padded)?;
+    let fk_openings = all_openings_fk(&halo2params.precomputed_y, domain, &padded, None)
+        .map_err(FkKzgError::Openings)?;
 
     let omega = domain.get_omega();
 
     // Compare with direct KZG openings
-    for i in 0..domain_size {
-        let z_i = domain.get_omega().pow_vartime([i as u64]);
-
-        let kzg = kzg_open(z_i, halo2params.clone(), elems.to_vec());
-        let fk = fk_openings[i];
-
+    let mut mismatches = Vec::new();
+    for (i, fk) in fk_openings.iter().enumerate() {
         let z = omega.pow_vartime([i as u64]);
 
-        println!("z^{} = {:?}", i, z);
-        println!("KZG = {:?}", kzg);
-        println!("FK  = {:?}", fk);
+        let kzg = G1Affine::from(kzg_open(z, halo2params.clone(), elems.to_vec()));
+        let fk = G1Affine::from(fk);
 
-        let kzg_affine = G1Affine::from(kzg);
-        let fk_affine = G1Affine::from(fk);
+        if verbose {
+            println!("z^{} = {:?}", i, z);
+            println!("KZG = {:?}", kzg);
+            println!("FK  = {:?}", fk);
+        }
 
-        if kzg_affine != fk_affine {
-            println!(
-                "Mismatch at i = {}: \n  kzg = {:?}\n  fk  = {:?}",
-                i, kzg_affine, fk_affine
-            );
-            return Err(format!("Mismatch at i = {}", i));
+        if kzg != fk {
+            let mismatch = FkKzgMismatch { index: i, kzg, fk };
+            if verbose {
+                println!("{}", mismatch);
+            }
+            mismatches.push(mismatch);
         }
     }
 
-    println!("✅ FK and KZG openings match at all points.");
+    if !mismatches.is_empty() {
+        return Err(FkKzgError::Mismatches(mismatches));
+    }
+
+    if verbose {
+        println!("✅ FK and KZG openings match at all points.");
+    }
     Ok(())
 }
 
@@ -310,6 +438,131 @@ mod tests {
             precomputed_y,
         };
 
-        compare_fk_vs_kzg(&halo2params, &elems).unwrap();
+        compare_fk_vs_kzg(&halo2params, &elems, true).unwrap();
+    }
+
+    #[test]
+    fn test_precompute_y_matches_unchunked_normalization() {
+        let k = 12;
+        let size = 1 << k;
+        let d = size - 1;
+        let params: ParamsKZG<Bn256> = ParamsKZG::new(k);
+        let domain = EvaluationDomain::new(1, k);
+        let y = precompute_y(&params.g[..size], &domain);
+
+        // Previous implementation: normalize the whole FFT output at once
+        let domain2 = EvaluationDomain::new(1, k + 1);
+        let mut hat_s = vec![G1::identity(); 2 * d + 2];
+        for (i, p) in params.g[..d].iter().rev().enumerate() {
+            hat_s[i] = (*p).into();
+        }
+        best_fft(
+            &mut hat_s,
+            domain2.get_extended_omega(),
+            domain2.extended_k(),
+        );
+        let mut expected = vec![G1Affine::identity(); 2 * d + 2];
+        G1::batch_normalize(&hat_s, &mut expected);
+
+        assert_eq!(y.len(), 2 * size);
+        assert_eq!(y, expected);
+    }
+
+    #[test]
+    fn test_fk_vs_kzg_reports_corrupted_y() {
+        let k = 3;
+        let size = 1 << k;
+        let params: ParamsKZG<Bn256> = ParamsKZG::new(k);
+        let domain = EvaluationDomain::new(1, k);
+        let precomputed_y = precompute_y(&params.g[..size], &domain);
+        let mut halo2params = Halo2Params {
+            k: k as usize,
+            params,
+            domain,
+            precomputed_y,
+        };
+        let elems = vec![Fr::from(1), Fr::from(0), Fr::from(1)];
+        assert_eq!(compare_fk_vs_kzg(&halo2params, &elems, false), Ok(()));
+
+        // Every y entry feeds every FK opening, so one bad entry shows up at
+        // each point of the domain
+        halo2params.precomputed_y[3] = (halo2params.precomputed_y[3] + G1::generator()).to_affine();
+        let Err(FkKzgError::Mismatches(mismatches)) =
+            compare_fk_vs_kzg(&halo2params, &elems, false)
+        else {
+            panic!("corrupted y should give mismatches");
+        };
+
+        let indices: Vec<usize> = mismatches.iter().map(|m| m.index).collect();
+        assert_eq!(indices, (0..size).collect::<Vec<_>>());
+        for mismatch in &mismatches {
+            assert_ne!(mismatch.kzg, mismatch.fk);
+        }
+        assert!(mismatches[2].to_string().starts_with("Mismatch at i = 2"));
+
+        // A y that does not fit the domain is an error, not a panic
+        halo2params.precomputed_y.pop();
+        assert!(matches!(
+            compare_fk_vs_kzg(&halo2params, &elems, false),
+            Err(FkKzgError::Openings(_))
+        ));
+    }
+
+    #[test]
+    fn test_fk_padded_matches_manual_padding() {
+        let k = 4;
+        let size = 1 << k;
+        let params: ParamsKZG<Bn256> = ParamsKZG::new(k);
+        let domain = EvaluationDomain::new(1, k);
+        let precomputed_y = precompute_y(&params.g[..size], &domain);
+
+        let elems = vec![Fr::from(1), Fr::from(0), Fr::from(1)];
+
+        let mut padded = elems.clone();
+        padded.resize(size, Fr::zero());
+        let manual = all_openings_fk(&precomputed_y, &domain, &padded, None).unwrap();
+
+        let wrapped = all_openings_fk_padded(&precomputed_y, &domain, &elems, None).unwrap();
+
+        assert_eq!(wrapped.len(), elems.len());
+        assert_eq!(wrapped[..], manual[..elems.len()]);
+
+        let too_long = vec![Fr::zero(); size + 1];
+        assert!(all_openings_fk_padded(&precomputed_y, &domain, &too_long, None).is_err());
+    }
+
+    #[test]
+    fn test_fk_reports_progress() {
+        let k = 3;
+        let size = 1 << k;
+        let params: ParamsKZG<Bn256> = ParamsKZG::new(k);
+        let domain = EvaluationDomain::new(1, k);
+        let precomputed_y = precompute_y(&params.g[..size], &domain);
+        let elems = vec![Fr::from(1); size];
+
+        let seen = std::cell::RefCell::new(Vec::new());
+        let record = |fraction: f32| seen.borrow_mut().push(fraction);
+        all_openings_fk(&precomputed_y, &domain, &elems, Some(&record)).unwrap();
+
+        let seen = seen.into_inner();
+        assert_eq!(seen, vec![0.2, 0.4, 0.6, 0.8, 1.0]);
+    }
+
+    #[test]
+    fn test_fk_rejects_mismatched_lengths() {
+        let k = 3;
+        let size = 1 << k;
+        let params: ParamsKZG<Bn256> = ParamsKZG::new(k);
+        let domain = EvaluationDomain::new(1, k);
+        let precomputed_y = precompute_y(&params.g[..size], &domain);
+        let elems = vec![Fr::from(1); size];
+
+        let short_evals = &elems[..size - 1];
+        let err = all_openings_fk(&precomputed_y, &domain, short_evals, None).unwrap_err();
+        assert!(err.contains("evals length"), "{}", err);
+
+        let short_y = &precomputed_y[..precomputed_y.len() - 1];
+        let err = all_openings_fk(short_y, &domain, &elems, None).unwrap_err();
+        assert!(err.contains("precomputed y length"), "{}", err);
     }
 }
@@ -0,0 +1,347 @@
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, PrimeField};
+use ark_poly::univariate::{DenseOrSparsePolynomial, DensePolynomial};
+use ark_poly::{DenseUVPolynomial, EvaluationDomain, Polynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{One, Zero};
+use std::ops::Mul;
+
+use crate::kzg_types::{CommitmentKey, Opening};
+use crate::kzg_utils::{plain_kzg_com, plain_kzg_verify};
+use crate::laconic_ot::Com;
+
+/// Proof that the first `num_bits` committed values are 0 or 1, the Plain
+/// counterpart of the Halo2 bitvector proof.
+///
+/// With `f` the committed polynomial over the domain `H` and `S` its first
+/// `num_bits` points, the prover commits to
+/// - `r = f^2 mod Z_H`, so `r(x) = f(x)^2` on all of `H`,
+/// - `t = (f^2 - r) / Z_H`,
+/// - `s = (r - f) / Z_S`, which only exists if `r = f` on `S`,
+///
+/// all of degree below the domain size. The verifier checks both identities
+/// at a Fiat-Shamir challenge `z`, from one batched KZG opening of `f`, `r`,
+/// `t` and `s`. Together they give `f(x)^2 = f(x)` on `S`. Padding positions
+/// past `num_bits` are unconstrained.
+///
+/// The proof reveals the four evaluations at `z`, so unlike the Halo2 proof
+/// it is not zero-knowledge. It is only as hiding as the random padding
+/// makes `f(z)`.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BitProof<E: Pairing> {
+    r_com: E::G1Affine,
+    t_com: E::G1Affine,
+    s_com: E::G1Affine,
+    f_eval: E::ScalarField,
+    r_eval: E::ScalarField,
+    t_eval: E::ScalarField,
+    s_eval: E::ScalarField,
+    /// KZG opening of `f + g r + g^2 t + g^3 s` at `z`, `g` a second challenge
+    opening: E::G1Affine,
+}
+
+impl<E: Pairing> BitProof<E> {
+    /// Prove that `elems[..num_bits]` are bits. `elems` is the full padded
+    /// vector the commitment was made to, one value per domain point.
+    ///
+    /// A proof is produced either way, it only verifies if the values are bits.
+    pub fn prove<D: EvaluationDomain<E::ScalarField>>(
+        ck: &CommitmentKey<E, D>,
+        elems: &[E::ScalarField],
+        num_bits: usize,
+    ) -> Self {
+        let n = ck.domain.size();
+        assert_eq!(elems.len(), n, "elems must cover the whole domain");
+        assert!(num_bits <= n, "more bits than domain points");
+
+        let f = DensePolynomial::from_coefficients_vec(ck.domain.ifft(elems));
+
+        // f^2 has degree below 2n, reducing it mod Z_H = X^n - 1 folds the top
+        // half onto the bottom one
+        let mut r = (&f * &f).coeffs;
+        let t = if r.len() > n {
+            r.split_off(n)
+        } else {
+            Vec::new()
+        };
+        for (i, c) in t.iter().enumerate() {
+            r[i] += c;
+        }
+        let r = DensePolynomial::from_coefficients_vec(r);
+        let t = DensePolynomial::from_coefficients_vec(t);
+
+        // Any remainder means some value is not a bit, and the proof will fail
+        let diff = &r - &f;
+        let z_s = vanishing_on_prefix(&ck.domain, num_bits);
+        let (s, _) = DenseOrSparsePolynomial::from(&diff)
+            .divide_with_q_and_r(&DenseOrSparsePolynomial::from(&z_s))
+            .expect("Z_S is never zero");
+
+        let com = plain_kzg_com(ck, elems);
+        let r_com = commit_coeffs(ck, &r);
+        let t_com = commit_coeffs(ck, &t);
+        let s_com = commit_coeffs(ck, &s);
+        let mut transcript = Transcript::new(&[com, r_com, t_com, s_com], num_bits, n);
+        let z = transcript.challenge::<E::ScalarField>();
+        let evals = [
+            f.evaluate(&z),
+            r.evaluate(&z),
+            t.evaluate(&z),
+            s.evaluate(&z),
+        ];
+        transcript.absorb(&z, &evals);
+        let g = transcript.challenge::<E::ScalarField>();
+
+        let polys = [&f, &r, &t, &s];
+        let mut batched = vec![E::ScalarField::zero(); n];
+        let mut weight = E::ScalarField::one();
+        for poly in polys {
+            for (b, c) in batched.iter_mut().zip(&poly.coeffs) {
+                *b += *c * weight;
+            }
+            weight *= g;
+        }
+        let witness = DensePolynomial::from_coefficients_vec(divide_by_linear(&batched, z));
+
+        let [f_eval, r_eval, t_eval, s_eval] = evals;
+        BitProof {
+            r_com,
+            t_com,
+            s_com,
+            f_eval,
+            r_eval,
+            t_eval,
+            s_eval,
+            opening: commit_coeffs(ck, &witness),
+        }
+    }
+
+    /// Check that the first `num_bits` values committed in `com` are bits.
+    /// `num_bits` is what the verifier expects, e.g. the evaluator input size
+    /// of the circuit, not something taken from the prover.
+    pub fn verify<D: EvaluationDomain<E::ScalarField>>(
+        &self,
+        ck: &CommitmentKey<E, D>,
+        com: &Com<E>,
+        num_bits: usize,
+    ) -> bool {
+        let n = ck.domain.size();
+        if num_bits > n {
+            return false;
+        }
+        let com = com.into_affine();
+        let mut transcript =
+            Transcript::new(&[com, self.r_com, self.t_com, self.s_com], num_bits, n);
+        let z = transcript.challenge::<E::ScalarField>();
+        transcript.absorb(&z, &[self.f_eval, self.r_eval, self.t_eval, self.s_eval]);
+        let g = transcript.challenge::<E::ScalarField>();
+
+        // f^2 = r + Z_H t and r - f = Z_S s at the challenge
+        let z_h = ck.domain.evaluate_vanishing_polynomial(z);
+        let z_s = vanishing_on_prefix_at(&ck.domain, num_bits, z);
+        if self.f_eval.square() != self.r_eval + z_h * self.t_eval
+            || self.r_eval - self.f_eval != z_s * self.s_eval
+        {
+            return false;
+        }
+
+        // The evaluations are those of the committed polynomials
+        let g2 = g.square();
+        let g3 = g2 * g;
+        let batched_com =
+            com.into_group() + self.r_com.mul(g) + self.t_com.mul(g2) + self.s_com.mul(g3);
+        let batched_eval = self.f_eval + g * self.r_eval + g2 * self.t_eval + g3 * self.s_eval;
+        plain_kzg_verify(
+            ck,
+            &batched_com.into_affine(),
+            z,
+            batched_eval,
+            &Opening { v: self.opening },
+        )
+    }
+}
+
+/// Commit to a polynomial of degree below the domain size in coefficient form
+fn commit_coeffs<E: Pairing, D: EvaluationDomain<E::ScalarField>>(
+    ck: &CommitmentKey<E, D>,
+    poly: &DensePolynomial<E::ScalarField>,
+) -> E::G1Affine {
+    assert!(poly.coeffs.len() <= ck.u.len());
+    <E::G1 as VariableBaseMSM>::msm(&ck.u[..poly.coeffs.len()], &poly.coeffs)
+        .unwrap()
+        .into_affine()
+}
+
+/// `prod_{i < m} (X - w^i)` over the first `m` domain points
+fn vanishing_on_prefix<F: PrimeField, D: EvaluationDomain<F>>(
+    domain: &D,
+    m: usize,
+) -> DensePolynomial<F> {
+    let mut coeffs = vec![F::one()];
+    for i in 0..m {
+        // multiply by X, then subtract root times the unshifted coefficients
+        let root = domain.element(i);
+        coeffs.insert(0, F::zero());
+        for j in 0..coeffs.len() - 1 {
+            let next = coeffs[j + 1];
+            coeffs[j] -= root * next;
+        }
+    }
+    DensePolynomial::from_coefficients_vec(coeffs)
+}
+
+/// `Z_S(z)` for the first `m` domain points, without building `Z_S`
+fn vanishing_on_prefix_at<F: PrimeField, D: EvaluationDomain<F>>(domain: &D, m: usize, z: F) -> F {
+    (0..m).fold(F::one(), |acc, i| acc * (z - domain.element(i)))
+}
+
+/// Quotient of `p(X) / (X - z)`, the remainder `p(z)` is dropped
+fn divide_by_linear<F: Field>(coeffs: &[F], z: F) -> Vec<F> {
+    let mut quotient = vec![F::zero(); coeffs.len().saturating_sub(1)];
+    let mut acc = F::zero();
+    for i in (1..coeffs.len()).rev() {
+        acc = coeffs[i] + acc * z;
+        quotient[i - 1] = acc;
+    }
+    quotient
+}
+
+/// Fiat-Shamir transcript. The evaluation point is bound to the commitment,
+/// the proof commitments and the claimed sizes. The batching weight is drawn
+/// after absorbing the point and the claimed evaluations, so the prover
+/// cannot pick evaluations that fit a weight it already knows.
+struct Transcript {
+    hsh: blake3::Hasher,
+}
+
+impl Transcript {
+    fn new<G: CanonicalSerialize>(points: &[G], num_bits: usize, n: usize) -> Self {
+        let mut hsh = blake3::Hasher::new();
+        hsh.update(b"laconic-ot bit proof");
+        for point in points {
+            point.serialize_compressed(&mut hsh).unwrap();
+        }
+        hsh.update(&(num_bits as u64).to_le_bytes());
+        hsh.update(&(n as u64).to_le_bytes());
+        Self { hsh }
+    }
+
+    fn absorb<F: CanonicalSerialize>(&mut self, z: &F, evals: &[F]) {
+        z.serialize_compressed(&mut self.hsh).unwrap();
+        for eval in evals {
+            eval.serialize_compressed(&mut self.hsh).unwrap();
+        }
+    }
+
+    /// Next challenge. Each one is also absorbed, so consecutive challenges
+    /// differ even with nothing absorbed in between.
+    fn challenge<F: PrimeField>(&mut self) -> F {
+        // 64 bytes keep the reduction mod the field order unbiased
+        let mut wide = [0u8; 64];
+        self.hsh.finalize_xof().fill(&mut wide);
+        self.hsh.update(&wide);
+        F::from_le_bytes_mod_order(&wide)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::Radix2EvaluationDomain;
+    use ark_std::{test_rng, UniformRand};
+
+    type Ck = CommitmentKey<Bls12_381, Radix2EvaluationDomain<Fr>>;
+
+    fn padded(values: &[u64], n: usize) -> Vec<Fr> {
+        let rng = &mut test_rng();
+        let mut elems: Vec<Fr> = values.iter().map(|&v| Fr::from(v)).collect();
+        elems.resize_with(n, || Fr::rand(rng));
+        elems
+    }
+
+    #[test]
+    fn test_bit_proof_accepts_bits() {
+        let ck = Ck::setup(&mut test_rng(), 8).unwrap();
+
+        // Full domain, and a prefix followed by random padding
+        for values in [&[1, 0, 0, 1, 1, 1, 0, 1][..], &[0, 1, 1][..]] {
+            let elems = padded(values, 8);
+            let com = plain_kzg_com(&ck, &elems).into_group();
+
+            let proof = BitProof::prove(&ck, &elems, values.len());
+            assert!(proof.verify(&ck, &com, values.len()));
+
+            let mut bytes = Vec::new();
+            proof.serialize_compressed(&mut bytes).unwrap();
+            let restored = BitProof::<Bls12_381>::deserialize_compressed(&bytes[..]).unwrap();
+            assert!(restored.verify(&ck, &com, values.len()));
+
+            // Claimed for another commitment or a longer prefix
+            let mut flipped = elems.clone();
+            flipped[0] = Fr::one() - flipped[0];
+            let other = plain_kzg_com(&ck, &flipped).into_group();
+            assert!(!proof.verify(&ck, &other, values.len()));
+            if values.len() < 8 {
+                assert!(!proof.verify(&ck, &com, values.len() + 1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bit_proof_rejects_forged_evaluations() {
+        let ck = Ck::setup(&mut test_rng(), 8).unwrap();
+        let elems = padded(&[1, 0, 2, 1], 8);
+        let com = plain_kzg_com(&ck, &elems);
+        let honest = BitProof::prove(&ck, &elems, 4);
+
+        // Challenges of the honest proof, which a forger knows
+        let mut transcript =
+            Transcript::new(&[com, honest.r_com, honest.t_com, honest.s_com], 4, 8);
+        let z: Fr = transcript.challenge();
+        let honest_evals = [honest.f_eval, honest.r_eval, honest.t_eval, honest.s_eval];
+        transcript.absorb(&z, &honest_evals);
+        let g: Fr = transcript.challenge();
+        let weighted = |e: [Fr; 4]| e[0] + g * e[1] + g.square() * e[2] + g.square() * g * e[3];
+        let target = weighted(honest_evals);
+
+        // Pick f(z), then solve both identities and the batched value for
+        // r(z), t(z), s(z), keeping the honest opening
+        let z_h = ck.domain.evaluate_vanishing_polynomial(z);
+        let z_s = vanishing_on_prefix_at(&ck.domain, 4, z);
+        let f = Fr::from(5u64);
+        let g3 = g.square() * g;
+        let fixed = f + g * f.square() + g3 * (f.square() - f) / z_s;
+        let t = (target - fixed) / (g.square() - g * z_h - g3 * z_h / z_s);
+        let r = f.square() - z_h * t;
+        let s = (r - f) / z_s;
+        assert_eq!(f.square(), r + z_h * t);
+        assert_eq!(r - f, z_s * s);
+        assert_eq!(weighted([f, r, t, s]), target);
+
+        let forged = BitProof {
+            f_eval: f,
+            r_eval: r,
+            t_eval: t,
+            s_eval: s,
+            ..honest
+        };
+        assert!(!forged.verify(&ck, &com.into_group(), 4));
+    }
+
+    #[test]
+    fn test_bit_proof_rejects_non_bits() {
+        let ck = Ck::setup(&mut test_rng(), 8).unwrap();
+
+        // A 2 among the bits, as `new_from_field` would allow
+        let elems = padded(&[1, 0, 2, 1], 8);
+        let com = plain_kzg_com(&ck, &elems).into_group();
+        let proof = BitProof::prove(&ck, &elems, 4);
+        assert!(!proof.verify(&ck, &com, 4));
+
+        // Only the prefix is constrained
+        let proof = BitProof::prove(&ck, &elems, 2);
+        assert!(proof.verify(&ck, &com, 2));
+    }
+}
@@ -4,6 +4,7 @@ use ark_std::Zero;
 use std::ops::Mul;
 
 use crate::kzg_types::{CommitmentKey, State};
+use crate::kzg_utils::{plain_kzg_com, witness_evals_inside};
 
 // this module allows to compute all openings in a
 // fast amortized way following the FK technique:
@@ -52,6 +53,35 @@ pub fn all_openings_single<E: Pairing, D: EvaluationDomain<E::ScalarField>>(
     h
 }
 
+/// Cross-check `all_openings_single` against openings computed one point at
+/// a time: the witness polynomial (f - f(w_i)) / (X - w_i) in evaluation form,
+/// committed with `plain_kzg_com`. Returns the first mismatching index.
+pub fn compare_fk_vs_single<E: Pairing, D: EvaluationDomain<E::ScalarField>>(
+    ck: &CommitmentKey<E, D>,
+    evals: &[E::ScalarField],
+) -> Result<(), String> {
+    if evals.len() != ck.domain.size() {
+        return Err(format!(
+            "Error: evals length ({}) != domain size ({})",
+            evals.len(),
+            ck.domain.size()
+        ));
+    }
+
+    let fk = all_openings_single::<E, D>(&ck.y, &ck.domain, evals);
+
+    for i in 0..ck.domain.size() {
+        let mut witn_evals = Vec::with_capacity(ck.domain.size());
+        witness_evals_inside::<E, D>(&ck.domain, evals, i, &mut witn_evals);
+        let single = plain_kzg_com(ck, &witn_evals);
+
+        if single != fk[i].into_affine() {
+            return Err(format!("Mismatch at i = {}", i));
+        }
+    }
+    Ok(())
+}
+
 /// compute the polynomial h (in exponent) from the paper (see Proposition 1)
 /// The polynomial f is given by domain.size() many coefficients, and we have
 /// powers[i] = g1^{alpha^i}
@@ -119,7 +149,7 @@ mod tests {
 
     use crate::kzg_types::VcKZG;
 
-    use super::{all_openings_single, base_poly};
+    use super::{all_openings_single, base_poly, compare_fk_vs_single};
 
     type F = <Bls12_381 as Pairing>::ScalarField;
     type D = Radix2EvaluationDomain<F>;
@@ -231,4 +261,26 @@ mod tests {
             }
         }
     }
+
+    /// test function compare_fk_vs_single on random bitvectors
+    #[test]
+    fn test_compare_fk_vs_single() {
+        use rand::Rng;
+
+        let mut rng = ark_std::rand::thread_rng();
+        let degree = 15;
+        let runs = 3;
+
+        let ck = VcKZG::<Bls12_381, D>::setup(&mut rng, degree - 1).unwrap();
+
+        for _ in 0..runs {
+            let bits: Vec<F> = (0..ck.domain.size())
+                .map(|_| F::from(rng.gen::<bool>() as u64))
+                .collect();
+            compare_fk_vs_single::<Bls12_381, D>(&ck, &bits).unwrap();
+        }
+
+        // a vector of the wrong length is rejected rather than compared
+        assert!(compare_fk_vs_single::<Bls12_381, D>(&ck, &[F::one()]).is_err());
+    }
 }
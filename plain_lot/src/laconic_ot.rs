@@ -1,44 +1,124 @@
+use crate::bit_proof::BitProof;
 use crate::kzg_utils::plain_kzg_com;
 use crate::{kzg_fk_open::all_openings_single, kzg_types::CommitmentKey};
 
 use ark_ec::pairing::Pairing;
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
 use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
 use ark_serialize::CanonicalDeserialize;
 use ark_serialize::CanonicalSerialize;
 use ark_std::One;
 use ark_std::UniformRand;
 use ark_std::Zero;
-use rand::Rng;
+use rand::{rngs::OsRng, Rng};
 use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
-const MSG_SIZE: usize = 16;
+/// Default message size, matching the 16-byte wire labels used for garbling
+pub const MSG_SIZE: usize = 16;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// Compare two recovered messages in constant time.
+///
+/// Payloads returned by `LaconicOTRecv::recv` are secret (e.g. wire labels),
+/// so protocols built on top should use this instead of `==`.
+pub fn ct_eq<const N: usize>(a: &[u8; N], b: &[u8; N]) -> subtle::Choice {
+    subtle::ConstantTimeEq::ct_eq(&a[..], &b[..])
+}
+
+/// Error returned by `LaconicOTRecv::recv`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvError {
+    /// The message index is past the end of the committed bits
+    IndexOutOfRange { index: usize, len: usize },
+    /// The committed selection at `index` has no message in a message with
+    /// `arity` branches, e.g. a `new_n` selection past 1 given to `recv`
+    SelectionOutOfRange {
+        index: usize,
+        selection: usize,
+        arity: usize,
+    },
+}
+
+impl std::fmt::Display for RecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecvError::IndexOutOfRange { index, len } => {
+                write!(
+                    f,
+                    "OT index {} out of range for {} committed bits",
+                    index, len
+                )
+            }
+            RecvError::SelectionOutOfRange {
+                index,
+                selection,
+                arity,
+            } => write!(
+                f,
+                "Selection {} at OT index {} out of range for {} messages",
+                selection, index, arity
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+/// Error returned by the `LaconicOTSender` send methods
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendError {
+    /// The receiver committed over another evaluation domain, so it could
+    /// not decrypt anything sent on ours
+    DomainMismatch,
+}
+
+impl std::fmt::Display for SendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendError::DomainMismatch => write!(
+                f,
+                "Receiver committed over a different evaluation domain than the sender's"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SendError {}
+
+/// Identifies an evaluation domain by its size and generator. The sender
+/// evaluates at `domain.element(i)`, so both sides must have the same one.
+/// Hashes the size as a little-endian `u64`, then the canonical
+/// little-endian encoding of the generator, as the halo2 backend does.
+pub fn domain_fingerprint<F: PrimeField, D: EvaluationDomain<F>>(domain: &D) -> [u8; 32] {
+    let mut hsh = blake3::Hasher::new();
+    hsh.update(&(domain.size() as u64).to_le_bytes());
+    domain
+        .group_gen()
+        .serialize_compressed(&mut hsh)
+        .expect("hashing cannot fail");
+    *hsh.finalize().as_bytes()
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
 pub enum Choice {
+    #[default]
     Zero,
     One,
 }
 
-impl<E: Pairing> Msg<E> {
+impl zeroize::DefaultIsZeroes for Choice {}
+
+impl<E: Pairing, const N: usize> Msg<E, N> {
+    /// JSON of the `SerializableMsg` form, so both encodings agree
     pub fn serialize(&self) -> Vec<u8> {
-        let serializable = SerializableMsg {
-            h: self.h.map(|(g2, msg)| {
-                let mut g2_bytes = Vec::new();
-                g2.serialize_compressed(&mut g2_bytes).unwrap();
-                (g2_bytes, msg)
-            }),
-        };
-        serde_json::to_vec(&serializable).unwrap()
+        serde_json::to_vec(&SerializableMsg::from(*self)).unwrap()
     }
 
     pub fn deserialize(data: &[u8]) -> Self {
         let serializable: SerializableMsg = serde_json::from_slice(data).unwrap();
-        let h = serializable.h.map(|(g2_bytes, msg)| {
-            let g2 = E::G2Affine::deserialize_compressed(&*g2_bytes)
-                .expect("Failed to deserialize G2Affine");
-            (g2, msg)
-        });
-        Self { h }
+        Self::try_from(serializable).expect("Failed to deserialize Msg")
     }
 }
 pub type Com<E: Pairing> = E::G1;
@@ -54,42 +134,59 @@ impl Choice {
     }
 }
 
+/// Laconic OT message carrying two `N`-byte ciphertexts, one per choice bit
 #[derive(Clone, Copy, Debug)]
-pub struct Msg<E: Pairing> {
-    pub h: [(E::G2Affine, [u8; MSG_SIZE]); 2],
+pub struct Msg<E: Pairing, const N: usize = MSG_SIZE> {
+    pub h: [(E::G2Affine, [u8; N]); 2],
 }
 
+/// Laconic OT message carrying `K` ciphertexts, of which the receiver can
+/// decrypt the one matching its committed selection, see
+/// `LaconicOTSender::send_n`
+#[derive(Clone, Copy, Debug)]
+pub struct MsgN<E: Pairing, const K: usize, const N: usize = MSG_SIZE> {
+    pub h: [(E::G2Affine, [u8; N]); K],
+}
+
+/// Serializable form of `Msg`, the ciphertext length is carried by the data.
+/// Points are compressed, the single encoding used for `Msg` everywhere.
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct SerializableMsg {
-    pub h: [(Vec<u8>, [u8; MSG_SIZE]); 2],
+    pub h: [(Vec<u8>, Vec<u8>); 2],
 }
 
-impl<E: Pairing> From<Msg<E>> for SerializableMsg {
-    fn from(msg: Msg<E>) -> Self {
-        let mut buf0 = Vec::new();
-        let mut buf1 = Vec::new();
-        let _ = msg.h[0].0.serialize_uncompressed(&mut buf0);
-        let _ = msg.h[1].0.serialize_uncompressed(&mut buf1);
-
+impl<E: Pairing, const N: usize> From<Msg<E, N>> for SerializableMsg {
+    fn from(msg: Msg<E, N>) -> Self {
         SerializableMsg {
-            h: [(buf0, msg.h[0].1), (buf1, msg.h[1].1)],
+            h: msg.h.map(|(g2, ct)| {
+                let mut g2_bytes = Vec::new();
+                g2.serialize_compressed(&mut g2_bytes)
+                    .expect("Failed to serialize G2Affine");
+                (g2_bytes, ct.to_vec())
+            }),
         }
     }
 }
 
-impl<E: Pairing> TryFrom<SerializableMsg> for Msg<E> {
+impl<E: Pairing, const N: usize> TryFrom<SerializableMsg> for Msg<E, N> {
     type Error = ark_serialize::SerializationError;
 
     fn try_from(s: SerializableMsg) -> Result<Self, Self::Error> {
+        let ct = |bytes: &[u8]| -> Result<[u8; N], Self::Error> {
+            bytes
+                .try_into()
+                .map_err(|_| ark_serialize::SerializationError::InvalidData)
+        };
+
         Ok(Msg {
             h: [
                 (
-                    E::G2Affine::deserialize_uncompressed(&s.h[0].0[..])?,
-                    s.h[0].1,
+                    E::G2Affine::deserialize_compressed(&s.h[0].0[..])?,
+                    ct(&s.h[0].1)?,
                 ),
                 (
-                    E::G2Affine::deserialize_uncompressed(&s.h[1].0[..])?,
-                    s.h[1].1,
+                    E::G2Affine::deserialize_compressed(&s.h[1].0[..])?,
+                    ct(&s.h[1].1)?,
                 ),
             ],
         })
@@ -100,125 +197,614 @@ pub struct LaconicOT<E: Pairing, D: EvaluationDomain<E::ScalarField>> {
     ck: CommitmentKey<E, D>,
 }
 
+/// The receiver owns everything it needs after construction, so it does not
+/// borrow the commitment key and can outlive it.
+///
+/// Fewer committed values than the domain size are padded up to it: every
+/// padding position holds an independent, uniformly random field element
+/// drawn from the constructor's rng (`OsRng` unless one is injected, or the
+/// seed for `new_with_seed`). A short input therefore commits like a full
+/// one, without the structure an all-zero tail would have. Padding positions
+/// are never opened: `recv` and friends reject them like any index past the
+/// committed values, and `padding_len` reports how many there are.
 #[derive(Debug, Clone)]
-pub struct LaconicOTRecv<'a, E: Pairing, D: EvaluationDomain<E::ScalarField>> {
-    ck: &'a CommitmentKey<E, D>,
+pub struct LaconicOTRecv<E: Pairing, D: EvaluationDomain<E::ScalarField>> {
     qs: Vec<E::G1>,
     com: E::G1,
     bits: Vec<Choice>,
+    /// Committed value per position, used by `recv_n`. Equal to the bits
+    /// unless built with `new_n`.
+    selections: Vec<usize>,
+    /// `domain_fingerprint` of the commitment key's domain
+    domain: [u8; 32],
+    /// Mixed into every keystream, see `with_session_id`
+    session: [u8; 32],
+    /// Proof that the committed values are bits, from `new_with_bit_proof`
+    bit_proof: Option<BitProof<E>>,
+    _d: PhantomData<D>,
+}
+
+/// A receiver whose commitment is computed but whose openings are not, from
+/// `LaconicOTRecv::commitment_only`. The commitment can be published right
+/// away; `prepare_openings` runs the expensive FK step later.
+///
+/// Holds the padded committed vector, which includes the choice bits. Both
+/// are wiped when dropped.
+#[derive(Debug, Clone)]
+pub struct PendingRecv<E: Pairing, D: EvaluationDomain<E::ScalarField>> {
+    elems: Zeroizing<Vec<E::ScalarField>>,
+    com: E::G1,
+    bits: Zeroizing<Vec<Choice>>,
+    domain: [u8; 32],
+    session: [u8; 32],
+    _d: PhantomData<D>,
 }
 
 pub struct LaconicOTSender<'a, E: Pairing, D: EvaluationDomain<E::ScalarField>> {
     ck: &'a CommitmentKey<E, D>,
     com: E::G1,
+    /// `domain_fingerprint` of the receiver, checked before every send
+    receiver_domain: [u8; 32],
+    /// Mixed into every keystream, see `with_session_id`
+    session: [u8; 32],
 }
 
-impl<'a, E: Pairing, D: EvaluationDomain<E::ScalarField>> LaconicOTRecv<'a, E, D> {
-    pub fn new(ck: &'a CommitmentKey<E, D>, bits: &[Choice]) -> Self {
-        let mut elems: Vec<_> = bits
+impl<E: Pairing, D: EvaluationDomain<E::ScalarField>> LaconicOTRecv<E, D> {
+    pub fn new(ck: &CommitmentKey<E, D>, bits: &[Choice]) -> Self {
+        Self::new_with_rng(&mut OsRng, ck, bits)
+    }
+
+    /// Same as `new`, but draws the padding elements from the given rng
+    pub fn new_with_rng<R: Rng>(rng: &mut R, ck: &CommitmentKey<E, D>, bits: &[Choice]) -> Self {
+        Self::commit_elems(rng, ck, bit_elems::<E>(bits), bits)
+    }
+
+    /// Same as `new`, but derives padding element `j` from
+    /// `blake3(seed || j)`. The commitment is reproducible for a given seed,
+    /// which is only as unpredictable as the seed itself, so callers must
+    /// keep it secret and never reuse it across receivers.
+    pub fn new_with_seed(ck: &CommitmentKey<E, D>, bits: &[Choice], seed: [u8; 32]) -> Self {
+        let mut elems = bit_elems::<E>(bits);
+        assert!(elems.len() <= ck.domain.size());
+        elems.extend((elems.len()..ck.domain.size()).map(|j| {
+            let mut hsh = blake3::Hasher::new();
+            hsh.update(&seed);
+            hsh.update(&(j as u64).to_le_bytes());
+
+            // 64 bytes keep the reduction mod the field order unbiased
+            let mut wide = [0u8; 64];
+            hsh.finalize_xof().fill(&mut wide);
+            E::ScalarField::from_le_bytes_mod_order(&wide)
+        }));
+
+        // Already padded to the domain size, the rng is never drawn from
+        Self::commit_elems(&mut OsRng, ck, elems, bits)
+    }
+
+    /// Commit to arbitrary field elements instead of bits. `bits[i]` still
+    /// selects which branch of the `i`-th message is decrypted, and that
+    /// branch only opens if the sender encrypted it under `elems[i]`, see
+    /// `LaconicOTSender::send_with_values`.
+    ///
+    /// Security caveat: the commitment is no longer guaranteed to hide a
+    /// binary vector. Protocols that rely on the receiver learning at most
+    /// one of two messages per position must not accept such commitments
+    /// from untrusted receivers.
+    pub fn new_from_field(
+        ck: &CommitmentKey<E, D>,
+        elems: &[E::ScalarField],
+        bits: &[Choice],
+    ) -> Self {
+        assert_eq!(
+            elems.len(),
+            bits.len(),
+            "elems and bits must have the same length"
+        );
+        Self::commit_elems(&mut OsRng, ck, elems.to_vec(), bits)
+    }
+
+    /// Commit to a small integer per position instead of a bit, for 1-out-of-K
+    /// transfers with `LaconicOTSender::send_n` and `recv_n`.
+    ///
+    /// There is no extra setup: the same commitment key and a single
+    /// commitment serve any `K`. The sender pays `K` pairings and `K` G2
+    /// multiplications per position instead of two, and messages carry `K`
+    /// G2 points.
+    ///
+    /// Positions holding a selection past 1 have no branch in a two-message
+    /// transfer, so `recv` rejects them with `SelectionOutOfRange`.
+    pub fn new_n(ck: &CommitmentKey<E, D>, selections: &[usize]) -> Self {
+        let elems: Vec<E::ScalarField> = selections
             .iter()
-            .map(|b| {
-                if *b == Choice::One {
-                    E::ScalarField::one()
-                } else {
-                    E::ScalarField::zero()
-                }
-            })
+            .map(|s| E::ScalarField::from(*s as u64))
             .collect();
+        let bits: Vec<Choice> = selections
+            .iter()
+            .map(|s| if *s == 1 { Choice::One } else { Choice::Zero })
+            .collect();
+        let mut recv = Self::commit_elems(&mut OsRng, ck, elems, &bits);
+        recv.selections.zeroize();
+        recv.selections.extend_from_slice(selections);
+        recv
+    }
 
+    /// Like `new`, also proving that the committed values are bits, see
+    /// `bit_proof`. Plain commitments carry no such guarantee otherwise.
+    pub fn new_with_bit_proof(ck: &CommitmentKey<E, D>, bits: &[Choice]) -> Self {
+        Self::commitment_only(ck, bits).prepare_openings_with_bit_proof(ck)
+    }
+
+    /// Compute only the commitment to `bits`, padded like `new`, and defer
+    /// the openings to `PendingRecv::prepare_openings`
+    pub fn commitment_only(ck: &CommitmentKey<E, D>, bits: &[Choice]) -> PendingRecv<E, D> {
+        Self::commit_only_elems(&mut OsRng, ck, bit_elems::<E>(bits), bits)
+    }
+
+    fn commit_elems<R: Rng>(
+        rng: &mut R,
+        ck: &CommitmentKey<E, D>,
+        elems: Vec<E::ScalarField>,
+        bits: &[Choice],
+    ) -> Self {
+        Self::commit_only_elems(rng, ck, elems, bits).prepare_openings(ck)
+    }
+
+    fn commit_only_elems<R: Rng>(
+        rng: &mut R,
+        ck: &CommitmentKey<E, D>,
+        mut elems: Vec<E::ScalarField>,
+        bits: &[Choice],
+    ) -> PendingRecv<E, D> {
         // pad with random elements
         assert!(elems.len() <= ck.domain.size());
-        elems.resize_with(ck.domain.size(), || {
-            E::ScalarField::rand(&mut ark_std::test_rng())
-        });
+        elems.resize_with(ck.domain.size(), || E::ScalarField::rand(rng));
 
         // compute commitment
         let com = plain_kzg_com(ck, &elems);
 
-        // compute all openings
-        let qs = all_openings_single::<E, D>(&ck.y, &ck.domain, &elems);
-
-        Self {
-            ck,
-            qs,
+        PendingRecv {
+            elems: Zeroizing::new(elems),
             com: com.into(),
-            bits: bits.to_vec(),
+            bits: Zeroizing::new(bits.to_vec()),
+            domain: domain_fingerprint(&ck.domain),
+            session: [0; 32],
+            _d: PhantomData,
         }
     }
 
-    pub fn recv(&self, i: usize, msg: Msg<E>) -> [u8; MSG_SIZE] {
-        let j: usize = if self.bits[i] == Choice::One { 1 } else { 0 };
+    /// Decrypt the `i`-th message. Compare results with [`ct_eq`].
+    ///
+    /// `i` comes from the sender's message stream, so it is checked against
+    /// the committed bits instead of panicking.
+    pub fn recv<const N: usize>(&self, i: usize, msg: Msg<E, N>) -> Result<[u8; N], RecvError> {
+        let j = usize::from(self.selected_branch(i)? == Choice::One);
         let h = msg.h[j].0;
         let c = msg.h[j].1;
         let m = E::pairing(self.qs[i], h);
-        decrypt::<E, MSG_SIZE>(m.0, &c)
+        Ok(decrypt::<E, N>(m.0, &self.session, i, j as u8, &c))
+    }
+
+    /// `len` bytes of keystream for position `i` of `msg`, on the committed
+    /// branch. `recv` XORs the first `N` of them into the ciphertext, callers
+    /// packing larger payloads can draw as many as they need from the same
+    /// pad.
+    pub fn keystream<const N: usize>(
+        &self,
+        i: usize,
+        msg: &Msg<E, N>,
+        len: usize,
+    ) -> Result<Vec<u8>, RecvError> {
+        let j = usize::from(self.selected_branch(i)? == Choice::One);
+        let m = E::pairing(self.qs[i], msg.h[j].0);
+        let mut stream = vec![0u8; len];
+        pad_hasher::<E>(m.0, &self.session, i, j as u8)
+            .finalize_xof()
+            .fill(&mut stream);
+        Ok(stream)
+    }
+
+    /// Decrypt many positions at once, in the order of `items`.
+    ///
+    /// Every output depends on its own pairing, so the Miller loops and final
+    /// exponentiations cannot be shared. The openings are however moved to
+    /// affine form with one batched inversion instead of one per call.
+    pub fn recv_many<const N: usize>(
+        &self,
+        items: &[(usize, Msg<E, N>)],
+    ) -> Result<Vec<[u8; N]>, RecvError> {
+        let mut branches = Vec::with_capacity(items.len());
+        let mut qs = Vec::with_capacity(items.len());
+        for (i, _) in items {
+            let bit = self.selected_branch(*i)?;
+            branches.push(if bit == Choice::One { 1 } else { 0 });
+            qs.push(self.qs[*i]);
+        }
+        let qs_affine = E::G1::normalize_batch(&qs);
+
+        Ok(items
+            .iter()
+            .zip(branches.into_iter().zip(qs_affine))
+            .map(|((i, msg), (j, q_affine))| {
+                let (h, c) = msg.h[j];
+                let m = E::pairing(q_affine, h);
+                decrypt::<E, N>(m.0, &self.session, *i, j as u8, &c)
+            })
+            .collect())
+    }
+
+    /// Decrypt the message at the committed selection of the `i`-th position
+    pub fn recv_n<const K: usize, const N: usize>(
+        &self,
+        i: usize,
+        msg: MsgN<E, K, N>,
+    ) -> Result<[u8; N], RecvError> {
+        let selection = *self.selections.get(i).ok_or(RecvError::IndexOutOfRange {
+            index: i,
+            len: self.selections.len(),
+        })?;
+        let (h, c) = *msg.h.get(selection).ok_or(RecvError::SelectionOutOfRange {
+            index: i,
+            selection,
+            arity: K,
+        })?;
+        let m = E::pairing(self.qs[i], h);
+        Ok(decrypt::<E, N>(m.0, &self.session, i, selection as u8, &c))
     }
 
     pub fn commitment(&self) -> Com<E> {
         self.com
     }
+
+    /// The committed choice at position `i`, i.e. the branch `recv` opens
+    /// there. Meant for debugging a failed transfer, the receiver knows its
+    /// bits anyway.
+    ///
+    /// Errors on a `new_n` selection past 1, which no branch of `recv` opens.
+    pub fn selected_branch(&self, i: usize) -> Result<Choice, RecvError> {
+        let bit = self
+            .bits
+            .get(i)
+            .copied()
+            .ok_or(RecvError::IndexOutOfRange {
+                index: i,
+                len: self.bits.len(),
+            })?;
+        match self.selections.get(i) {
+            Some(&selection) if selection > 1 => Err(RecvError::SelectionOutOfRange {
+                index: i,
+                selection,
+                arity: 2,
+            }),
+            _ => Ok(bit),
+        }
+    }
+
+    /// Proof to send along with the commitment, if built with
+    /// `new_with_bit_proof`. The sender checks it with `BitProof::verify`.
+    pub fn bit_proof(&self) -> Option<&BitProof<E>> {
+        self.bit_proof.as_ref()
+    }
+
+    /// Number of random padding positions after the committed values
+    pub fn padding_len(&self) -> usize {
+        self.qs.len() - self.bits.len()
+    }
+
+    /// Fingerprint of the domain the commitment was made over, to send along
+    /// with the commitment and hand to `LaconicOTSender::new`
+    pub fn domain_fingerprint(&self) -> [u8; 32] {
+        self.domain
+    }
+
+    /// Decrypt under session `id`, which the sender must also use, see
+    /// `LaconicOTSender::with_session_id`. Defaults to all zeros.
+    pub fn with_session_id(mut self, id: [u8; 32]) -> Self {
+        self.session = id;
+        self
+    }
 }
 
-fn encrypt<E: Pairing, const N: usize>(pad: E::TargetField, msg: &[u8; N]) -> [u8; N] {
-    // hash the pad
+impl<E: Pairing, D: EvaluationDomain<E::ScalarField>> PendingRecv<E, D> {
+    pub fn commitment(&self) -> Com<E> {
+        self.com
+    }
+
+    /// See `LaconicOTRecv::domain_fingerprint`
+    pub fn domain_fingerprint(&self) -> [u8; 32] {
+        self.domain
+    }
+
+    /// Session id the receiver will decrypt under, see
+    /// `LaconicOTRecv::with_session_id`
+    pub fn with_session_id(mut self, id: [u8; 32]) -> Self {
+        self.session = id;
+        self
+    }
+
+    /// Proof that the committed choice bits are 0 or 1, to send along with
+    /// the commitment. The sender checks it with `BitProof::verify`.
+    pub fn bit_proof(&self, ck: &CommitmentKey<E, D>) -> BitProof<E> {
+        BitProof::prove(ck, &self.elems, self.bits.len())
+    }
+
+    /// Compute all openings and turn this into a full receiver. `ck` must be
+    /// the key the commitment was made with.
+    pub fn prepare_openings(mut self, ck: &CommitmentKey<E, D>) -> LaconicOTRecv<E, D> {
+        assert_eq!(
+            self.domain,
+            domain_fingerprint(&ck.domain),
+            "commitment key does not match the commitment"
+        );
+
+        // compute all openings
+        let qs = all_openings_single::<E, D>(&ck.y, &ck.domain, &self.elems);
+
+        LaconicOTRecv {
+            qs,
+            com: self.com,
+            selections: self
+                .bits
+                .iter()
+                .map(|b| (*b == Choice::One) as usize)
+                .collect(),
+            bits: std::mem::take(&mut *self.bits),
+            domain: self.domain,
+            session: self.session,
+            bit_proof: None,
+            _d: PhantomData,
+        }
+    }
+
+    /// Like `prepare_openings`, also keeping a `bit_proof` for the receiver
+    pub fn prepare_openings_with_bit_proof(self, ck: &CommitmentKey<E, D>) -> LaconicOTRecv<E, D> {
+        let proof = self.bit_proof(ck);
+        let mut recv = self.prepare_openings(ck);
+        recv.bit_proof = Some(proof);
+        recv
+    }
+}
+
+/// Wipes the committed choices and selections, the receiver's private input.
+/// Runs on drop; a wiped receiver rejects every index like an empty one.
+impl<E: Pairing, D: EvaluationDomain<E::ScalarField>> Zeroize for LaconicOTRecv<E, D> {
+    fn zeroize(&mut self) {
+        self.bits.zeroize();
+        self.selections.zeroize();
+    }
+}
+
+impl<E: Pairing, D: EvaluationDomain<E::ScalarField>> Drop for LaconicOTRecv<E, D> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<E: Pairing, D: EvaluationDomain<E::ScalarField>> ZeroizeOnDrop for LaconicOTRecv<E, D> {}
+
+/// Map choice bits to the 0/1 field elements the receiver commits to
+fn bit_elems<E: Pairing>(bits: &[Choice]) -> Vec<E::ScalarField> {
+    bits.iter()
+        .map(|b| {
+            if *b == Choice::One {
+                E::ScalarField::one()
+            } else {
+                E::ScalarField::zero()
+            }
+        })
+        .collect()
+}
+
+/// Blake3 state after absorbing the pad, the XOF of which is the keystream
+fn pad_hasher<E: Pairing>(
+    pad: E::TargetField,
+    session: &[u8; 32],
+    i: usize,
+    branch: u8,
+) -> blake3::Hasher {
+    // hash the pad, bound to the session, position and branch it encrypts
     let mut hsh = blake3::Hasher::new();
     pad.serialize_uncompressed(&mut hsh).unwrap();
+    hsh.update(session);
+    hsh.update(&(i as u64).to_le_bytes());
+    hsh.update(&[branch]);
+    hsh
+}
 
+/// Encrypt the `branch` (0 or 1) message at OT position `i`
+fn encrypt<E: Pairing, const N: usize>(
+    pad: E::TargetField,
+    session: &[u8; 32],
+    i: usize,
+    branch: u8,
+    msg: &[u8; N],
+) -> [u8; N] {
     // xor the message with the pad
-    let mut xof = hsh.finalize_xof();
+    let mut xof = pad_hasher::<E>(pad, session, i, branch).finalize_xof();
     let mut res = [0u8; N];
     xof.fill(&mut res);
 
-    for i in 0..N {
-        res[i] ^= msg[i];
+    for (r, m) in res.iter_mut().zip(msg) {
+        *r ^= m;
     }
     res
 }
 
-fn decrypt<E: Pairing, const N: usize>(pad: E::TargetField, ct: &[u8; N]) -> [u8; N] {
-    encrypt::<E, N>(pad, ct)
+fn decrypt<E: Pairing, const N: usize>(
+    pad: E::TargetField,
+    session: &[u8; 32],
+    i: usize,
+    branch: u8,
+    ct: &[u8; N],
+) -> [u8; N] {
+    encrypt::<E, N>(pad, session, i, branch, ct)
 }
 
 impl<'a, E: Pairing, D: EvaluationDomain<E::ScalarField>> LaconicOTSender<'a, E, D> {
-    pub fn new(ck: &'a CommitmentKey<E, D>, com: Com<E>) -> Self {
-        Self { ck, com }
+    /// Sender for the commitment `com` of a receiver whose
+    /// `LaconicOTRecv::domain_fingerprint` is `receiver_domain`. Sending fails
+    /// if the receiver used another commitment key size, instead of producing
+    /// messages `recv` silently decrypts to garbage.
+    pub fn new(ck: &'a CommitmentKey<E, D>, com: Com<E>, receiver_domain: [u8; 32]) -> Self {
+        Self {
+            ck,
+            com,
+            receiver_domain,
+            session: [0; 32],
+        }
+    }
+
+    /// Encrypt under session `id`, mixed into every keystream. Concurrent
+    /// sessions with distinct ids never share a pad, even for equal
+    /// commitments and messages. The receiver must use the same id, see
+    /// `LaconicOTRecv::with_session_id`. Defaults to all zeros.
+    pub fn with_session_id(mut self, id: [u8; 32]) -> Self {
+        self.session = id;
+        self
     }
 
-    pub fn send<R: Rng>(
+    /// Whether the receiver committed over the domain we send on
+    pub fn check_domain(&self) -> Result<(), SendError> {
+        if self.receiver_domain != domain_fingerprint(&self.ck.domain) {
+            return Err(SendError::DomainMismatch);
+        }
+        Ok(())
+    }
+
+    /// Errors if the receiver domain differs, see `check_domain`
+    pub fn send<R: Rng, const N: usize>(
         &self,
         rng: &mut R,
         i: usize,
-        m0: [u8; MSG_SIZE],
-        m1: [u8; MSG_SIZE],
-    ) -> Msg<E> {
-        let x = self.ck.domain.element(i);
-        let r0 = E::ScalarField::rand(rng);
-        let r1 = E::ScalarField::rand(rng);
+        m0: [u8; N],
+        m1: [u8; N],
+    ) -> Result<Msg<E, N>, SendError> {
+        Ok(self.send_batch(rng, &[i], &[(m0, m1)])?.remove(0))
+    }
+
+    /// Like `send`, but branch `j` decrypts only for a receiver whose committed
+    /// value at `i` equals `values[j]`, instead of 0 and 1. Meant for
+    /// receivers built with `LaconicOTRecv::new_from_field`.
+    pub fn send_with_values<R: Rng, const N: usize>(
+        &self,
+        rng: &mut R,
+        i: usize,
+        values: [E::ScalarField; 2],
+        m0: [u8; N],
+        m1: [u8; N],
+    ) -> Result<Msg<E, N>, SendError> {
+        self.check_domain()?;
 
         let g1 = self.ck.u[0];
         let g2 = self.ck.g2;
-        let tau = self.ck.r;
+        let tau = Into::<E::G2>::into(self.ck.r);
+        let x = self.ck.domain.element(i);
+
+        let r0 = E::ScalarField::rand(rng);
+        let r1 = E::ScalarField::rand(rng);
 
-        // y = 0/1
-        let l0 = self.com * r0; // r * (c - [y])
-        let l1 = (self.com - g1) * r1; // r * (c - [y])
+        // r * (c - [y])
+        let l0 = (self.com - g1 * values[0]) * r0;
+        let l1 = (self.com - g1 * values[1]) * r1;
 
-        // m0, m1
-        let msk0 = E::pairing(l0, self.ck.g2);
-        let msk1 = E::pairing(l1, self.ck.g2);
+        let msk0 = E::pairing(l0, g2);
+        let msk1 = E::pairing(l1, g2);
 
-        // h0, h1
-        let cm = Into::<E::G2>::into(tau) - g2 * x;
+        let cm = tau - g2 * x;
         let h0: E::G2 = cm * r0;
         let h1: E::G2 = cm * r1;
 
-        // encapsulate the messages
-        Msg {
+        Ok(Msg {
             h: [
-                (h0.into(), encrypt::<E, MSG_SIZE>(msk0.0, &m0)),
-                (h1.into(), encrypt::<E, MSG_SIZE>(msk1.0, &m1)),
+                (h0.into(), encrypt::<E, N>(msk0.0, &self.session, i, 0, &m0)),
+                (h1.into(), encrypt::<E, N>(msk1.0, &self.session, i, 1, &m1)),
             ],
-        }
+        })
+    }
+
+    /// 1-out-of-K transfer: message `j` decrypts only for a receiver whose
+    /// committed value at `i` is `j`, see `LaconicOTRecv::new_n`. `K` is
+    /// meant to be small, each branch costs one pairing.
+    pub fn send_n<R: Rng, const K: usize, const N: usize>(
+        &self,
+        rng: &mut R,
+        i: usize,
+        msgs: &[[u8; N]; K],
+    ) -> Result<MsgN<E, K, N>, SendError> {
+        assert!(K <= 256, "send_n supports at most 256 messages");
+        self.check_domain()?;
+
+        let g1 = self.ck.u[0];
+        let g2 = self.ck.g2;
+        let tau = Into::<E::G2>::into(self.ck.r);
+        let cm = tau - g2 * self.ck.domain.element(i);
+
+        Ok(MsgN {
+            h: std::array::from_fn(|j| {
+                let r = E::ScalarField::rand(rng);
+                // r * (c - [j])
+                let l = (self.com - g1 * E::ScalarField::from(j as u64)) * r;
+                let msk = E::pairing(l, g2);
+                let h: E::G2 = cm * r;
+                (
+                    h.into(),
+                    encrypt::<E, N>(msk.0, &self.session, i, j as u8, &msgs[j]),
+                )
+            }),
+        })
+    }
+
+    /// Send one message pair per index, returned in the same order as `indices`.
+    /// Randomness is drawn from `rng` in the same order as repeated calls to `send`.
+    pub fn send_batch<R: Rng, const N: usize>(
+        &self,
+        rng: &mut R,
+        indices: &[usize],
+        msgs: &[([u8; N], [u8; N])],
+    ) -> Result<Vec<Msg<E, N>>, SendError> {
+        assert_eq!(
+            indices.len(),
+            msgs.len(),
+            "indices and messages must have the same length"
+        );
+        self.check_domain()?;
+
+        let g1 = self.ck.u[0];
+        let g2 = self.ck.g2;
+        let tau = Into::<E::G2>::into(self.ck.r);
+
+        // c - [1] is shared by every one-branch
+        let com_minus_g1 = self.com - g1;
+
+        Ok(indices
+            .iter()
+            .zip(msgs)
+            .map(|(&i, (m0, m1))| {
+                let x = self.ck.domain.element(i);
+                let r0 = E::ScalarField::rand(rng);
+                let r1 = E::ScalarField::rand(rng);
+
+                // y = 0/1
+                let l0 = self.com * r0; // r * (c - [y])
+                let l1 = com_minus_g1 * r1; // r * (c - [y])
+
+                // m0, m1
+                let msk0 = E::pairing(l0, g2);
+                let msk1 = E::pairing(l1, g2);
+
+                // h0, h1
+                let cm = tau - g2 * x;
+                let h0: E::G2 = cm * r0;
+                let h1: E::G2 = cm * r1;
+
+                // encapsulate the messages
+                Msg {
+                    h: [
+                        (h0.into(), encrypt::<E, N>(msk0.0, &self.session, i, 0, m0)),
+                        (h1.into(), encrypt::<E, N>(msk1.0, &self.session, i, 1, m1)),
+                    ],
+                }
+            })
+            .collect())
     }
 }
 
@@ -233,15 +819,476 @@ fn test_laconic_ot() {
     let ck = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, degree).unwrap();
 
     let sender = LaconicOTRecv::new(&ck, &[Choice::Zero, Choice::One, Choice::Zero, Choice::One]);
-    let receiver = LaconicOTSender::new(&ck, sender.commitment());
+    let receiver = LaconicOTSender::new(&ck, sender.commitment(), sender.domain_fingerprint());
 
     let m0 = [0u8; MSG_SIZE];
     let m1 = [1u8; MSG_SIZE];
-    let msg = receiver.send(rng, 0, m0, m1);
-    let res = sender.recv(0, msg);
+    let msg = receiver.send(rng, 0, m0, m1).unwrap();
+    let res = sender.recv(0, msg).unwrap();
     assert_eq!(res, m0);
 }
 
+#[test]
+fn test_receiver_zeroize_wipes_choices() {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+
+    let rng = &mut test_rng();
+    let ck = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, 4).unwrap();
+
+    let mut receiver = LaconicOTRecv::new(&ck, &[Choice::One, Choice::Zero]);
+    let sender = LaconicOTSender::new(&ck, receiver.commitment(), receiver.domain_fingerprint());
+    assert_eq!(receiver.selected_branch(0), Ok(Choice::One));
+
+    receiver.zeroize();
+    let msg = sender
+        .send(rng, 0, [0u8; MSG_SIZE], [1u8; MSG_SIZE])
+        .unwrap();
+    assert_eq!(
+        receiver.recv(0, msg),
+        Err(RecvError::IndexOutOfRange { index: 0, len: 0 })
+    );
+    assert_eq!(
+        receiver.selected_branch(0),
+        Err(RecvError::IndexOutOfRange { index: 0, len: 0 })
+    );
+}
+
+#[test]
+fn test_laconic_ot_field_elements() {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+
+    let rng = &mut test_rng();
+
+    let ck = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, 4).unwrap();
+
+    let elems = [
+        Fr::from(2u64),
+        Fr::from(3u64),
+        Fr::from(5u64),
+        Fr::from(7u64),
+    ];
+    let bits = [Choice::Zero, Choice::One, Choice::One, Choice::Zero];
+    let receiver = LaconicOTRecv::new_from_field(&ck, &elems, &bits);
+    let sender = LaconicOTSender::new(&ck, receiver.commitment(), receiver.domain_fingerprint());
+
+    let m0 = [0u8; MSG_SIZE];
+    let m1 = [1u8; MSG_SIZE];
+
+    // Position 2 holds 5, so the branch encrypted under 5 opens
+    let msg = sender
+        .send_with_values(rng, 2, [Fr::from(4u64), Fr::from(5u64)], m0, m1)
+        .unwrap();
+    assert_eq!(receiver.recv(2, msg).unwrap(), m1);
+
+    // Under values that don't match the commitment, the selected branch
+    // decrypts to garbage
+    let msg = sender
+        .send_with_values(rng, 2, [Fr::from(0u64), Fr::from(1u64)], m0, m1)
+        .unwrap();
+    assert_ne!(receiver.recv(2, msg).unwrap(), m1);
+}
+
+#[test]
+fn test_sender_rejects_receiver_domain() {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+
+    let rng = &mut test_rng();
+    let ck = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, 4).unwrap();
+    let receiver = LaconicOTRecv::new(&ck, &[Choice::One, Choice::Zero]);
+
+    let m0 = [0u8; MSG_SIZE];
+    let m1 = [1u8; MSG_SIZE];
+    let sender = LaconicOTSender::new(&ck, receiver.commitment(), receiver.domain_fingerprint());
+    let msg = sender.send(rng, 0, m0, m1).unwrap();
+    assert_eq!(receiver.recv(0, msg).unwrap(), m1);
+
+    // A key for a larger domain evaluates at other points
+    let other = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, 8).unwrap();
+    let sender = LaconicOTSender::new(&other, receiver.commitment(), receiver.domain_fingerprint());
+    assert_eq!(sender.check_domain(), Err(SendError::DomainMismatch));
+    assert_eq!(
+        sender.send(rng, 0, m0, m1).err(),
+        Some(SendError::DomainMismatch)
+    );
+    assert_eq!(
+        sender.send_batch(rng, &[0], &[(m0, m1)]).err(),
+        Some(SendError::DomainMismatch)
+    );
+    assert_eq!(
+        sender.send_n(rng, 0, &[m0, m1]).err(),
+        Some(SendError::DomainMismatch)
+    );
+    let values = [Fr::from(0u64), Fr::from(1u64)];
+    assert_eq!(
+        sender.send_with_values(rng, 0, values, m0, m1).err(),
+        Some(SendError::DomainMismatch)
+    );
+}
+
+#[test]
+fn test_one_out_of_four() {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+
+    let rng = &mut test_rng();
+    let ck = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, 4).unwrap();
+
+    let selections = [2, 0, 3, 1];
+    let receiver = LaconicOTRecv::new_n(&ck, &selections);
+    let sender = LaconicOTSender::new(&ck, receiver.commitment(), receiver.domain_fingerprint());
+
+    for (i, selection) in selections.iter().enumerate() {
+        let msgs: [[u8; MSG_SIZE]; 4] = std::array::from_fn(|j| [(4 * i + j) as u8; MSG_SIZE]);
+        let msg = sender.send_n(rng, i, &msgs).unwrap();
+        assert_eq!(receiver.recv_n(i, msg).unwrap(), msgs[*selection]);
+    }
+
+    // Selection 3 has no branch in a two-message transfer
+    let msg = sender.send_n(rng, 2, &[[0u8; MSG_SIZE]; 2]).unwrap();
+    assert_eq!(
+        receiver.recv_n(2, msg),
+        Err(RecvError::SelectionOutOfRange {
+            index: 2,
+            selection: 3,
+            arity: 2
+        })
+    );
+
+    // Nor does `recv` open it, instead of falling back to branch 0
+    let msg = sender
+        .send(rng, 0, [0u8; MSG_SIZE], [1u8; MSG_SIZE])
+        .unwrap();
+    assert_eq!(
+        receiver.recv(0, msg),
+        Err(RecvError::SelectionOutOfRange {
+            index: 0,
+            selection: 2,
+            arity: 2
+        })
+    );
+    assert_eq!(receiver.selected_branch(3), Ok(Choice::One));
+}
+
+#[test]
+fn test_keystream_bound_to_position_and_branch() {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+
+    let rng = &mut test_rng();
+    let ck = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, 4).unwrap();
+    let pad = Bls12_381::pairing(ck.u[0], ck.g2).0;
+
+    // Same pad, same plaintext: only the slot differs
+    let zero = [0u8; MSG_SIZE];
+    let slot_0_0 = encrypt::<Bls12_381, MSG_SIZE>(pad, &[0; 32], 0, 0, &zero);
+    assert_ne!(
+        slot_0_0,
+        encrypt::<Bls12_381, MSG_SIZE>(pad, &[0; 32], 1, 0, &zero)
+    );
+    assert_ne!(
+        slot_0_0,
+        encrypt::<Bls12_381, MSG_SIZE>(pad, &[0; 32], 0, 1, &zero)
+    );
+    assert_eq!(
+        decrypt::<Bls12_381, MSG_SIZE>(pad, &[0; 32], 0, 0, &slot_0_0),
+        zero
+    );
+    assert_ne!(
+        slot_0_0,
+        encrypt::<Bls12_381, MSG_SIZE>(pad, &[1; 32], 0, 0, &zero)
+    );
+}
+
+#[test]
+fn test_keystream_extends_encrypt() {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+
+    let rng = &mut test_rng();
+    let ck = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, 4).unwrap();
+    let receiver = LaconicOTRecv::new(&ck, &[Choice::Zero, Choice::One]);
+    let sender = LaconicOTSender::new(&ck, receiver.commitment(), receiver.domain_fingerprint());
+
+    let m1 = [5u8; 16];
+    let msg = sender.send(rng, 1, [0u8; 16], m1).unwrap();
+    let stream = receiver.keystream(1, &msg, 64).unwrap();
+    assert_eq!(stream.len(), 64);
+
+    // The first 16 bytes are the ones `recv` XORs into the ciphertext
+    let opened: Vec<u8> = msg.h[1].1.iter().zip(&stream).map(|(c, k)| c ^ k).collect();
+    assert_eq!(opened, m1);
+    assert_eq!(
+        receiver.keystream(2, &msg, 64),
+        Err(RecvError::IndexOutOfRange { index: 2, len: 2 })
+    );
+}
+
+#[test]
+fn test_session_id_must_match() {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+
+    let rng = &mut test_rng();
+    let ck = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, 4).unwrap();
+    let receiver = LaconicOTRecv::new(&ck, &[Choice::One]).with_session_id([7; 32]);
+
+    let sender = LaconicOTSender::new(&ck, receiver.commitment(), receiver.domain_fingerprint())
+        .with_session_id([7; 32]);
+    let msg = sender
+        .send(rng, 0, [0u8; MSG_SIZE], [1u8; MSG_SIZE])
+        .unwrap();
+    assert_eq!(receiver.recv(0, msg).unwrap(), [1u8; MSG_SIZE]);
+
+    // The default id is another session
+    let sender = LaconicOTSender::new(&ck, receiver.commitment(), receiver.domain_fingerprint());
+    let msg = sender
+        .send(rng, 0, [0u8; MSG_SIZE], [1u8; MSG_SIZE])
+        .unwrap();
+    assert_ne!(receiver.recv(0, msg).unwrap(), [1u8; MSG_SIZE]);
+}
+
+#[test]
+fn test_recv_index_out_of_range() {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+
+    let rng = &mut test_rng();
+
+    let ck = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, 4).unwrap();
+
+    // Three committed bits, the fourth domain position is padding
+    let bits = [Choice::Zero, Choice::One, Choice::Zero];
+    let receiver = LaconicOTRecv::new(&ck, &bits);
+    let sender = LaconicOTSender::new(&ck, receiver.commitment(), receiver.domain_fingerprint());
+
+    let msg = sender
+        .send(rng, 3, [0u8; MSG_SIZE], [1u8; MSG_SIZE])
+        .unwrap();
+    assert_eq!(
+        receiver.recv(3, msg),
+        Err(RecvError::IndexOutOfRange { index: 3, len: 3 })
+    );
+
+    // The selected branches are the committed bits, padding has none
+    for (i, &bit) in bits.iter().enumerate() {
+        assert_eq!(receiver.selected_branch(i), Ok(bit));
+    }
+    assert_eq!(
+        receiver.selected_branch(3),
+        Err(RecvError::IndexOutOfRange { index: 3, len: 3 })
+    );
+}
+
+#[test]
+fn test_commitment_only_matches_new() {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+
+    let rng = &mut test_rng();
+
+    let ck = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, 4).unwrap();
+    let bits = [Choice::One, Choice::Zero, Choice::One, Choice::One];
+
+    // No padding, so the commitment is fully determined by the bits
+    let pending = LaconicOTRecv::commitment_only(&ck, &bits);
+    assert_eq!(
+        pending.commitment(),
+        LaconicOTRecv::new(&ck, &bits).commitment()
+    );
+
+    let receiver = pending.prepare_openings(&ck);
+    let sender = LaconicOTSender::new(&ck, receiver.commitment(), receiver.domain_fingerprint());
+    let msg = sender
+        .send(rng, 0, [0u8; MSG_SIZE], [1u8; MSG_SIZE])
+        .unwrap();
+    assert_eq!(receiver.recv(0, msg).unwrap(), [1u8; MSG_SIZE]);
+}
+
+#[test]
+fn test_new_with_bit_proof() {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+
+    let rng = &mut test_rng();
+
+    let ck = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, 8).unwrap();
+    let bits = [Choice::One, Choice::Zero, Choice::One];
+
+    let receiver = LaconicOTRecv::new_with_bit_proof(&ck, &bits);
+    let proof = receiver.bit_proof().unwrap();
+    assert!(proof.verify(&ck, &receiver.commitment(), bits.len()));
+    assert!(LaconicOTRecv::new(&ck, &bits).bit_proof().is_none());
+
+    let sender = LaconicOTSender::new(&ck, receiver.commitment(), receiver.domain_fingerprint());
+    let msg = sender
+        .send(rng, 2, [0u8; MSG_SIZE], [1u8; MSG_SIZE])
+        .unwrap();
+    assert_eq!(receiver.recv(2, msg).unwrap(), [1u8; MSG_SIZE]);
+}
+
+#[test]
+fn test_recv_rejects_padded_indices() {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+
+    let rng = &mut test_rng();
+
+    let ck = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, 8).unwrap();
+    let receiver = LaconicOTRecv::new_with_rng(rng, &ck, &[Choice::One, Choice::Zero, Choice::One]);
+    assert_eq!(receiver.padding_len(), 5);
+
+    let sender = LaconicOTSender::new(&ck, receiver.commitment(), receiver.domain_fingerprint());
+    for i in 3..8 {
+        let msg = sender
+            .send(rng, i, [0u8; MSG_SIZE], [1u8; MSG_SIZE])
+            .unwrap();
+        assert_eq!(
+            receiver.recv(i, msg),
+            Err(RecvError::IndexOutOfRange { index: i, len: 3 })
+        );
+    }
+
+    let full = LaconicOTRecv::new(&ck, &[Choice::Zero; 8]);
+    assert_eq!(full.padding_len(), 0);
+}
+
+#[test]
+fn test_new_with_seed_is_reproducible() {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+
+    let rng = &mut test_rng();
+
+    let ck = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, 8).unwrap();
+    let bits = [Choice::One, Choice::Zero, Choice::One];
+
+    let a = LaconicOTRecv::new_with_seed(&ck, &bits, [1u8; 32]);
+    let b = LaconicOTRecv::new_with_seed(&ck, &bits, [1u8; 32]);
+    let c = LaconicOTRecv::new_with_seed(&ck, &bits, [2u8; 32]);
+    assert_eq!(a.commitment(), b.commitment());
+    assert_ne!(a.commitment(), c.commitment());
+
+    // Seeded padding still opens like any other receiver
+    let sender = LaconicOTSender::new(&ck, c.commitment(), c.domain_fingerprint());
+    let msg = sender
+        .send(rng, 2, [0u8; MSG_SIZE], [1u8; MSG_SIZE])
+        .unwrap();
+    assert_eq!(c.recv(2, msg).unwrap(), [1u8; MSG_SIZE]);
+}
+
+#[test]
+fn test_recv_many_matches_recv() {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+
+    let rng = &mut test_rng();
+
+    let ck = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, 4).unwrap();
+    let receiver = LaconicOTRecv::new(&ck, &[Choice::One, Choice::Zero, Choice::One]);
+    let sender = LaconicOTSender::new(&ck, receiver.commitment(), receiver.domain_fingerprint());
+
+    let items: Vec<_> = [2, 0, 1]
+        .into_iter()
+        .map(|i| {
+            (
+                i,
+                sender
+                    .send(rng, i, [i as u8; MSG_SIZE], [0xf0; MSG_SIZE])
+                    .unwrap(),
+            )
+        })
+        .collect();
+
+    let single: Vec<_> = items
+        .iter()
+        .map(|(i, msg)| receiver.recv(*i, *msg).unwrap())
+        .collect();
+    assert_eq!(receiver.recv_many(&items).unwrap(), single);
+}
+
+#[test]
+fn test_send_batch_matches_send() {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+
+    let rng = &mut test_rng();
+
+    let degree = 4;
+    let bits = [Choice::Zero, Choice::One, Choice::Zero, Choice::One];
+    let ck = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, degree).unwrap();
+    let receiver = LaconicOTRecv::new(&ck, &bits);
+    let sender = LaconicOTSender::new(&ck, receiver.commitment(), receiver.domain_fingerprint());
+
+    let indices = [3, 0, 2, 1];
+    let msgs: Vec<_> = (0..4u8)
+        .map(|i| ([i; MSG_SIZE], [i + 10; MSG_SIZE]))
+        .collect();
+
+    // same rng stream for both paths
+    let mut rng_single = test_rng();
+    let mut rng_batch = test_rng();
+    let single: Vec<_> = indices
+        .iter()
+        .zip(&msgs)
+        .map(|(&i, &(m0, m1))| sender.send(&mut rng_single, i, m0, m1).unwrap())
+        .collect();
+    let batch = sender.send_batch(&mut rng_batch, &indices, &msgs).unwrap();
+
+    assert_eq!(single.len(), batch.len());
+    for (a, b) in single.iter().zip(&batch) {
+        assert_eq!(a.h[0].0, b.h[0].0);
+        assert_eq!(a.h[1].0, b.h[1].0);
+        assert_eq!(a.h[0].1, b.h[0].1);
+        assert_eq!(a.h[1].1, b.h[1].1);
+    }
+
+    // output order follows the input order
+    for ((&i, &(m0, m1)), msg) in indices.iter().zip(&msgs).zip(batch) {
+        let expected = if bits[i] == Choice::One { m1 } else { m0 };
+        assert_eq!(receiver.recv(i, msg).unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_padding_is_randomized() {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+
+    let rng = &mut test_rng();
+
+    let degree = 4;
+    let ck = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, degree).unwrap();
+
+    // fewer bits than the domain size, so the commitment depends on the padding
+    let bits = [Choice::Zero, Choice::One];
+    let first = LaconicOTRecv::new(&ck, &bits);
+    let second = LaconicOTRecv::new(&ck, &bits);
+    assert_ne!(first.commitment(), second.commitment());
+}
+
+#[test]
+fn test_laconic_ot_32_byte_messages() {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+
+    let rng = &mut test_rng();
+
+    let degree = 4;
+    let ck = CommitmentKey::<Bls12_381, Radix2EvaluationDomain<Fr>>::setup(rng, degree).unwrap();
+
+    let receiver = LaconicOTRecv::new(&ck, &[Choice::Zero, Choice::One, Choice::Zero, Choice::One]);
+    let sender = LaconicOTSender::new(&ck, receiver.commitment(), receiver.domain_fingerprint());
+
+    let m0 = [0u8; 32];
+    let m1 = [1u8; 32];
+    let msg = sender.send(rng, 1, m0, m1).unwrap();
+    let res = receiver.recv(1, msg).unwrap();
+    assert_eq!(res, m1);
+}
+
 #[test]
 fn test_msg_serialization() {
     use ark_bls12_381::{Bls12_381, Fr, G2Affine};
@@ -268,3 +1315,35 @@ fn test_msg_serialization() {
     assert_eq!(original_msg.h[0].0, deserialized_msg.h[0].0);
     assert_eq!(original_msg.h[1].0, deserialized_msg.h[1].0);
 }
+
+#[test]
+fn test_msg_encodings_agree() {
+    use ark_bls12_381::{Bls12_381, G2Affine};
+    use rand::rngs::OsRng;
+
+    let rng = &mut OsRng;
+    let msg = Msg::<Bls12_381> {
+        h: [
+            (G2Affine::rand(rng), [1u8; MSG_SIZE]),
+            (G2Affine::rand(rng), [2u8; MSG_SIZE]),
+        ],
+    };
+
+    let serializable = SerializableMsg::from(msg);
+    assert_eq!(
+        serializable.h[0].0.len(),
+        G2Affine::rand(rng).compressed_size()
+    );
+    assert_eq!(msg.serialize(), serde_json::to_vec(&serializable).unwrap());
+
+    // Each path reads what the other wrote
+    let via_from = Msg::<Bls12_381>::deserialize(&serde_json::to_vec(&serializable).unwrap());
+    let via_serialize = Msg::<Bls12_381>::try_from(
+        serde_json::from_slice::<SerializableMsg>(&msg.serialize()).unwrap(),
+    )
+    .unwrap();
+    for decoded in [via_from, via_serialize] {
+        assert_eq!(decoded.h[0], msg.h[0]);
+        assert_eq!(decoded.h[1], msg.h[1]);
+    }
+}
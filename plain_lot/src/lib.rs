@@ -1,3 +1,4 @@
+mod bit_proof;
 mod kzg;
 mod kzg_fk_open;
 mod kzg_types;
@@ -5,10 +6,15 @@ mod kzg_utils;
 
 mod laconic_ot;
 
-pub use laconic_ot::{Choice, Com, LaconicOTRecv, LaconicOTSender, Msg, SerializableMsg};
+pub use laconic_ot::{
+    ct_eq, domain_fingerprint, Choice, Com, LaconicOTRecv, LaconicOTSender, Msg, MsgN, PendingRecv,
+    RecvError, SendError, SerializableMsg, MSG_SIZE,
+};
+
+pub use bit_proof::BitProof;
 
 pub use kzg_utils::plain_kzg_com;
 
 pub use kzg_types::CommitmentKey;
 
-pub use kzg_fk_open::all_openings_single;
+pub use kzg_fk_open::{all_openings_single, compare_fk_vs_single};
@@ -0,0 +1,96 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use trinity::{parse_circuit, CircuitWrapper, TrinityEvaluator, TrinityGarbler, TrinityWasmSetup};
+
+const MODES: &[&str] = &[
+    #[cfg(feature = "plain")]
+    "Plain",
+    #[cfg(feature = "halo2")]
+    "Halo2",
+];
+
+// (circuit file, input bits per party)
+const CIRCUITS: [(&str, usize); 2] = [
+    ("circuits/simple_8bit_add.txt", 8),
+    ("circuits/simple_16bit_add.txt", 16),
+];
+
+fn load_circuit(path: &str, bits: usize) -> CircuitWrapper {
+    let circuit_str = std::fs::read_to_string(path).unwrap();
+    parse_circuit(&circuit_str, bits, bits, bits).unwrap()
+}
+
+fn bench_setup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("setup");
+    group.sample_size(10);
+
+    for &mode in MODES {
+        group.bench_with_input(BenchmarkId::from_parameter(mode), &mode, |b, &mode| {
+            b.iter(|| TrinityWasmSetup::new(mode).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_garble_evaluate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("two_pc");
+    group.sample_size(10);
+
+    for &mode in MODES {
+        let setup = TrinityWasmSetup::new(mode).unwrap();
+
+        for (path, bits) in CIRCUITS {
+            let circuit = load_circuit(path, bits);
+            let input = vec![1u8; bits / 8];
+            let id = format!("{}/{}", mode, bits);
+
+            let evaluator = TrinityEvaluator::new(&setup, input.clone()).unwrap();
+            let commitment = evaluator.commitment_serialized().unwrap();
+
+            group.bench_function(BenchmarkId::new("garble", &id), |b| {
+                b.iter(|| {
+                    TrinityGarbler::new_deterministic(
+                        commitment.clone(),
+                        &setup,
+                        input.clone(),
+                        &circuit,
+                        0,
+                    )
+                    .unwrap()
+                })
+            });
+
+            let garbler = TrinityGarbler::new_deterministic(
+                commitment.clone(),
+                &setup,
+                input.clone(),
+                &circuit,
+                0,
+            )
+            .unwrap();
+            group.bench_function(BenchmarkId::new("evaluate", &id), |b| {
+                b.iter(|| evaluator.evaluate(&garbler, &circuit).unwrap())
+            });
+
+            // Commit, garble and evaluate, as in the `two_pc_e2e` tests
+            group.bench_function(BenchmarkId::new("e2e", &id), |b| {
+                b.iter(|| {
+                    let evaluator = TrinityEvaluator::new(&setup, input.clone()).unwrap();
+                    let garbler = TrinityGarbler::new(
+                        evaluator.commitment_serialized().unwrap(),
+                        &setup,
+                        input.clone(),
+                        &circuit,
+                    )
+                    .unwrap();
+                    evaluator.evaluate(&garbler, &circuit).unwrap()
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_setup, bench_garble_evaluate);
+criterion_main!(benches);
@@ -0,0 +1,79 @@
+//! Write a seed corpus of valid encodings for the targets in `fuzz/`:
+//!
+//! ```sh
+//! cargo run --example fuzz_corpus --features fuzzing
+//! ```
+
+use std::fs;
+use std::path::Path;
+
+use trinity::fuzzing::TrinityCom;
+use trinity::{
+    parse_circuit, write_ciphertexts, GarbledBundle, TrinityEvaluator, TrinityGarbler, TrinityMsg,
+    TrinityWasmSetup,
+};
+
+const MODES: &[&str] = &[
+    #[cfg(feature = "plain")]
+    "Plain",
+    #[cfg(feature = "halo2")]
+    "Halo2",
+];
+
+fn write_seed(target: &str, name: &str, bytes: &[u8]) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("fuzz/corpus")
+        .join(target);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join(name), bytes).unwrap();
+}
+
+fn main() {
+    let circuit = parse_circuit(include_str!("../circuits/simple_8bit_add.txt"), 8, 8, 8).unwrap();
+
+    for &mode in MODES {
+        let setup = TrinityWasmSetup::new(mode).unwrap();
+        let evaluator = TrinityEvaluator::new(&setup, vec![4]).unwrap();
+        let commitment = evaluator.commitment_serialized().unwrap();
+        let garbler = TrinityGarbler::new(commitment.clone(), &setup, vec![6], &circuit).unwrap();
+        let bundle_bytes = garbler.bundle();
+        let bundle = GarbledBundle::from_bytes(&bundle_bytes).unwrap();
+        let msgs: Vec<TrinityMsg> = bundle
+            .ciphertexts()
+            .iter()
+            .map(|bytes| TrinityMsg::from_bytes(bytes).unwrap())
+            .collect();
+
+        let com = TrinityCom::deserialize(commitment.as_bytes()).unwrap();
+        write_seed(
+            "trinity_com",
+            &format!("{mode}_json"),
+            commitment.as_bytes(),
+        );
+        write_seed(
+            "trinity_com",
+            &format!("{mode}_tagged"),
+            &com.to_tagged_bytes(),
+        );
+        write_seed(
+            "trinity_com",
+            &format!("{mode}_hex"),
+            com.to_hex().as_bytes(),
+        );
+
+        write_seed("trinity_msg", &format!("{mode}_bytes"), &msgs[0].to_bytes());
+        write_seed("trinity_msg", &format!("{mode}_json"), &msgs[0].serialize());
+
+        write_seed("sender_bytes", mode, &setup.to_sender_setup());
+        write_seed(
+            "sender_bytes",
+            &format!("{mode}_compressed"),
+            &setup.to_sender_setup_compressed(),
+        );
+
+        let mut stream = Vec::new();
+        write_ciphertexts(&mut stream, &msgs).unwrap();
+        write_seed("ciphertexts", &format!("{mode}_stream"), &stream);
+        write_seed("ciphertexts", &format!("{mode}_bundle"), &bundle_bytes);
+    }
+}
@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use trinity::{read_ciphertexts, write_ciphertexts, GarbledBundle};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(msgs) = read_ciphertexts::<_, 16>(data) {
+        let mut stream = Vec::new();
+        write_ciphertexts(&mut stream, &msgs).unwrap();
+        assert_eq!(
+            read_ciphertexts::<_, 16>(&stream[..]).unwrap().len(),
+            msgs.len()
+        );
+    }
+    let _ = GarbledBundle::from_bytes(data);
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use trinity::fuzzing::Trinity;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Trinity::from_sender_bytes(data);
+});
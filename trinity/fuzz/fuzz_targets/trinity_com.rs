@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use trinity::fuzzing::TrinityCom;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = TrinityCom::deserialize(data);
+    let _ = TrinityCom::deserialize_with_proof(data);
+    if let Ok(com) = TrinityCom::from_tagged_bytes(data) {
+        assert!(TrinityCom::from_tagged_bytes(&com.to_tagged_bytes()).is_ok());
+    }
+    if let Ok(s) = std::str::from_utf8(data) {
+        let _ = TrinityCom::from_hex(s);
+        let _ = TrinityCom::from_base64url(s);
+    }
+});
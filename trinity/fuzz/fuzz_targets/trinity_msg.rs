@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use trinity::fuzzing::TrinityMsg;
+
+fuzz_target!(|data: &[u8]| {
+    // Both encodings must return Err on bad input, and anything they accept
+    // must survive a round trip
+    if let Ok(msg) = TrinityMsg::<16>::deserialize(data) {
+        assert!(TrinityMsg::<16>::deserialize(&msg.serialize()).is_ok());
+    }
+    if let Ok(msg) = TrinityMsg::<16>::from_bytes(data) {
+        assert!(TrinityMsg::<16>::from_bytes(&msg.to_bytes()).is_ok());
+    }
+});
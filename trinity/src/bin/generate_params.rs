@@ -1,38 +1,81 @@
 use std::env;
 use std::fs::File;
 use std::io::Write;
-use trinity::TrinityWasmSetup;
+use trinity::{KZGType, TrinityWasmSetup, MAX_SETUP_DEGREE, MIN_SETUP_DEGREE};
 
-// cargo run --release --bin generate_params -- Halo2
+const USAGE: &str =
+    "Usage: cargo run --release --bin generate_params -- <Plain|Halo2> [--out <path>] [--degree <k>]";
+
+// cargo run --release --bin generate_params -- Halo2 --degree 12 --out ./params/halo2_k12.bin
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let args: Vec<String> = env::args().skip(1).collect();
 
     // Check for the correct number of arguments and validate the mode.
-    if args.len() != 2 || (args[1] != "Plain" && args[1] != "Halo2") {
-        eprintln!("Usage: cargo run --release --bin generate_params -- <Plain|Halo2>");
-        std::process::exit(1);
+    let mode: KZGType = match args.first().map(|mode| mode.parse()) {
+        Some(Ok(mode)) => mode,
+        Some(Err(e)) => {
+            eprintln!("{}", e);
+            exit_with_usage()
+        }
+        None => exit_with_usage(),
+    };
+
+    let mut out = None;
+    let mut degree = None;
+    let mut rest = args[1..].iter();
+    while let Some(flag) = rest.next() {
+        let value = rest.next().unwrap_or_else(|| exit_with_usage());
+        match flag.as_str() {
+            "--out" => out = Some(value.clone()),
+            "--degree" => degree = Some(parse_degree(value)),
+            _ => exit_with_usage(),
+        }
     }
 
-    let mode = &args[1];
     println!("[1/5] Starting parameter generation for '{}' mode...", mode);
 
     println!("[2/5] Creating new TrinityWasmSetup for '{}' mode...", mode);
-    let setup = TrinityWasmSetup::new(mode);
+    let setup = match degree {
+        Some(k) => {
+            println!("      Using degree k = {} ({} input bits)", k, 1u64 << k);
+            TrinityWasmSetup::with_degree(&mode.to_string(), k)
+        }
+        None => TrinityWasmSetup::new(&mode.to_string()),
+    }
+    .unwrap_or_else(|_| {
+        eprintln!("Failed to create setup for '{}' mode", mode);
+        std::process::exit(1);
+    });
     println!("[3/5] Setup created successfully.");
 
     let params = setup.to_full_params_bytes();
     println!("[4/5] Parameters serialized to bytes.");
 
-    // Determine the output filename based on the mode.
-    let filename = if mode == "Halo2" {
-        "halo2params.bin"
-    } else {
-        "plainparams.bin"
-    };
+    // Default to a per-mode file in the current directory (workspace root).
+    let path = out.unwrap_or_else(|| format!("./{}params.bin", mode.to_string().to_lowercase()));
 
-    // Write the file to the current directory (workspace root).
-    let path = format!("./{}", filename);
-    let mut file = File::create(&path).expect("Unable to create file");
+    let mut file = File::create(&path).unwrap_or_else(|e| {
+        eprintln!("Unable to create {}: {}", path, e);
+        std::process::exit(1);
+    });
     file.write_all(&params).expect("Unable to write data");
-    println!("[5/5] Success! {} written to {}", filename, path);
+    println!("[5/5] Success! Parameters written to {}", path);
+}
+
+fn parse_degree(value: &str) -> u32 {
+    match value.parse::<u32>() {
+        Ok(k) if (MIN_SETUP_DEGREE..=MAX_SETUP_DEGREE).contains(&k) => k,
+        _ => {
+            eprintln!(
+                "Invalid --degree '{}': expected an integer k between {} and {}, params support 2^k input bits",
+                value, MIN_SETUP_DEGREE, MAX_SETUP_DEGREE
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(1);
 }
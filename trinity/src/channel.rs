@@ -0,0 +1,210 @@
+//! Async drivers running one party of the protocol over a byte stream.
+//!
+//! The evaluator sends its commitment with the proof that it commits to bits
+//! (`SerializableTrinityCom::with_proof`), the garbler checks the proof and
+//! answers with the garbled bundle (`GarbledBundle::to_bytes`) and the
+//! evaluator decodes the output. Each message is framed as a little-endian
+//! u64 length, at most `MAX_FRAME_LEN`, followed by that many bytes.
+//!
+//! Committing, garbling and evaluating run on tokio's blocking pool so they
+//! don't stall the executor.
+
+use std::io::{Error, ErrorKind};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::task::spawn_blocking;
+
+use crate::commit::{to_json, Randomness, SerializableTrinityCom, TrinityCom};
+use crate::encoding::Lsb0Bits;
+use crate::evaluate::{ev_commit, evaluate_circuit};
+use crate::garble::{generate_garbled_circuit_with_randomness, GarbledBundle};
+use crate::{CircuitWrapper, TrinityWasmSetup};
+
+/// Largest frame `run_garbler` and `run_evaluator` accept. The length is
+/// chosen by the peer, so anything above is rejected before reading instead
+/// of buffering without bound.
+pub const MAX_FRAME_LEN: u64 = 1 << 28;
+
+/// Garbler side: wait for the evaluator commitment, check its proof, garble
+/// `circuit` on `garbler_bits` and send the bundle back. The garbler learns
+/// nothing, the output is only decoded by the evaluator.
+pub async fn run_garbler<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    circuit: &CircuitWrapper,
+    garbler_bits: Lsb0Bits,
+    setup: &TrinityWasmSetup,
+) -> Result<(), Error> {
+    let commitment = read_frame(stream).await?;
+    let (commitment, proof) = TrinityCom::deserialize_with_proof(&commitment)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
+    let proof =
+        proof.ok_or_else(|| Error::new(ErrorKind::InvalidData, "commitment has no proof"))?;
+
+    let circ = circuit.circuit.clone();
+    let num_bits = circuit.evaluator_input_size;
+    let trinity = setup.params.trinity.clone();
+    let bundle = spawn_blocking(move || {
+        trinity
+            .verify_commitment_proof(&commitment, &proof, num_bits)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
+        generate_garbled_circuit_with_randomness(
+            circ,
+            garbler_bits,
+            Randomness::Secure,
+            &trinity,
+            commitment,
+        )
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, e.to_string()))
+    })
+    .await
+    .map_err(Error::other)??;
+
+    write_frame(stream, &bundle.to_bytes()).await
+}
+
+/// Evaluator side: send the commitment to `evaluator_bits` with its proof,
+/// then evaluate the bundle the garbler answers with and return the output
+/// bits.
+pub async fn run_evaluator<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    circuit: &CircuitWrapper,
+    evaluator_bits: Lsb0Bits,
+    setup: &TrinityWasmSetup,
+) -> Result<Vec<bool>, Error> {
+    let params = setup.params.clone();
+    let bits = evaluator_bits.clone();
+    let (commitment, commitment_bytes) = spawn_blocking(move || {
+        let commitment = ev_commit(bits, &params)?;
+        let (com, proof) = commitment
+            .ot_receiver
+            .trinity_receiver
+            .commitment_with_proof();
+        let bytes = to_json(&SerializableTrinityCom::with_proof(com, proof))
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
+        Ok::<_, Error>((commitment, bytes))
+    })
+    .await
+    .map_err(Error::other)??;
+    write_frame(stream, &commitment_bytes).await?;
+
+    let bundle = read_frame(stream).await?;
+    let bundle = GarbledBundle::from_bytes(&bundle)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
+
+    let circ = circuit.circuit.clone();
+    spawn_blocking(move || evaluate_circuit(circ, bundle, evaluator_bits, &commitment.ot_receiver))
+        .await
+        .map_err(Error::other)?
+}
+
+async fn write_frame<S: AsyncWrite + Unpin>(stream: &mut S, bytes: &[u8]) -> Result<(), Error> {
+    stream.write_u64_le(bytes.len() as u64).await?;
+    stream.write_all(bytes).await?;
+    stream.flush().await
+}
+
+/// Read one frame. The length comes from the peer, so it is capped at
+/// `MAX_FRAME_LEN` and the buffer only grows as bytes arrive instead of
+/// being allocated up front.
+async fn read_frame<S: AsyncRead + Unpin>(stream: &mut S) -> Result<Vec<u8>, Error> {
+    let len = stream.read_u64_le().await?;
+    if len > MAX_FRAME_LEN {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("frame of {} bytes exceeds MAX_FRAME_LEN", len),
+        ));
+    }
+    let mut bytes = Vec::new();
+    stream.take(len).read_to_end(&mut bytes).await?;
+    if (bytes.len() as u64) < len {
+        return Err(Error::new(ErrorKind::UnexpectedEof, "frame is truncated"));
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_circuit;
+
+    const MODES: &[&str] = &[
+        #[cfg(feature = "plain")]
+        "Plain",
+        #[cfg(feature = "halo2")]
+        "Halo2",
+    ];
+
+    #[tokio::test]
+    async fn test_garbler_and_evaluator_over_duplex() {
+        let circuit =
+            parse_circuit(include_str!("../circuits/simple_16bit_add.txt"), 16, 16, 16).unwrap();
+
+        for &mode in MODES {
+            let setup = TrinityWasmSetup::new(mode).unwrap();
+            let (mut garbler_end, mut evaluator_end) = tokio::io::duplex(64 * 1024);
+
+            let (garbled, output) = tokio::join!(
+                run_garbler(&mut garbler_end, &circuit, Lsb0Bits::from_u16(6), &setup),
+                run_evaluator(&mut evaluator_end, &circuit, Lsb0Bits::from_u16(4), &setup),
+            );
+            garbled.unwrap();
+            assert_eq!(output.unwrap(), Lsb0Bits::from_u16(10).into_inner());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evaluator_rejects_truncated_bundle() {
+        let circuit =
+            parse_circuit(include_str!("../circuits/simple_16bit_add.txt"), 16, 16, 16).unwrap();
+        let setup = TrinityWasmSetup::new(MODES[0]).unwrap();
+        let (mut garbler_end, mut evaluator_end) = tokio::io::duplex(64 * 1024);
+
+        // Announce a longer bundle than is sent, then hang up
+        let garbler = async move {
+            read_frame(&mut garbler_end).await.unwrap();
+            garbler_end.write_u64_le(1024).await.unwrap();
+            garbler_end.write_all(&[0; 10]).await.unwrap();
+        };
+        let (_, output) = tokio::join!(
+            garbler,
+            run_evaluator(&mut evaluator_end, &circuit, Lsb0Bits::from_u16(4), &setup),
+        );
+        assert_eq!(output.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[tokio::test]
+    async fn test_read_frame_rejects_oversized_length() {
+        let (mut a, mut b) = tokio::io::duplex(64);
+        a.write_u64_le(MAX_FRAME_LEN + 1).await.unwrap();
+        assert_eq!(
+            read_frame(&mut b).await.unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+
+    #[tokio::test]
+    async fn test_garbler_rejects_commitment_without_valid_proof() {
+        let circuit =
+            parse_circuit(include_str!("../circuits/simple_16bit_add.txt"), 16, 16, 16).unwrap();
+
+        for &mode in MODES {
+            let setup = TrinityWasmSetup::new(mode).unwrap();
+            let commitment = ev_commit(Lsb0Bits::from_u16(4), &setup.params).unwrap();
+            let (com, mut proof) = commitment
+                .ot_receiver
+                .trinity_receiver
+                .commitment_with_proof();
+            let last = proof.len() - 1;
+            proof[last] ^= 1;
+            let tampered = to_json(&SerializableTrinityCom::with_proof(com, proof)).unwrap();
+
+            for frame in [com.serialize(), tampered] {
+                let (mut garbler_end, mut evaluator_end) = tokio::io::duplex(64 * 1024);
+                write_frame(&mut evaluator_end, &frame).await.unwrap();
+                let garbled =
+                    run_garbler(&mut garbler_end, &circuit, Lsb0Bits::from_u16(6), &setup).await;
+                assert_eq!(garbled.unwrap_err().kind(), ErrorKind::InvalidData);
+            }
+        }
+    }
+}
@@ -1,30 +1,338 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 
+#[cfg(feature = "plain")]
 use ark_bn254::{Bn254, Fr, G1Affine};
+#[cfg(feature = "plain")]
 use ark_poly::Radix2EvaluationDomain;
+#[cfg(feature = "plain")]
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+#[cfg(feature = "halo2")]
 use halo2_we_kzg::{
-    params::SerializableHalo2Params, Com as Halo2Com, Halo2Params, LaconicOTRecv as Halo2OTRecv,
-    LaconicOTSender as Halo2OTSender, LaconicParams,
+    params::SerializableHalo2Params, verify_kzg_commitment_with_halo2_proof,
+    verify_kzg_commitment_with_vk, Com as Halo2Com, Halo2Params, Halo2VerifierParams,
+    LaconicOTRecv as Halo2OTRecv, LaconicOTSender as Halo2OTSender, LaconicParams,
+    PendingRecv as Halo2PendingRecv,
 };
+#[cfg(feature = "halo2")]
+use halo2curves::{bn256::G1Affine as Halo2G1Affine, group::GroupEncoding};
+#[cfg(feature = "plain")]
 use laconic_ot::{
-    Com as PlainCom, CommitmentKey, LaconicOTRecv as PlainOTRecv, LaconicOTSender as PlainOTSender,
+    BitProof, Com as PlainCom, CommitmentKey, LaconicOTRecv as PlainOTRecv,
+    LaconicOTSender as PlainOTSender, PendingRecv as PlainPendingRecv,
+};
+use mpz_circuits::Circuit;
+use rand::{
+    rngs::{OsRng, StdRng},
+    CryptoRng, Rng, SeedableRng,
 };
-use rand::{rngs::OsRng, Rng};
 use serde::{Deserialize, Serialize};
 
 use std::sync::Arc;
+use zeroize::Zeroizing;
 
+use crate::encoding::Lsb0Bits;
+use crate::evaluate::pack_lsb0;
 use crate::ot::{KZGOTReceiver, KZGOTSender};
 
-const MSG_SIZE: usize = 16;
+/// Default OT message size, matching the 16-byte garbling wire labels
+pub const MSG_SIZE: usize = 16;
+
+/// Sender params bytes start with this magic, then `SENDER_BYTES_VERSION`,
+/// the mode tag byte (0 = Plain, 1 = Halo2, 2 = compressed Plain) and the
+/// 32-byte session id. Version 1 bytes, without the session id, are still
+/// read and get the all-zero id.
+pub const SENDER_BYTES_MAGIC: [u8; 4] = *b"TRNS";
+pub const SENDER_BYTES_VERSION: u8 = 2;
+
+/// Full params bytes start with this magic, then `FULL_PARAMS_VERSION`, the
+/// mode tag byte and the 32-byte session id. Bytes without the header, the
+/// tag directly followed by the params, are still read and get the all-zero
+/// id.
+pub const FULL_PARAMS_MAGIC: [u8; 4] = *b"TRNF";
+pub const FULL_PARAMS_VERSION: u8 = 1;
+
+/// Range of `k` accepted by `Trinity::setup_with_degree`, which supports
+/// `1 << k` OT positions
+pub const MIN_SETUP_DEGREE: u32 = 4;
+pub const MAX_SETUP_DEGREE: u32 = 24;
+
+/// Also applied to the `k` of deserialized Halo2 params, which the backend
+/// only bounds by what `1 << k` and the evaluation domain can hold
+fn check_degree(k: u32) -> Result<(), TrinityError> {
+    if !(MIN_SETUP_DEGREE..=MAX_SETUP_DEGREE).contains(&k) {
+        return Err(TrinityError::InvalidDegree(k));
+    }
+    Ok(())
+}
+
+/// Length of `Trinity::to_full_params_bytes` for params of degree `k`,
+/// computed from the point counts without generating anything, so a browser
+/// can warn before a large download or setup. Exact for the current
+/// encodings, including the header.
+pub fn estimate_params_bytes(mode: KZGType, k: usize) -> usize {
+    let backend = match mode {
+        #[cfg(feature = "plain")]
+        KZGType::Plain => {
+            // `CommitmentKey` fields in order, every Vec behind a u64 length
+            let n = 1usize << k;
+            let g1 = G1Affine::default().uncompressed_size();
+            let g2 = ark_bn254::G2Affine::default().uncompressed_size();
+            let domain = <Radix2EvaluationDomain<Fr> as ark_poly::EvaluationDomain<Fr>>::new(n)
+                .map_or(0, |domain| domain.uncompressed_size());
+
+            8 // message_length
+                + domain
+                + 3 * (8 + n * g1) // u, hat_u, lagranges
+                + 2 * g2 // g2, r
+                + (8 + n * g2) // d
+                + (8 + 2 * n * g1) // y
+        }
+        #[cfg(feature = "halo2")]
+        KZGType::Halo2 => halo2_we_kzg::estimate_params_bytes(k),
+    };
+
+    FULL_PARAMS_MAGIC.len() + 2 + 32 + backend
+}
+
+/// Errors returned when building, (de)serializing or pairing Trinity parameters
+/// and commitments
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TrinityError {
+    /// The input byte buffer was empty
+    EmptyBytes,
+    /// The leading tag byte does not name a known mode
+    InvalidTagByte(u8),
+    /// The Plain `CommitmentKey` could not be deserialized
+    DeserializeCommitmentKey,
+    /// The full Halo2 parameters could not be deserialized
+    DeserializeHalo2Params,
+    /// The Halo2 sender parameters could not be deserialized
+    DeserializeLaconicParams,
+    /// The evaluator commitment could not be deserialized
+    DeserializeCommitment,
+    /// An OT message could not be deserialized
+    DeserializeMessage,
+    /// The commitment was produced under a different mode than the parameters
+    MismatchedCommitmentType,
+    /// An OT receiver needs the full parameters, not the sender-only ones
+    CreateReceiverFromSenderParams,
+    /// An OT receiver needs the full parameters, not the verifier-only ones
+    CreateReceiverFromVerifierParams,
+    /// Plain parameters or full or verifier Halo2 parameters are needed to
+    /// check commitment proofs
+    NoVerifierParams,
+    /// The Halo2 verifier parameters could not be deserialized
+    DeserializeVerifierParams,
+    /// The commitment proof did not verify
+    InvalidProof,
+    /// The data belongs to a backend that was not compiled in
+    UnsupportedMode,
+    /// The input ended before a complete value could be read
+    TruncatedInput,
+    /// Reading the input failed
+    Io(String),
+    /// The sender params bytes don't start with `SENDER_BYTES_MAGIC`
+    BadMagic,
+    /// The sender params bytes use a format version this build can't read
+    UnsupportedVersion(u8),
+    /// More input bits than the parameters have OT positions
+    InputTooLong { len: usize, capacity: usize },
+    /// An OT message index is past the end of the committed bits
+    IndexOutOfRange { index: usize, len: usize },
+    /// The value committed at `index` has no branch among `arity` messages
+    SelectionOutOfRange {
+        index: usize,
+        selection: usize,
+        arity: usize,
+    },
+    /// The setup degree is outside `MIN_SETUP_DEGREE..=MAX_SETUP_DEGREE`
+    InvalidDegree(u32),
+    /// The commitment was made under `got`, the parameters are for `expected`
+    ModeMismatch { expected: KZGType, got: KZGType },
+    /// A `TrinityBuilder` is missing a setting or has conflicting ones
+    InvalidSetupConfig(&'static str),
+    /// A value could not be serialized to JSON
+    Serialize(String),
+    /// `CircuitWrapper::compose` could not chain the two circuits
+    InvalidComposition(String),
+    /// `CircuitWrapper::with_constants` could not fix the given input wires
+    InvalidConstants(String),
+    /// The evaluator committed over a different evaluation domain than the
+    /// sender's params
+    DomainMismatch,
+    /// The Halo2 verifier params were made for proofs over a different
+    /// number of bits
+    InvalidProofLength,
+    /// The receiver's openings could not be computed from the parameters
+    Openings(String),
+}
+
+impl std::fmt::Display for TrinityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrinityError::EmptyBytes => write!(f, "Empty bytes"),
+            TrinityError::InvalidTagByte(tag) => write!(f, "Invalid tag byte: {}", tag),
+            TrinityError::DeserializeCommitmentKey => {
+                write!(f, "Failed to deserialize CommitmentKey")
+            }
+            TrinityError::DeserializeHalo2Params => write!(f, "Failed to deserialize Halo2Params"),
+            TrinityError::DeserializeLaconicParams => {
+                write!(f, "Failed to deserialize LaconicParams")
+            }
+            TrinityError::DeserializeCommitment => write!(f, "Failed to deserialize commitment"),
+            TrinityError::DeserializeMessage => write!(f, "Failed to deserialize OT message"),
+            TrinityError::MismatchedCommitmentType => write!(f, "Mismatched commitment type"),
+            TrinityError::CreateReceiverFromSenderParams => {
+                write!(f, "Cannot create receiver from sender params")
+            }
+            TrinityError::CreateReceiverFromVerifierParams => {
+                write!(f, "Cannot create receiver from verifier params")
+            }
+            TrinityError::NoVerifierParams => {
+                write!(f, "No params available to verify the commitment proof")
+            }
+            TrinityError::DeserializeVerifierParams => {
+                write!(f, "Failed to deserialize Halo2VerifierParams")
+            }
+            TrinityError::InvalidProof => write!(f, "Invalid commitment proof"),
+            TrinityError::UnsupportedMode => write!(f, "Mode not enabled in this build"),
+            TrinityError::TruncatedInput => write!(f, "Input is truncated"),
+            TrinityError::Io(e) => write!(f, "I/O error: {}", e),
+            TrinityError::BadMagic => write!(f, "Not Trinity sender params (bad magic)"),
+            TrinityError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported sender params version: {}", version)
+            }
+            TrinityError::InputTooLong { len, capacity } => write!(
+                f,
+                "Input has {} bits, params support at most {}",
+                len, capacity
+            ),
+            TrinityError::IndexOutOfRange { index, len } => write!(
+                f,
+                "OT index {} out of range for {} committed bits",
+                index, len
+            ),
+            TrinityError::SelectionOutOfRange {
+                index,
+                selection,
+                arity,
+            } => write!(
+                f,
+                "Selection {} at OT index {} out of range for {} messages",
+                selection, index, arity
+            ),
+            TrinityError::InvalidDegree(k) => write!(
+                f,
+                "Invalid degree {}, expected {} to {}",
+                k, MIN_SETUP_DEGREE, MAX_SETUP_DEGREE
+            ),
+            TrinityError::ModeMismatch { expected, got } => write!(
+                f,
+                "Commitment is for {} mode, parameters are for {} mode",
+                got, expected
+            ),
+            TrinityError::InvalidSetupConfig(reason) => {
+                write!(f, "Invalid setup configuration: {}", reason)
+            }
+            TrinityError::Serialize(e) => write!(f, "Serialization failed: {}", e),
+            TrinityError::InvalidComposition(e) => write!(f, "Cannot compose circuits: {}", e),
+            TrinityError::InvalidConstants(e) => write!(f, "Cannot fix circuit inputs: {}", e),
+            TrinityError::DomainMismatch => {
+                write!(f, "Commitment was made over a different evaluation domain")
+            }
+            TrinityError::Openings(e) => write!(f, "Cannot compute the OT openings: {}", e),
+            TrinityError::InvalidProofLength => {
+                write!(
+                    f,
+                    "Verifier params are for proofs over a different number of bits"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for TrinityError {}
+
+impl From<serde_json::Error> for TrinityError {
+    fn from(e: serde_json::Error) -> Self {
+        TrinityError::Serialize(e.to_string())
+    }
+}
+
+#[cfg(feature = "plain")]
+impl From<laconic_ot::RecvError> for TrinityError {
+    fn from(e: laconic_ot::RecvError) -> Self {
+        match e {
+            laconic_ot::RecvError::IndexOutOfRange { index, len } => {
+                TrinityError::IndexOutOfRange { index, len }
+            }
+            laconic_ot::RecvError::SelectionOutOfRange {
+                index,
+                selection,
+                arity,
+            } => TrinityError::SelectionOutOfRange {
+                index,
+                selection,
+                arity,
+            },
+        }
+    }
+}
+
+/// JSON encoding shared by the `try_serialize` methods
+pub(crate) fn to_json<T: Serialize>(value: &T) -> Result<Vec<u8>, TrinityError> {
+    Ok(serde_json::to_vec(value)?)
+}
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum TrinityChoice {
+    #[default]
     Zero,
     One,
 }
 
+// Choices are the evaluator's private input, zeroizing resets them to `Zero`
+impl zeroize::DefaultIsZeroes for TrinityChoice {}
+
+impl From<bool> for TrinityChoice {
+    fn from(bit: bool) -> Self {
+        if bit {
+            TrinityChoice::One
+        } else {
+            TrinityChoice::Zero
+        }
+    }
+}
+
+impl TrinityChoice {
+    /// Pack choices into bytes in the LSB0 order of `Lsb0Bits`: choice `i` is
+    /// bit `i % 8` of byte `i / 8`, and the last byte is zero-padded
+    pub fn pack(choices: &[TrinityChoice]) -> Vec<u8> {
+        let bits: Vec<bool> = choices.iter().map(|&c| c == TrinityChoice::One).collect();
+        pack_lsb0(&bits)
+    }
+
+    /// Inverse of `pack`, reading the first `bit_len` choices from `bytes`.
+    ///
+    /// Panics if `bytes` holds fewer than `bit_len` bits.
+    pub fn unpack(bytes: &[u8], bit_len: usize) -> Vec<TrinityChoice> {
+        assert!(
+            bit_len <= bytes.len() * 8,
+            "{} bytes can't hold {} choices",
+            bytes.len(),
+            bit_len
+        );
+        Lsb0Bits::from_bytes(bytes).as_slice()[..bit_len]
+            .iter()
+            .map(|&bit| TrinityChoice::from(bit))
+            .collect()
+    }
+}
+
+#[cfg(feature = "plain")]
 impl From<laconic_ot::Choice> for TrinityChoice {
     fn from(ch: laconic_ot::Choice) -> Self {
         match ch {
@@ -34,6 +342,7 @@ impl From<laconic_ot::Choice> for TrinityChoice {
     }
 }
 
+#[cfg(feature = "plain")]
 impl From<TrinityChoice> for laconic_ot::Choice {
     fn from(ch: TrinityChoice) -> Self {
         match ch {
@@ -43,6 +352,17 @@ impl From<TrinityChoice> for laconic_ot::Choice {
     }
 }
 
+#[cfg(feature = "halo2")]
+impl From<halo2_we_kzg::Choice> for TrinityChoice {
+    fn from(ch: halo2_we_kzg::Choice) -> Self {
+        match ch {
+            halo2_we_kzg::Choice::Zero => TrinityChoice::Zero,
+            halo2_we_kzg::Choice::One => TrinityChoice::One,
+        }
+    }
+}
+
+#[cfg(feature = "halo2")]
 impl From<TrinityChoice> for halo2_we_kzg::Choice {
     fn from(ch: TrinityChoice) -> Self {
         match ch {
@@ -52,112 +372,552 @@ impl From<TrinityChoice> for halo2_we_kzg::Choice {
     }
 }
 
-#[derive(Serialize)]
+/// Backends compiled in through the `plain` and `halo2` cargo features
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum KZGType {
+    #[cfg(feature = "plain")]
     Plain,
+    #[cfg(feature = "halo2")]
     Halo2,
 }
 
+/// Error returned when parsing a `KZGType` from a string
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseModeError {
+    /// The mode exists but its backend was not compiled in
+    NotEnabled(&'static str),
+    /// The string names no mode
+    Unknown(String),
+}
+
+impl fmt::Display for ParseModeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseModeError::NotEnabled(mode) => {
+                write!(f, "{} mode is not enabled in this build", mode)
+            }
+            ParseModeError::Unknown(mode) => {
+                write!(f, "Unknown mode '{}', expected 'Plain' or 'Halo2'", mode)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseModeError {}
+
+impl std::str::FromStr for KZGType {
+    type Err = ParseModeError;
+
+    /// Match `s` case-insensitively against `"Plain"` and `"Halo2"`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            #[cfg(feature = "plain")]
+            "plain" => Ok(KZGType::Plain),
+            #[cfg(feature = "halo2")]
+            "halo2" => Ok(KZGType::Halo2),
+            #[cfg(not(feature = "plain"))]
+            "plain" => Err(ParseModeError::NotEnabled("Plain")),
+            #[cfg(not(feature = "halo2"))]
+            "halo2" => Err(ParseModeError::NotEnabled("Halo2")),
+            _ => Err(ParseModeError::Unknown(s.to_string())),
+        }
+    }
+}
+
+/// Canonical mode name, accepted back by `FromStr`
+impl fmt::Display for KZGType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "plain")]
+            KZGType::Plain => write!(f, "Plain"),
+            #[cfg(feature = "halo2")]
+            KZGType::Halo2 => write!(f, "Halo2"),
+        }
+    }
+}
+
+/// Full parameters, shared behind `Arc` so clones are cheap.
+/// `Send + Sync`, see the assertions at the bottom of `two_pc.rs`.
 #[derive(Clone)]
 pub enum TrinityParams {
+    #[cfg(feature = "plain")]
     Plain(Arc<CommitmentKey<Bn254, Radix2EvaluationDomain<Fr>>>),
+    #[cfg(feature = "halo2")]
     Halo2(Arc<Halo2Params>),
 }
 
 #[derive(Clone)]
 pub enum TrinitySenderParams {
+    #[cfg(feature = "plain")]
     Plain(Arc<CommitmentKey<Bn254, Radix2EvaluationDomain<Fr>>>),
+    #[cfg(feature = "halo2")]
     Halo2(Arc<LaconicParams>),
 }
 
+#[derive(Clone)]
 pub enum TrinityInnerParams {
     // Full parameters (for evaluator)
     Full(TrinityParams),
     // Minimal parameters (for garbler/sender)
     Sender(TrinitySenderParams),
+    // Enough to check a Halo2 commitment proof, but not to create a receiver
+    #[cfg(feature = "halo2")]
+    Verifier(Arc<Halo2VerifierParams>),
+}
+
+impl TrinityInnerParams {
+    /// Mode of the params themselves, which `create_ot_sender` checks
+    /// commitments against rather than trusting `Trinity::mode`
+    pub fn mode(&self) -> KZGType {
+        match self {
+            TrinityInnerParams::Full(params) => params.mode(),
+            TrinityInnerParams::Sender(sender_params) => sender_params.mode(),
+            #[cfg(feature = "halo2")]
+            TrinityInnerParams::Verifier(_) => KZGType::Halo2,
+        }
+    }
+}
+
+impl fmt::Debug for TrinityInnerParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrinityInnerParams::Full(params) => f.debug_tuple("Full").field(params).finish(),
+            TrinityInnerParams::Sender(params) => f.debug_tuple("Sender").field(params).finish(),
+            #[cfg(feature = "halo2")]
+            TrinityInnerParams::Verifier(params) => {
+                f.debug_struct("Verifier").field("k", &params.k).finish()
+            }
+        }
+    }
 }
 
+/// Evaluator commitment, a plain curve point that is `Copy + Send + Sync`,
+/// with the `domain_fingerprint` of the receiver. A sender on params of
+/// another size fails instead of sending messages the receiver can't open.
 #[derive(Clone, Copy)]
 pub enum TrinityCom {
-    Plain(PlainCom<Bn254>),
-    Halo2(Halo2Com),
+    #[cfg(feature = "plain")]
+    Plain(PlainCom<Bn254>, [u8; 32]),
+    #[cfg(feature = "halo2")]
+    Halo2(Halo2Com, [u8; 32]),
+}
+
+impl PartialEq for TrinityCom {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            #[cfg(feature = "plain")]
+            (TrinityCom::Plain(a, a_domain), TrinityCom::Plain(b, b_domain)) => {
+                a == b && a_domain == b_domain
+            }
+            #[cfg(feature = "halo2")]
+            (TrinityCom::Halo2(a, a_domain), TrinityCom::Halo2(b, b_domain)) => {
+                a == b && a_domain == b_domain
+            }
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+}
+
+impl Eq for TrinityCom {}
+
+impl Hash for TrinityCom {
+    // Both commitments are projective points, so hash the canonical compressed
+    // affine encoding to keep equal points hashing alike
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            #[cfg(feature = "plain")]
+            TrinityCom::Plain(com, domain) => {
+                let mut bytes = Vec::new();
+                com.serialize_compressed(&mut bytes).unwrap();
+                0u8.hash(state);
+                bytes.hash(state);
+                domain.hash(state);
+            }
+            #[cfg(feature = "halo2")]
+            TrinityCom::Halo2(com, domain) => {
+                1u8.hash(state);
+                Halo2G1Affine::from(*com).to_bytes().as_ref().hash(state);
+                domain.hash(state);
+            }
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
 pub enum SerializableTrinityCom {
-    Plain(Vec<u8>), // Compressed G1
-    Halo2(Vec<u8>), // halo2 Com
+    Plain(SerializablePlainCom), // Compressed G1, optionally with its bit proof
+    Halo2(SerializableHalo2Com), // halo2 Com, optionally with its proof
+}
+
+/// Plain commitment bytes, laid out like `SerializableHalo2Com` and likewise
+/// rejected without a domain
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SerializablePlainCom {
+    WithProof {
+        com: Vec<u8>,
+        proof: Option<Vec<u8>>,
+        #[serde(default)]
+        domain: Option<[u8; 32]>,
+    },
+    Bare(Vec<u8>),
+}
+
+/// Halo2 commitment bytes with the receiver's domain fingerprint. Older peers
+/// skip the `domain` field, but commitments from them, bare or without a
+/// domain, are rejected: a sender could not check them.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SerializableHalo2Com {
+    WithProof {
+        com: Vec<u8>,
+        proof: Option<Vec<u8>>,
+        #[serde(default)]
+        domain: Option<[u8; 32]>,
+    },
+    Bare(Vec<u8>),
+}
+
+impl SerializableTrinityCom {
+    /// Attach the proof that the commitment is made of bits
+    pub fn with_proof(com: TrinityCom, proof: Vec<u8>) -> Self {
+        match com.into() {
+            SerializableTrinityCom::Plain(SerializablePlainCom::WithProof {
+                com, domain, ..
+            }) => SerializableTrinityCom::Plain(SerializablePlainCom::WithProof {
+                com,
+                proof: Some(proof),
+                domain,
+            }),
+            SerializableTrinityCom::Halo2(SerializableHalo2Com::WithProof {
+                com, domain, ..
+            }) => SerializableTrinityCom::Halo2(SerializableHalo2Com::WithProof {
+                com,
+                proof: Some(proof),
+                domain,
+            }),
+            bare => bare,
+        }
+    }
+
+    pub fn proof(&self) -> Option<&[u8]> {
+        match self {
+            SerializableTrinityCom::Plain(SerializablePlainCom::WithProof {
+                proof: Some(proof),
+                ..
+            })
+            | SerializableTrinityCom::Halo2(SerializableHalo2Com::WithProof {
+                proof: Some(proof),
+                ..
+            }) => Some(proof),
+            _ => None,
+        }
+    }
+
+    /// Mode tag, point bytes and domain fingerprint, or `None` for
+    /// commitments without a domain
+    fn parts(&self) -> Option<(u8, &[u8], &[u8; 32])> {
+        match self {
+            SerializableTrinityCom::Plain(SerializablePlainCom::WithProof {
+                com,
+                domain: Some(domain),
+                ..
+            }) => Some((0, &com[..], domain)),
+            SerializableTrinityCom::Halo2(SerializableHalo2Com::WithProof {
+                com,
+                domain: Some(domain),
+                ..
+            }) => Some((1, &com[..], domain)),
+            _ => None,
+        }
+    }
 }
 
 impl From<TrinityCom> for SerializableTrinityCom {
     fn from(com: TrinityCom) -> Self {
         match com {
-            TrinityCom::Plain(g1) => {
+            #[cfg(feature = "plain")]
+            TrinityCom::Plain(g1, domain) => {
                 let mut bytes = Vec::new();
                 g1.serialize_compressed(&mut bytes).unwrap();
-                SerializableTrinityCom::Plain(bytes)
+                SerializableTrinityCom::Plain(SerializablePlainCom::WithProof {
+                    com: bytes,
+                    proof: None,
+                    domain: Some(domain),
+                })
             }
-            TrinityCom::Halo2(halo2_com) => {
-                let bytes = bincode::serialize(&halo2_com).unwrap();
-                SerializableTrinityCom::Halo2(bytes)
+            #[cfg(feature = "halo2")]
+            TrinityCom::Halo2(halo2_com, domain) => {
+                SerializableTrinityCom::Halo2(SerializableHalo2Com::WithProof {
+                    com: bincode::serialize(&halo2_com).unwrap(),
+                    proof: None,
+                    domain: Some(domain),
+                })
             }
         }
     }
 }
 
 impl TryFrom<SerializableTrinityCom> for TrinityCom {
-    type Error = &'static str;
+    type Error = TrinityError;
 
     fn try_from(value: SerializableTrinityCom) -> Result<Self, Self::Error> {
         match value {
-            SerializableTrinityCom::Plain(bytes) => {
+            #[cfg(feature = "plain")]
+            SerializableTrinityCom::Plain(SerializablePlainCom::WithProof {
+                com: bytes,
+                domain: Some(domain),
+                ..
+            }) => {
                 let g1 = G1Affine::deserialize_compressed(&*bytes)
-                    .map_err(|_| "Failed to deserialize PlainCom")?;
-                Ok(TrinityCom::Plain(g1.into()))
+                    .map_err(|_| TrinityError::DeserializeCommitment)?;
+                Ok(TrinityCom::Plain(g1.into(), domain))
             }
-            SerializableTrinityCom::Halo2(bytes) => {
-                let com: Halo2Com =
-                    bincode::deserialize(&bytes).map_err(|_| "Failed to deserialize Halo2Com")?;
-                Ok(TrinityCom::Halo2(com))
+            #[cfg(feature = "plain")]
+            SerializableTrinityCom::Plain(_) => Err(TrinityError::DeserializeCommitment),
+            #[cfg(feature = "halo2")]
+            SerializableTrinityCom::Halo2(SerializableHalo2Com::WithProof {
+                com: bytes,
+                domain: Some(domain),
+                ..
+            }) => {
+                let com: Halo2Com = bincode::deserialize(&bytes)
+                    .map_err(|_| TrinityError::DeserializeCommitment)?;
+                Ok(TrinityCom::Halo2(com, domain))
             }
+            #[cfg(feature = "halo2")]
+            SerializableTrinityCom::Halo2(_) => Err(TrinityError::DeserializeCommitment),
+            #[allow(unreachable_patterns)]
+            _ => Err(TrinityError::UnsupportedMode),
         }
     }
 }
 
 impl TrinityCom {
+    /// Mode the commitment was made under
+    pub fn mode(&self) -> KZGType {
+        match self {
+            #[cfg(feature = "plain")]
+            TrinityCom::Plain(..) => KZGType::Plain,
+            #[cfg(feature = "halo2")]
+            TrinityCom::Halo2(..) => KZGType::Halo2,
+        }
+    }
+
+    /// `domain_fingerprint` of the receiver that made the commitment
+    pub fn domain(&self) -> [u8; 32] {
+        match self {
+            #[cfg(feature = "plain")]
+            TrinityCom::Plain(_, domain) => *domain,
+            #[cfg(feature = "halo2")]
+            TrinityCom::Halo2(_, domain) => *domain,
+        }
+    }
+
     pub fn serialize(&self) -> Vec<u8> {
-        let serializable: SerializableTrinityCom = (*self).into();
-        serde_json::to_vec(&serializable).expect("JSON serialization failed")
+        self.try_serialize().expect("JSON serialization failed")
     }
 
-    pub fn deserialize(data: &[u8]) -> Result<Self, &'static str> {
-        let serializable: SerializableTrinityCom =
-            serde_json::from_slice(data).map_err(|_| "JSON deserialization failed")?;
+    /// Like `serialize`, returning serde errors instead of panicking
+    pub fn try_serialize(&self) -> Result<Vec<u8>, TrinityError> {
+        to_json(&SerializableTrinityCom::from(*self))
+    }
+
+    pub fn deserialize(data: &[u8]) -> Result<Self, TrinityError> {
+        Self::deserialize_with_proof(data).map(|(com, _)| com)
+    }
+
+    /// One mode tag byte (0 Plain, 1 Halo2) followed by the same point bytes
+    /// `serialize` wraps in JSON, then the 32-byte domain fingerprint. The
+    /// bit proof is not included.
+    pub fn to_tagged_bytes(&self) -> Vec<u8> {
+        let serializable = SerializableTrinityCom::from(*self);
+        let (tag, com, domain) = serializable
+            .parts()
+            .expect("commitments carry their domain");
+        [&[tag][..], com, domain].concat()
+    }
+
+    pub fn from_tagged_bytes(bytes: &[u8]) -> Result<Self, TrinityError> {
+        let (&tag, rest) = bytes
+            .split_first()
+            .ok_or(TrinityError::DeserializeCommitment)?;
+        let (com, domain) = rest
+            .split_last_chunk::<32>()
+            .ok_or(TrinityError::DeserializeCommitment)?;
+        let (com, domain) = (com.to_vec(), Some(*domain));
+        let serializable = match tag {
+            0 => SerializableTrinityCom::Plain(SerializablePlainCom::WithProof {
+                com,
+                proof: None,
+                domain,
+            }),
+            1 => SerializableTrinityCom::Halo2(SerializableHalo2Com::WithProof {
+                com,
+                proof: None,
+                domain,
+            }),
+            _ => return Err(TrinityError::DeserializeCommitment),
+        };
         TrinityCom::try_from(serializable)
     }
+
+    /// Lowercase hex of `to_tagged_bytes`
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.to_tagged_bytes())
+    }
+
+    pub fn from_hex(s: &str) -> Result<Self, TrinityError> {
+        let bytes = hex::decode(s).map_err(|_| TrinityError::DeserializeCommitment)?;
+        Self::from_tagged_bytes(&bytes)
+    }
+
+    /// Unpadded URL-safe base64 of `to_tagged_bytes`, fit for URLs and QR codes
+    pub fn to_base64url(&self) -> String {
+        URL_SAFE_NO_PAD.encode(self.to_tagged_bytes())
+    }
+
+    pub fn from_base64url(s: &str) -> Result<Self, TrinityError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(s)
+            .map_err(|_| TrinityError::DeserializeCommitment)?;
+        Self::from_tagged_bytes(&bytes)
+    }
+
+    /// The commitment point alone as a 32-byte compressed BN254 G1 encoding,
+    /// for contracts and other verifiers that take a fixed-size point rather
+    /// than JSON: arkworks' `serialize_compressed` for Plain, the halo2curves
+    /// `GroupEncoding` bytes for Halo2.
+    pub fn to_g1_bytes(&self) -> Option<[u8; 32]> {
+        let mut bytes = [0u8; 32];
+        match self {
+            #[cfg(feature = "plain")]
+            TrinityCom::Plain(g1, _) => g1.serialize_compressed(&mut bytes[..]).ok()?,
+            #[cfg(feature = "halo2")]
+            TrinityCom::Halo2(com, _) => {
+                bytes.copy_from_slice(Halo2G1Affine::from(*com).to_bytes().as_ref())
+            }
+        }
+        Some(bytes)
+    }
+
+    /// Like `deserialize`, also returning the bit proof if one was attached
+    pub fn deserialize_with_proof(data: &[u8]) -> Result<(Self, Option<Vec<u8>>), TrinityError> {
+        let serializable: SerializableTrinityCom =
+            serde_json::from_slice(data).map_err(|_| TrinityError::DeserializeCommitment)?;
+        let proof = serializable.proof().map(<[u8]>::to_vec);
+        Ok((TrinityCom::try_from(serializable)?, proof))
+    }
 }
 
-pub enum TrinityReceiver<'a> {
-    Plain(PlainOTRecv<'a, Bn254, Radix2EvaluationDomain<Fr>>),
+#[derive(Clone)]
+pub enum TrinityReceiver {
+    #[cfg(feature = "plain")]
+    Plain(PlainOTRecv<Bn254, Radix2EvaluationDomain<Fr>>),
+    #[cfg(feature = "halo2")]
     Halo2(Halo2OTRecv),
 }
 
+/// An OT receiver whose commitment is ready but whose openings are not, from
+/// `Trinity::commit_only`. Publish `commitment` first and call
+/// `prepare_openings` before the OT messages arrive.
+pub enum PendingTrinityReceiver {
+    #[cfg(feature = "plain")]
+    Plain(
+        PlainPendingRecv<Bn254, Radix2EvaluationDomain<Fr>>,
+        Arc<CommitmentKey<Bn254, Radix2EvaluationDomain<Fr>>>,
+    ),
+    #[cfg(feature = "halo2")]
+    Halo2(Halo2PendingRecv),
+}
+
 pub enum TrinitySender<'a> {
+    #[cfg(feature = "plain")]
     Plain(PlainOTSender<'a, Bn254, Radix2EvaluationDomain<Fr>>),
-    Halo2(Halo2OTSender),
+    /// Only the Plain sender borrows its params, the marker keeps `'a` used
+    /// when the Halo2 backend is compiled in alone
+    #[cfg(feature = "halo2")]
+    Halo2(Halo2OTSender, PhantomData<&'a ()>),
 }
 
+/// Trinity parameters for one mode.
+///
+/// All parameter data sits behind `Arc` and is never mutated after setup, so a
+/// `Trinity` is `Send + Sync` and can be shared across threads, e.g. a server
+/// garbling for many clients concurrently.
+///
+/// `Debug` prints the mode, size and a fingerprint rather than the params,
+/// and a prefix of the session id. Cloning shares the params, see
+/// `with_session_id`.
+#[derive(Clone)]
 pub struct Trinity {
     pub mode: KZGType,
     pub params: TrinityInnerParams,
+    /// How the params were generated, `Secure` for deserialized params
+    pub randomness: Randomness,
+    /// Mixed into every OT keystream, so sessions over the same params never
+    /// share a pad. Random unless set with `with_session_id`; the garbler
+    /// gets it from the sender bytes.
+    pub session_id: [u8; 32],
+}
+
+impl fmt::Debug for Trinity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Trinity")
+            .field("mode", &self.mode)
+            .field("params", &self.params)
+            .field("randomness", &self.randomness)
+            .field("session_id", &hex::encode(&self.session_id[..8]))
+            .finish()
+    }
+}
+
+/// Source of setup or garbling randomness.
+///
+/// `Deterministic` is for reproducible tests only: anyone who knows the seed
+/// can recompute the trapdoor or the wire labels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Randomness {
+    /// Seeded from OS entropy
+    #[default]
+    Secure,
+    /// Seeded from a fixed value
+    Deterministic { seed: u64 },
+    /// Drawn from a caller-supplied rng, e.g. `Trinity::setup_with_rng`.
+    /// Anyone who can replay that rng gets the same output.
+    Injected,
+}
+
+impl Randomness {
+    /// Whether someone other than this process may be able to reproduce the
+    /// output: a fixed seed, or an rng the caller supplied
+    pub fn is_deterministic(&self) -> bool {
+        matches!(
+            self,
+            Randomness::Deterministic { .. } | Randomness::Injected
+        )
+    }
+
+    /// Fresh rng for this source. `Injected` has no rng of its own to replay
+    /// and draws from OS entropy like `Secure`.
+    pub fn rng(&self) -> StdRng {
+        match self {
+            Randomness::Secure | Randomness::Injected => StdRng::from_entropy(),
+            Randomness::Deterministic { seed } => StdRng::seed_from_u64(*seed),
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
-pub enum TrinityMsg {
-    Plain(laconic_ot::Msg<Bn254>),
-    Halo2(halo2_we_kzg::Msg),
+pub enum TrinityMsg<const N: usize = MSG_SIZE> {
+    #[cfg(feature = "plain")]
+    Plain(laconic_ot::Msg<Bn254, N>),
+    #[cfg(feature = "halo2")]
+    Halo2(halo2_we_kzg::Msg<N>),
 }
 
+#[cfg(feature = "plain")]
 #[derive(Serialize, Deserialize)]
 pub struct SerializablePlainParams {
     pub commitment_key_bytes: Vec<u8>,
@@ -165,10 +925,13 @@ pub struct SerializablePlainParams {
 
 #[derive(Serialize, Deserialize)]
 pub enum TrinitySerializableParams {
+    #[cfg(feature = "plain")]
     Plain(SerializablePlainParams),
+    #[cfg(feature = "halo2")]
     Halo2(SerializableHalo2Params),
 }
 
+#[cfg(feature = "plain")]
 impl From<&CommitmentKey<Bn254, Radix2EvaluationDomain<Fr>>> for SerializablePlainParams {
     fn from(ck: &CommitmentKey<Bn254, Radix2EvaluationDomain<Fr>>) -> Self {
         let mut bytes = Vec::new();
@@ -181,19 +944,22 @@ impl From<&CommitmentKey<Bn254, Radix2EvaluationDomain<Fr>>> for SerializablePla
     }
 }
 
+#[cfg(feature = "plain")]
 impl TryFrom<SerializablePlainParams> for CommitmentKey<Bn254, Radix2EvaluationDomain<Fr>> {
-    type Error = &'static str;
+    type Error = TrinityError;
 
     fn try_from(s: SerializablePlainParams) -> Result<Self, Self::Error> {
         CommitmentKey::deserialize_uncompressed(&mut &s.commitment_key_bytes[..])
-            .map_err(|_| "Failed to deserialize CommitmentKey")
+            .map_err(|_| TrinityError::DeserializeCommitmentKey)
     }
 }
 
 impl TrinityParams {
     pub fn to_sender_params(&self) -> TrinitySenderParams {
         match self {
+            #[cfg(feature = "plain")]
             TrinityParams::Plain(ck) => TrinitySenderParams::Plain(ck.clone()),
+            #[cfg(feature = "halo2")]
             TrinityParams::Halo2(params) => {
                 // Extract LaconicParams from Halo2Params
                 // As the garbler doesn't need the full Halo2Params
@@ -204,17 +970,211 @@ impl TrinityParams {
     }
 }
 
+impl TrinitySenderParams {
+    /// Mode of the commitments these params can send to
+    pub fn mode(&self) -> KZGType {
+        match self {
+            #[cfg(feature = "plain")]
+            TrinitySenderParams::Plain(_) => KZGType::Plain,
+            #[cfg(feature = "halo2")]
+            TrinitySenderParams::Halo2(_) => KZGType::Halo2,
+        }
+    }
+
+    /// Number of OT positions, see `Trinity::capacity`
+    pub fn capacity(&self) -> usize {
+        match self {
+            #[cfg(feature = "plain")]
+            TrinitySenderParams::Plain(ck) => ark_poly::EvaluationDomain::size(&ck.domain),
+            #[cfg(feature = "halo2")]
+            TrinitySenderParams::Halo2(laconic_params) => 1 << laconic_params.k,
+        }
+    }
+
+    /// Like `to_tagged_bytes`, with the Plain `CommitmentKey` in arkworks'
+    /// compressed form under tag `2`. Halo2 params are encoded as usual.
+    fn to_tagged_bytes_compressed(&self) -> Vec<u8> {
+        match self {
+            #[cfg(feature = "plain")]
+            TrinitySenderParams::Plain(ck) => {
+                let mut bytes = vec![2]; // Tag byte for compressed Plain
+                ck.serialize_compressed(&mut bytes)
+                    .expect("Serialization failed");
+                bytes
+            }
+            #[allow(unreachable_patterns)]
+            _ => self.to_tagged_bytes(),
+        }
+    }
+
+    /// Mode tag byte followed by the backend encoding of the params
+    fn to_tagged_bytes(&self) -> Vec<u8> {
+        match self {
+            #[cfg(feature = "plain")]
+            TrinitySenderParams::Plain(ck) => {
+                let mut bytes = vec![0]; // Tag byte for Plain
+                let mut param_bytes = Vec::new();
+                ck.serialize_uncompressed(&mut param_bytes)
+                    .expect("Serialization failed");
+                bytes.append(&mut param_bytes);
+                bytes
+            }
+            #[cfg(feature = "halo2")]
+            TrinitySenderParams::Halo2(laconic_params) => {
+                let mut bytes = vec![1]; // Tag byte for Halo2
+                let mut param_bytes =
+                    bincode::serialize(laconic_params.as_ref()).expect("Serialization failed");
+                bytes.append(&mut param_bytes);
+                bytes
+            }
+        }
+    }
+
+    /// Short hash of the sender params: the first 8 bytes of a blake3 hash,
+    /// in hex. Full and sender-only params from the same setup share a
+    /// fingerprint, so both parties can compare them.
+    ///
+    /// Halo2 sender params are a few points and hashed whole, as tagged
+    /// bytes. A Plain key is fixed by its size, the generators `u[0]`,
+    /// `hat_u[0]` and `g2`, and `r = g2^alpha`, so only those are hashed
+    /// after the tag rather than every power.
+    fn fingerprint(&self) -> String {
+        let mut hasher = blake3::Hasher::new();
+        match self {
+            #[cfg(feature = "plain")]
+            TrinitySenderParams::Plain(ck) => {
+                hasher.update(&[0]);
+                hasher.update(&(ck.message_length as u64).to_le_bytes());
+                for point in ck.u.iter().take(1).chain(ck.hat_u.iter().take(1)) {
+                    point
+                        .serialize_uncompressed(&mut hasher)
+                        .expect("Serialization failed");
+                }
+                for point in [ck.g2, ck.r] {
+                    point
+                        .serialize_uncompressed(&mut hasher)
+                        .expect("Serialization failed");
+                }
+            }
+            #[cfg(feature = "halo2")]
+            TrinitySenderParams::Halo2(_) => {
+                hasher.update(&self.to_tagged_bytes());
+            }
+        }
+        hex::encode(&hasher.finalize().as_bytes()[..8])
+    }
+}
+
+impl fmt::Debug for TrinitySenderParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "plain")]
+            TrinitySenderParams::Plain(ck) => f
+                .debug_struct("Plain")
+                .field("message_length", &ck.message_length)
+                .field("fingerprint", &self.fingerprint())
+                .finish(),
+            #[cfg(feature = "halo2")]
+            TrinitySenderParams::Halo2(laconic_params) => f
+                .debug_struct("Halo2")
+                .field("k", &laconic_params.k)
+                .field("fingerprint", &self.fingerprint())
+                .finish(),
+        }
+    }
+}
+
+impl TrinityParams {
+    pub fn mode(&self) -> KZGType {
+        match self {
+            #[cfg(feature = "plain")]
+            TrinityParams::Plain(_) => KZGType::Plain,
+            #[cfg(feature = "halo2")]
+            TrinityParams::Halo2(_) => KZGType::Halo2,
+        }
+    }
+
+    /// Number of OT positions, see `Trinity::capacity`
+    pub fn capacity(&self) -> usize {
+        match self {
+            #[cfg(feature = "plain")]
+            TrinityParams::Plain(ck) => ark_poly::EvaluationDomain::size(&ck.domain),
+            #[cfg(feature = "halo2")]
+            TrinityParams::Halo2(halo2_params) => 1 << halo2_params.k,
+        }
+    }
+}
+
+impl fmt::Debug for TrinityParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fingerprint = self.to_sender_params().fingerprint();
+        match self {
+            #[cfg(feature = "plain")]
+            TrinityParams::Plain(ck) => f
+                .debug_struct("Plain")
+                .field("message_length", &ck.message_length)
+                .field("fingerprint", &fingerprint)
+                .finish(),
+            #[cfg(feature = "halo2")]
+            TrinityParams::Halo2(halo2_params) => f
+                .debug_struct("Halo2")
+                .field("k", &halo2_params.k)
+                .field("fingerprint", &fingerprint)
+                .finish(),
+        }
+    }
+}
+
 impl Trinity {
     pub fn setup(mode: KZGType, message_length: usize) -> Self {
-        let rng = &mut OsRng;
+        Self::setup_from(&mut OsRng, Randomness::Secure, mode, message_length)
+    }
+
+    /// Same as `setup`, with the trapdoor drawn from `randomness`. The choice
+    /// is kept so `is_deterministic` can flag test-only params.
+    pub fn setup_with_randomness(
+        randomness: Randomness,
+        mode: KZGType,
+        message_length: usize,
+    ) -> Self {
+        Self::setup_from(&mut randomness.rng(), randomness, mode, message_length)
+    }
+
+    /// Whether the params came from a fixed seed or a caller-supplied rng,
+    /// see `Randomness`. Only known for params set up in this process,
+    /// deserialized params report `false`.
+    pub fn is_deterministic(&self) -> bool {
+        self.randomness.is_deterministic()
+    }
+
+    /// Same as `setup`, but draws the trapdoor from the given CSPRNG, e.g. one
+    /// seeded from a ceremony transcript. The same seed gives the same params.
+    /// The params are recorded as `Injected`, so `is_deterministic` is true.
+    pub fn setup_with_rng<R: Rng + CryptoRng>(
+        rng: &mut R,
+        mode: KZGType,
+        message_length: usize,
+    ) -> Self {
+        Self::setup_from(rng, Randomness::Injected, mode, message_length)
+    }
 
+    /// Setup drawing from `rng`, which comes from `randomness`
+    #[cfg_attr(not(feature = "plain"), allow(unused_variables))]
+    fn setup_from<R: Rng + CryptoRng>(
+        rng: &mut R,
+        randomness: Randomness,
+        mode: KZGType,
+        message_length: usize,
+    ) -> Self {
         let params = match mode {
+            #[cfg(feature = "plain")]
             KZGType::Plain => {
                 let plainparams =
                     CommitmentKey::<Bn254, Radix2EvaluationDomain<Fr>>::setup(rng, message_length)
                         .expect("setup failed");
                 TrinityParams::Plain(Arc::new(plainparams))
             }
+            #[cfg(feature = "halo2")]
             KZGType::Halo2 => {
                 // To do: remove hardcoded k parameter
                 let halo2params = Halo2Params::setup(rng, 8).expect("Failed to setup Halo2Params");
@@ -225,245 +1185,858 @@ impl Trinity {
         Self {
             mode,
             params: TrinityInnerParams::Full(params),
+            randomness,
+            session_id: rng.gen(),
         }
     }
 
-    pub fn setup_for_garbler(sender_params: TrinitySenderParams) -> Self {
-        let mode = match sender_params {
-            TrinitySenderParams::Plain(_) => KZGType::Plain,
-            TrinitySenderParams::Halo2(_) => KZGType::Halo2,
-        };
-
-        Self {
-            mode,
-            params: TrinityInnerParams::Sender(sender_params),
-        }
+    /// Generate params with `1 << k` OT positions in either mode, for inputs
+    /// larger than the default setup supports
+    pub fn setup_with_degree(mode: KZGType, k: u32) -> Result<Self, TrinityError> {
+        Self::setup_with_degree_and_rng(&mut OsRng, Randomness::Secure, mode, k)
     }
 
-    // Create Trinity from full parameter bytes
-    pub fn from_full_params_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
-        if bytes.is_empty() {
-            return Err("Empty bytes");
-        }
-
-        let mode = match bytes[0] {
-            0 => KZGType::Plain,
-            1 => KZGType::Halo2,
-            _ => return Err("Invalid tag byte"),
-        };
+    fn setup_with_degree_and_rng<R: Rng + CryptoRng>(
+        rng: &mut R,
+        randomness: Randomness,
+        mode: KZGType,
+        k: u32,
+    ) -> Result<Self, TrinityError> {
+        check_degree(k)?;
 
         let params = match mode {
+            #[cfg(feature = "plain")]
             KZGType::Plain => {
-                let ck = CommitmentKey::deserialize_uncompressed(&mut &bytes[1..])
-                    .map_err(|_| "Failed to deserialize CommitmentKey")?;
-                TrinityParams::Plain(Arc::new(ck))
+                let plainparams =
+                    CommitmentKey::<Bn254, Radix2EvaluationDomain<Fr>>::setup(rng, 1 << k)
+                        .expect("setup failed");
+                TrinityParams::Plain(Arc::new(plainparams))
             }
+            #[cfg(feature = "halo2")]
             KZGType::Halo2 => {
-                let halo2_params = Halo2Params::from_bytes(&bytes[1..])
-                    .map_err(|_| "Failed to convert from SerializableHalo2Params")?;
-                TrinityParams::Halo2(Arc::new(halo2_params))
+                let halo2params =
+                    Halo2Params::setup(rng, k as usize).expect("Failed to setup Halo2Params");
+                TrinityParams::Halo2(Arc::new(halo2params))
             }
         };
 
         Ok(Self {
             mode,
             params: TrinityInnerParams::Full(params),
+            randomness,
+            session_id: rng.gen(),
         })
     }
 
-    // Serialize full params to bytes
-    pub fn to_full_params_bytes(&self) -> Vec<u8> {
-        match &self.params {
-            TrinityInnerParams::Full(full_params) => match full_params {
-                TrinityParams::Plain(ck) => {
-                    let mut bytes = vec![0]; // Tag for Plain
-                    let mut param_bytes = Vec::new();
-                    ck.serialize_uncompressed(&mut param_bytes)
-                        .expect("Serialization failed");
-                    bytes.append(&mut param_bytes);
-                    bytes
+    /// Generate params with enough OT positions for `circuit`, the smallest
+    /// supported `1 << k` covering its input bits. The circuit does not record
+    /// which inputs are the evaluator's, so this sizes for all of them, which
+    /// fits any split between the parties.
+    pub fn setup_for_circuit(mode: KZGType, circuit: &Circuit) -> Result<Self, TrinityError> {
+        Self::setup_with_degree(mode, Self::degree_for(circuit.input_len())?)
+    }
+
+    /// Smallest supported `k` with at least `positions` OT positions
+    fn degree_for(positions: usize) -> Result<u32, TrinityError> {
+        let k = positions
+            .next_power_of_two()
+            .trailing_zeros()
+            .max(MIN_SETUP_DEGREE);
+        if k > MAX_SETUP_DEGREE {
+            return Err(TrinityError::InputTooLong {
+                len: positions,
+                capacity: 1 << MAX_SETUP_DEGREE,
+            });
+        }
+        Ok(k)
+    }
+
+    /// Start a `TrinityBuilder`
+    pub fn builder() -> TrinityBuilder {
+        TrinityBuilder::default()
+    }
+
+    /// Maximum number of bits an evaluator can commit to under these params:
+    /// the domain size for Plain, `1 << k` for Halo2. OT indices must be
+    /// below this.
+    pub fn capacity(&self) -> usize {
+        match &self.params {
+            TrinityInnerParams::Full(params) => params.capacity(),
+            TrinityInnerParams::Sender(sender_params) => sender_params.capacity(),
+            #[cfg(feature = "halo2")]
+            TrinityInnerParams::Verifier(verifier_params) => 1 << verifier_params.k,
+        }
+    }
+
+    /// Mode the parameters were generated or deserialized for
+    pub fn mode(&self) -> KZGType {
+        self.mode
+    }
+
+    /// Use `id` as the session id, e.g. one per garbler when an evaluator
+    /// runs several sessions over the same params. Both parties need the
+    /// same id, the garbler reads it from `to_sender_bytes`.
+    pub fn with_session_id(mut self, id: [u8; 32]) -> Self {
+        self.session_id = id;
+        self
+    }
+
+    pub fn setup_for_garbler(sender_params: TrinitySenderParams) -> Self {
+        Self {
+            mode: sender_params.mode(),
+            params: TrinityInnerParams::Sender(sender_params),
+            randomness: Randomness::Secure,
+            session_id: OsRng.gen(),
+        }
+    }
+
+    /// Create Trinity from full parameter bytes, see `to_full_params_bytes`
+    /// for the format
+    pub fn from_full_params_bytes(bytes: &[u8]) -> Result<Self, TrinityError> {
+        if bytes.is_empty() {
+            return Err(TrinityError::EmptyBytes);
+        }
+        let (tag, session_id, bytes) = match bytes.strip_prefix(&FULL_PARAMS_MAGIC[..]) {
+            Some(bytes) => {
+                let (&version, bytes) = bytes.split_first().ok_or(TrinityError::TruncatedInput)?;
+                if version != FULL_PARAMS_VERSION {
+                    return Err(TrinityError::UnsupportedVersion(version));
+                }
+                let (&tag, bytes) = bytes.split_first().ok_or(TrinityError::TruncatedInput)?;
+                let (session_id, bytes) = bytes
+                    .split_first_chunk::<32>()
+                    .ok_or(TrinityError::TruncatedInput)?;
+                (tag, *session_id, bytes)
+            }
+            // Written before the header, the tag never matches the magic
+            None => (bytes[0], [0u8; 32], &bytes[1..]),
+        };
+        let mode = match tag {
+            #[cfg(feature = "plain")]
+            0 => KZGType::Plain,
+            #[cfg(feature = "halo2")]
+            1 => KZGType::Halo2,
+            tag => return Err(TrinityError::InvalidTagByte(tag)),
+        };
+
+        let params = match mode {
+            #[cfg(feature = "plain")]
+            KZGType::Plain => {
+                let ck = CommitmentKey::deserialize_uncompressed(&mut &bytes[..])
+                    .map_err(|_| TrinityError::DeserializeCommitmentKey)?;
+                TrinityParams::Plain(Arc::new(ck))
+            }
+            #[cfg(feature = "halo2")]
+            KZGType::Halo2 => {
+                let halo2_params = Halo2Params::from_bytes(bytes)
+                    .map_err(|_| TrinityError::DeserializeHalo2Params)?;
+                check_degree(halo2_params.k as u32)?;
+                TrinityParams::Halo2(Arc::new(halo2_params))
+            }
+        };
+
+        Ok(Self {
+            mode,
+            params: TrinityInnerParams::Full(params),
+            randomness: Randomness::Secure,
+            session_id,
+        })
+    }
+
+    /// Serialize the full (evaluator) params to bytes.
+    ///
+    /// The format is `FULL_PARAMS_MAGIC`, the `FULL_PARAMS_VERSION` byte, one
+    /// tag byte, the 32-byte session id, then the backend encoding:
+    /// - `0`: Plain, the uncompressed arkworks `CommitmentKey`
+    /// - `1`: Halo2, `Halo2Params::to_bytes`
+    ///
+    /// The session id is saved so that reloaded params keep decrypting what
+    /// a garbler sends under `to_sender_bytes` from the original setup.
+    /// `from_full_params_bytes` also reads the older bytes without the header,
+    /// one tag byte directly followed by the backend encoding.
+    ///
+    /// Panics for garbler-only and verifier-only setups, which do not hold the
+    /// full params.
+    pub fn to_full_params_bytes(&self) -> Vec<u8> {
+        match &self.params {
+            TrinityInnerParams::Full(full_params) => match full_params {
+                #[cfg(feature = "plain")]
+                TrinityParams::Plain(ck) => {
+                    let mut param_bytes = Vec::new();
+                    ck.serialize_uncompressed(&mut param_bytes)
+                        .expect("Serialization failed");
+                    self.with_full_params_header(0, &param_bytes) // Tag for Plain
                 }
+                #[cfg(feature = "halo2")]
                 TrinityParams::Halo2(halo2_params) => {
-                    let mut bytes = vec![1]; // Tag for Halo2
-                    let mut param_bytes = Halo2Params::to_bytes(halo2_params.as_ref());
-                    bytes.append(&mut param_bytes);
-                    bytes
+                    let param_bytes = Halo2Params::to_bytes(halo2_params.as_ref());
+                    self.with_full_params_header(1, &param_bytes) // Tag for Halo2
                 }
             },
             TrinityInnerParams::Sender(_) => {
                 panic!("Cannot serialize full params from sender-only params")
             }
+            #[cfg(feature = "halo2")]
+            TrinityInnerParams::Verifier(_) => {
+                panic!("Cannot serialize full params from verifier-only params")
+            }
         }
     }
 
+    fn with_full_params_header(&self, tag: u8, params: &[u8]) -> Vec<u8> {
+        [
+            &FULL_PARAMS_MAGIC[..],
+            &[FULL_PARAMS_VERSION, tag],
+            &self.session_id,
+            params,
+        ]
+        .concat()
+    }
+
     // Convert to sender params (for network transfer)
     pub fn to_sender_params(&self) -> Option<TrinitySenderParams> {
         match &self.params {
             TrinityInnerParams::Full(full_params) => Some(full_params.to_sender_params()),
             TrinityInnerParams::Sender(sender_params) => Some(sender_params.clone()),
+            #[cfg(feature = "halo2")]
+            TrinityInnerParams::Verifier(verifier_params) => Some(TrinitySenderParams::Halo2(
+                Arc::new(LaconicParams::from(verifier_params.as_ref())),
+            )),
+        }
+    }
+
+    /// Serialize the verifier params for proofs over `num_bits` bits, a tag
+    /// byte (`1`, Halo2) followed by `Halo2VerifierParams::to_bytes`. They
+    /// only hold `g[0]`, `g2`, `s_g2` and the verifying key: enough for
+    /// `verify_commitment_proof`, not for `create_ot_receiver`. Plain params
+    /// verify bit proofs as they are, so only Halo2 full or verifier params
+    /// have them.
+    #[cfg(feature = "halo2")]
+    pub fn to_verifier_bytes(&self, num_bits: usize) -> Result<Vec<u8>, TrinityError> {
+        let verifier_bytes = match &self.params {
+            TrinityInnerParams::Full(TrinityParams::Halo2(halo2_params)) => {
+                Halo2VerifierParams::new(halo2_params.as_ref(), num_bits)
+                    .map_err(|_| TrinityError::InputTooLong {
+                        len: num_bits,
+                        capacity: self.capacity(),
+                    })?
+                    .to_bytes()
+            }
+            TrinityInnerParams::Verifier(verifier_params)
+                if verifier_params.num_bits == num_bits =>
+            {
+                verifier_params.to_bytes()
+            }
+            TrinityInnerParams::Verifier(_) => return Err(TrinityError::InvalidProofLength),
+            _ => return Err(TrinityError::NoVerifierParams),
+        };
+        Ok([vec![1], verifier_bytes].concat())
+    }
+
+    /// Create a verifier-only Trinity from `to_verifier_bytes`
+    #[cfg(feature = "halo2")]
+    pub fn from_verifier_bytes(bytes: &[u8]) -> Result<Self, TrinityError> {
+        let (&tag, bytes) = bytes.split_first().ok_or(TrinityError::EmptyBytes)?;
+        if tag != 1 {
+            return Err(TrinityError::InvalidTagByte(tag));
+        }
+        let verifier_params = Halo2VerifierParams::from_bytes(bytes)
+            .map_err(|_| TrinityError::DeserializeVerifierParams)?;
+        check_degree(verifier_params.k as u32)?;
+
+        Ok(Self {
+            mode: KZGType::Halo2,
+            params: TrinityInnerParams::Verifier(Arc::new(verifier_params)),
+            randomness: Randomness::Secure,
+            session_id: OsRng.gen(),
+        })
+    }
+
+    /// Check that `proof` shows `com` commits to `num_bits` bits. Plain
+    /// commitments are checked with a `BitProof`, which any Plain params can
+    /// verify. Halo2 proofs need full or verifier Halo2 params.
+    pub fn verify_commitment_proof(
+        &self,
+        com: &TrinityCom,
+        proof: &[u8],
+        num_bits: usize,
+    ) -> Result<(), TrinityError> {
+        match com {
+            #[cfg(feature = "plain")]
+            TrinityCom::Plain(com, _) => {
+                let ck = match &self.params {
+                    TrinityInnerParams::Full(TrinityParams::Plain(ck))
+                    | TrinityInnerParams::Sender(TrinitySenderParams::Plain(ck)) => ck,
+                    #[allow(unreachable_patterns)]
+                    _ => return Err(TrinityError::MismatchedCommitmentType),
+                };
+                let bit_proof = BitProof::<Bn254>::deserialize_compressed(proof)
+                    .map_err(|_| TrinityError::InvalidProof)?;
+                if bit_proof.verify(ck.as_ref(), com, num_bits) {
+                    Ok(())
+                } else {
+                    Err(TrinityError::InvalidProof)
+                }
+            }
+            #[cfg(feature = "halo2")]
+            TrinityCom::Halo2(com, _) => match &self.params {
+                TrinityInnerParams::Full(TrinityParams::Halo2(halo2_params)) => {
+                    verify_kzg_commitment_with_halo2_proof(
+                        &halo2_params.params,
+                        num_bits,
+                        com,
+                        proof,
+                    )
+                    .map_err(|_| TrinityError::InvalidProof)
+                }
+                TrinityInnerParams::Verifier(verifier_params) => {
+                    // The verifying key fixes how many bits the proof covers
+                    if verifier_params.num_bits != num_bits {
+                        return Err(TrinityError::InvalidProofLength);
+                    }
+                    verify_kzg_commitment_with_vk(
+                        &verifier_params.kzg_params(),
+                        &verifier_params.vk,
+                        com,
+                        proof,
+                    )
+                    .map_err(|_| TrinityError::InvalidProof)
+                }
+                _ => Err(TrinityError::NoVerifierParams),
+            },
         }
     }
 
     // Serialize directly to minimal bytes for transfer
     pub fn to_sender_bytes(&self) -> Vec<u8> {
         if let Some(sender_params) = self.to_sender_params() {
-            match sender_params {
-                TrinitySenderParams::Plain(ck) => {
-                    let mut bytes = vec![0]; // Tag byte for Plain
-                    let mut param_bytes = Vec::new();
-                    ck.serialize_uncompressed(&mut param_bytes)
-                        .expect("Serialization failed");
-                    bytes.append(&mut param_bytes);
-                    bytes
-                }
-                TrinitySenderParams::Halo2(laconic_params) => {
-                    let mut bytes = vec![1]; // Tag byte for Halo2
-                    let mut param_bytes =
-                        bincode::serialize(laconic_params.as_ref()).expect("Serialization failed");
-                    bytes.append(&mut param_bytes);
-                    bytes
-                }
-            }
+            self.with_sender_header(sender_params.to_tagged_bytes())
+        } else {
+            panic!("No sender params available");
+        }
+    }
+
+    /// Like `to_sender_bytes`, with Plain points compressed: 32 instead of
+    /// 64 bytes per G1 point and 64 instead of 128 per G2 point, so Plain
+    /// sender params shrink to about half (e.g. ~229 KB instead of ~459 KB
+    /// for 1024 positions). Reading them back costs a square root per point.
+    /// Halo2 output is the same as `to_sender_bytes`.
+    pub fn to_sender_bytes_compressed(&self) -> Vec<u8> {
+        if let Some(sender_params) = self.to_sender_params() {
+            self.with_sender_header(sender_params.to_tagged_bytes_compressed())
         } else {
             panic!("No sender params available");
         }
     }
 
+    /// Magic and version, then the tag byte of `tagged`, the session id and
+    /// the rest of `tagged`
+    fn with_sender_header(&self, tagged: Vec<u8>) -> Vec<u8> {
+        let (tag, params) = tagged.split_at(1);
+        [
+            &SENDER_BYTES_MAGIC[..],
+            &[SENDER_BYTES_VERSION],
+            tag,
+            &self.session_id,
+            params,
+        ]
+        .concat()
+    }
+
     // Create Trinity from sender bytes
-    pub fn from_sender_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+    pub fn from_sender_bytes(bytes: &[u8]) -> Result<Self, TrinityError> {
         if bytes.is_empty() {
-            return Err("Empty bytes");
+            return Err(TrinityError::EmptyBytes);
         }
+        let bytes = bytes
+            .strip_prefix(&SENDER_BYTES_MAGIC[..])
+            .ok_or(TrinityError::BadMagic)?;
+        let (&version, bytes) = bytes.split_first().ok_or(TrinityError::EmptyBytes)?;
+        let (&tag, bytes) = bytes.split_first().ok_or(TrinityError::EmptyBytes)?;
+        let (session_id, bytes) = match version {
+            1 => (&[0u8; 32], bytes),
+            SENDER_BYTES_VERSION => bytes
+                .split_first_chunk::<32>()
+                .ok_or(TrinityError::TruncatedInput)?,
+            version => return Err(TrinityError::UnsupportedVersion(version)),
+        };
 
-        match bytes[0] {
+        let trinity = match tag {
+            #[cfg(feature = "plain")]
             0 => {
                 let ck: CommitmentKey<_, _> =
-                    CommitmentKey::deserialize_uncompressed(&mut &bytes[1..])
-                        .map_err(|_| "Failed to deserialize CommitmentKey")?;
-                Ok(Self::setup_for_garbler(TrinitySenderParams::Plain(
-                    Arc::new(ck),
-                )))
+                    CommitmentKey::deserialize_uncompressed(&mut &bytes[..])
+                        .map_err(|_| TrinityError::DeserializeCommitmentKey)?;
+                Self::setup_for_garbler(TrinitySenderParams::Plain(Arc::new(ck)))
             }
+            #[cfg(feature = "plain")]
+            2 => {
+                let ck: CommitmentKey<_, _> =
+                    CommitmentKey::deserialize_compressed(&mut &bytes[..])
+                        .map_err(|_| TrinityError::DeserializeCommitmentKey)?;
+                Self::setup_for_garbler(TrinitySenderParams::Plain(Arc::new(ck)))
+            }
+            #[cfg(feature = "halo2")]
             1 => {
                 // Deserialize Halo2 sender params (LaconicParams)
-                let laconic_params: LaconicParams = bincode::deserialize(&bytes[1..])
-                    .map_err(|_| "Failed to deserialize LaconicParams")?;
+                let laconic_params: LaconicParams = bincode::deserialize(bytes)
+                    .map_err(|_| TrinityError::DeserializeLaconicParams)?;
+                check_degree(laconic_params.k)?;
 
-                Ok(Self::setup_for_garbler(TrinitySenderParams::Halo2(
-                    Arc::new(laconic_params),
-                )))
+                Self::setup_for_garbler(TrinitySenderParams::Halo2(Arc::new(laconic_params)))
             }
-            _ => Err("Invalid tag byte"),
-        }
+            tag => return Err(TrinityError::InvalidTagByte(tag)),
+        };
+        Ok(trinity.with_session_id(*session_id))
     }
 
     pub fn create_ot_receiver<Ctx>(
         &self,
         bits: &[TrinityChoice],
-    ) -> Result<KZGOTReceiver<Ctx>, &'static str> {
+    ) -> Result<KZGOTReceiver<Ctx>, TrinityError> {
+        self.create_ot_receiver_with_progress(bits, None)
+    }
+
+    /// Like `create_ot_receiver`, reporting the completed fraction to `progress`
+    pub fn create_ot_receiver_with_progress<Ctx>(
+        &self,
+        bits: &[TrinityChoice],
+        progress: Option<&dyn Fn(f32)>,
+    ) -> Result<KZGOTReceiver<Ctx>, TrinityError> {
         match &self.params {
             TrinityInnerParams::Full(params) => {
-                let trinity_receiver = TrinityReceiver::new(params, bits);
+                let capacity = params.capacity();
+                if bits.len() > capacity {
+                    return Err(TrinityError::InputTooLong {
+                        len: bits.len(),
+                        capacity,
+                    });
+                }
+                let trinity_receiver = TrinityReceiver::new_with_progress(params, bits, progress)?
+                    .with_session_id(self.session_id);
                 Ok(KZGOTReceiver {
                     trinity_receiver,
                     _phantom: PhantomData,
                 })
             }
-            TrinityInnerParams::Sender(_) => Err("Cannot create receiver from sender params"),
+            TrinityInnerParams::Sender(_) => Err(TrinityError::CreateReceiverFromSenderParams),
+            #[cfg(feature = "halo2")]
+            TrinityInnerParams::Verifier(_) => Err(TrinityError::CreateReceiverFromVerifierParams),
         }
     }
 
-    pub fn create_ot_sender<'a, Ctx>(&'a self, com: TrinityCom) -> KZGOTSender<'a, Ctx> {
+    /// Commit to `bits` without computing the openings, the expensive part
+    /// of `create_ot_receiver`, so the commitment can be published first
+    pub fn commit_only(
+        &self,
+        bits: &[TrinityChoice],
+    ) -> Result<PendingTrinityReceiver, TrinityError> {
+        match &self.params {
+            TrinityInnerParams::Full(params) => {
+                let capacity = params.capacity();
+                if bits.len() > capacity {
+                    return Err(TrinityError::InputTooLong {
+                        len: bits.len(),
+                        capacity,
+                    });
+                }
+                Ok(PendingTrinityReceiver::new(params, bits).with_session_id(self.session_id))
+            }
+            TrinityInnerParams::Sender(_) => Err(TrinityError::CreateReceiverFromSenderParams),
+            #[cfg(feature = "halo2")]
+            TrinityInnerParams::Verifier(_) => Err(TrinityError::CreateReceiverFromVerifierParams),
+        }
+    }
+
+    pub fn create_ot_sender<'a, Ctx>(
+        &'a self,
+        com: TrinityCom,
+    ) -> Result<KZGOTSender<'a, Ctx>, TrinityError> {
+        // Compare against the params rather than the `mode` field, so a
+        // commitment can never reach a backend it does not belong to
+        let expected = self.params.mode();
+        if com.mode() != expected {
+            return Err(TrinityError::ModeMismatch {
+                expected,
+                got: com.mode(),
+            });
+        }
+
         let trinity_sender = match &self.params {
-            TrinityInnerParams::Full(params) => TrinitySender::new(params, com),
+            TrinityInnerParams::Full(params) => TrinitySender::new(params, com)?,
             TrinityInnerParams::Sender(sender_params) => {
                 match (sender_params, com) {
-                    (TrinitySenderParams::Plain(ck), TrinityCom::Plain(com)) => {
+                    #[cfg(feature = "plain")]
+                    (TrinitySenderParams::Plain(ck), TrinityCom::Plain(com, domain)) => {
                         // Create Plain sender directly from plain sender params
-                        TrinitySender::Plain(PlainOTSender::new(ck.as_ref(), com))
-                    }
-                    (TrinitySenderParams::Halo2(laconic_params), TrinityCom::Halo2(com)) => {
-                        TrinitySender::Halo2(Halo2OTSender::new_from(
-                            laconic_params.as_ref().clone(),
-                            com,
-                        ))
+                        TrinitySender::Plain(PlainOTSender::new(ck.as_ref(), com, domain))
                     }
-                    _ => panic!("Mismatched commitment type"),
+                    #[cfg(feature = "halo2")]
+                    (
+                        TrinitySenderParams::Halo2(laconic_params),
+                        TrinityCom::Halo2(com, domain),
+                    ) => TrinitySender::Halo2(
+                        Halo2OTSender::new_from(laconic_params.as_ref().clone(), com, domain),
+                        PhantomData,
+                    ),
+                    #[allow(unreachable_patterns)]
+                    _ => return Err(TrinityError::MismatchedCommitmentType),
                 }
             }
+            #[cfg(feature = "halo2")]
+            TrinityInnerParams::Verifier(verifier_params) => match com {
+                TrinityCom::Halo2(com, domain) => TrinitySender::Halo2(
+                    Halo2OTSender::new_from(
+                        LaconicParams::from(verifier_params.as_ref()),
+                        com,
+                        domain,
+                    ),
+                    PhantomData,
+                ),
+                #[allow(unreachable_patterns)]
+                _ => return Err(TrinityError::MismatchedCommitmentType),
+            },
         };
 
-        KZGOTSender {
-            trinity_sender,
+        Ok(KZGOTSender {
+            trinity_sender: trinity_sender.with_session_id(self.session_id),
             _phantom: PhantomData,
+        })
+    }
+}
+
+/// Collects the setup settings before generating params, see
+/// `Trinity::builder`. A mode and exactly one of `message_length` or
+/// `from_circuit` are required; the rng defaults to `OsRng`.
+///
+/// Both sizes round up to the smallest supported `1 << k`, in either mode.
+#[derive(Debug)]
+pub struct TrinityBuilder<R = OsRng> {
+    mode: Option<KZGType>,
+    message_length: Option<usize>,
+    circuit_inputs: Option<usize>,
+    rng: R,
+    /// `Injected` once `rng` is set
+    randomness: Randomness,
+}
+
+impl Default for TrinityBuilder {
+    fn default() -> Self {
+        Self {
+            mode: None,
+            message_length: None,
+            circuit_inputs: None,
+            rng: OsRng,
+            randomness: Randomness::Secure,
         }
     }
 }
 
-impl<'a> TrinityReceiver<'a> {
-    pub fn new(params: &'a TrinityParams, bits: &[TrinityChoice]) -> Self {
+impl<R: Rng + CryptoRng> TrinityBuilder<R> {
+    pub fn mode(mut self, mode: KZGType) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Number of bits the evaluator will commit to
+    pub fn message_length(mut self, message_length: usize) -> Self {
+        self.message_length = Some(message_length);
+        self
+    }
+
+    /// Size the params for every input bit of `circuit`, like
+    /// `Trinity::setup_for_circuit`
+    pub fn from_circuit(mut self, circuit: &Circuit) -> Self {
+        self.circuit_inputs = Some(circuit.input_len());
+        self
+    }
+
+    /// Draw the trapdoor from `rng` instead of `OsRng`, see
+    /// `Trinity::setup_with_rng`
+    pub fn rng<R2: Rng + CryptoRng>(self, rng: R2) -> TrinityBuilder<R2> {
+        TrinityBuilder {
+            mode: self.mode,
+            message_length: self.message_length,
+            circuit_inputs: self.circuit_inputs,
+            rng,
+            randomness: Randomness::Injected,
+        }
+    }
+
+    pub fn build(mut self) -> Result<Trinity, TrinityError> {
+        let mode = self
+            .mode
+            .ok_or(TrinityError::InvalidSetupConfig("no mode set"))?;
+        let positions = match (self.message_length, self.circuit_inputs) {
+            (Some(len), None) | (None, Some(len)) => len,
+            (Some(_), Some(_)) => {
+                return Err(TrinityError::InvalidSetupConfig(
+                    "set only one of message_length and from_circuit",
+                ))
+            }
+            (None, None) => {
+                return Err(TrinityError::InvalidSetupConfig(
+                    "set message_length or from_circuit",
+                ))
+            }
+        };
+
+        let k = Trinity::degree_for(positions)?;
+        Trinity::setup_with_degree_and_rng(&mut self.rng, self.randomness, mode, k)
+    }
+}
+
+impl TrinityReceiver {
+    pub fn new(params: &TrinityParams, bits: &[TrinityChoice]) -> Result<Self, TrinityError> {
+        Self::new_with_progress(params, bits, None)
+    }
+
+    /// Like `new`, but reports the completed fraction (0.0..=1.0) to
+    /// `progress`. Plain mode has no intermediate steps and only reports 1.0.
+    pub fn new_with_progress(
+        params: &TrinityParams,
+        bits: &[TrinityChoice],
+        progress: Option<&dyn Fn(f32)>,
+    ) -> Result<Self, TrinityError> {
         match params {
+            #[cfg(feature = "plain")]
             TrinityParams::Plain(ck_arc) => {
-                let plain_bits: Vec<laconic_ot::Choice> = bits.iter().map(|&b| b.into()).collect();
-                let plain_recv = PlainOTRecv::new(ck_arc.as_ref(), &plain_bits);
-                TrinityReceiver::Plain(plain_recv)
+                let plain_bits: Zeroizing<Vec<laconic_ot::Choice>> =
+                    Zeroizing::new(bits.iter().map(|&b| b.into()).collect());
+                let plain_recv = PlainOTRecv::new_with_bit_proof(ck_arc.as_ref(), &plain_bits);
+                if let Some(progress) = progress {
+                    progress(1.0);
+                }
+                Ok(TrinityReceiver::Plain(plain_recv))
             }
+            #[cfg(feature = "halo2")]
             TrinityParams::Halo2(halo2_params_arc) => {
-                let halo2_bits: Vec<halo2_we_kzg::Choice> =
-                    bits.iter().map(|&b| b.into()).collect();
+                let halo2_bits: Zeroizing<Vec<halo2_we_kzg::Choice>> =
+                    Zeroizing::new(bits.iter().map(|&b| b.into()).collect());
                 let halo2_params = halo2_params_arc.as_ref();
-                let halo2_recv = Halo2OTRecv::new(halo2_params.clone(), &halo2_bits);
-                TrinityReceiver::Halo2(halo2_recv)
+                let halo2_recv =
+                    Halo2OTRecv::new_with_progress(halo2_params.clone(), &halo2_bits, progress)
+                        .map_err(TrinityError::Openings)?;
+                Ok(TrinityReceiver::Halo2(halo2_recv))
             }
         }
     }
 
-    pub fn recv(&self, i: usize, msg: TrinityMsg) -> [u8; MSG_SIZE] {
+    /// Recovered payloads are secret, compare them with the backends' `ct_eq`
+    pub fn recv<const N: usize>(
+        &self,
+        i: usize,
+        msg: TrinityMsg<N>,
+    ) -> Result<[u8; N], TrinityError> {
         match (self, msg) {
-            (TrinityReceiver::Plain(recv), TrinityMsg::Plain(msg)) => recv.recv(i, msg),
-            (TrinityReceiver::Halo2(recv), TrinityMsg::Halo2(msg)) => recv.recv(i, msg),
-            _ => panic!("Mismatched receiver and message types"),
+            #[cfg(feature = "plain")]
+            (TrinityReceiver::Plain(recv), TrinityMsg::Plain(msg)) => {
+                recv.recv(i, msg).map_err(TrinityError::from)
+            }
+            #[cfg(feature = "halo2")]
+            (TrinityReceiver::Halo2(recv), TrinityMsg::Halo2(msg)) => {
+                recv.recv(i, msg).map_err(|e| match e {
+                    halo2_we_kzg::RecvError::IndexOutOfRange { index, len } => {
+                        TrinityError::IndexOutOfRange { index, len }
+                    }
+                })
+            }
+            #[allow(unreachable_patterns)]
+            _ => Err(TrinityError::MismatchedCommitmentType),
+        }
+    }
+
+    /// Decrypt under session `id`, see `Trinity::with_session_id`
+    pub fn with_session_id(self, id: [u8; 32]) -> Self {
+        match self {
+            #[cfg(feature = "plain")]
+            TrinityReceiver::Plain(recv) => TrinityReceiver::Plain(recv.with_session_id(id)),
+            #[cfg(feature = "halo2")]
+            TrinityReceiver::Halo2(recv) => TrinityReceiver::Halo2(recv.with_session_id(id)),
         }
     }
 
     pub fn commitment(&self) -> TrinityCom {
         match self {
-            TrinityReceiver::Plain(recv) => TrinityCom::Plain(recv.commitment()),
-            TrinityReceiver::Halo2(recv) => TrinityCom::Halo2(recv.commitment()),
+            #[cfg(feature = "plain")]
+            TrinityReceiver::Plain(recv) => {
+                TrinityCom::Plain(recv.commitment(), recv.domain_fingerprint())
+            }
+            #[cfg(feature = "halo2")]
+            TrinityReceiver::Halo2(recv) => {
+                TrinityCom::Halo2(recv.commitment(), recv.domain_fingerprint())
+            }
+        }
+    }
+
+    /// Commitment together with the proof that it commits to bits, see
+    /// `Trinity::verify_commitment_proof`
+    pub fn commitment_with_proof(&self) -> (TrinityCom, Vec<u8>) {
+        match self {
+            #[cfg(feature = "plain")]
+            TrinityReceiver::Plain(recv) => {
+                let mut proof = Vec::new();
+                if let Some(bit_proof) = recv.bit_proof() {
+                    bit_proof.serialize_compressed(&mut proof).unwrap();
+                }
+                let com = TrinityCom::Plain(recv.commitment(), recv.domain_fingerprint());
+                (com, proof)
+            }
+            #[cfg(feature = "halo2")]
+            TrinityReceiver::Halo2(recv) => {
+                let com = TrinityCom::Halo2(recv.commitment(), recv.domain_fingerprint());
+                (com, recv.proof.clone())
+            }
+        }
+    }
+
+    /// The choice committed at position `i`, i.e. the branch `recv` opens
+    /// there. For checking which branch was taken when a protocol run fails.
+    pub fn selected_branch(&self, i: usize) -> Result<TrinityChoice, TrinityError> {
+        match self {
+            #[cfg(feature = "plain")]
+            TrinityReceiver::Plain(recv) => recv
+                .selected_branch(i)
+                .map(TrinityChoice::from)
+                .map_err(TrinityError::from),
+            #[cfg(feature = "halo2")]
+            TrinityReceiver::Halo2(recv) => recv
+                .selected_branch(i)
+                .map(TrinityChoice::from)
+                .map_err(|e| match e {
+                    halo2_we_kzg::RecvError::IndexOutOfRange { index, len } => {
+                        TrinityError::IndexOutOfRange { index, len }
+                    }
+                }),
         }
     }
 }
 
+impl PendingTrinityReceiver {
+    fn new(params: &TrinityParams, bits: &[TrinityChoice]) -> Self {
+        match params {
+            #[cfg(feature = "plain")]
+            TrinityParams::Plain(ck_arc) => {
+                let plain_bits: Zeroizing<Vec<laconic_ot::Choice>> =
+                    Zeroizing::new(bits.iter().map(|&b| b.into()).collect());
+                let pending = PlainOTRecv::commitment_only(ck_arc.as_ref(), &plain_bits);
+                PendingTrinityReceiver::Plain(pending, ck_arc.clone())
+            }
+            #[cfg(feature = "halo2")]
+            TrinityParams::Halo2(halo2_params_arc) => {
+                let halo2_bits: Zeroizing<Vec<halo2_we_kzg::Choice>> =
+                    Zeroizing::new(bits.iter().map(|&b| b.into()).collect());
+                let halo2_params = halo2_params_arc.as_ref().clone();
+                PendingTrinityReceiver::Halo2(Halo2OTRecv::commitment_only(
+                    halo2_params,
+                    &halo2_bits,
+                ))
+            }
+        }
+    }
+
+    /// Decrypt under session `id`, see `Trinity::with_session_id`
+    pub fn with_session_id(self, id: [u8; 32]) -> Self {
+        match self {
+            #[cfg(feature = "plain")]
+            PendingTrinityReceiver::Plain(pending, ck) => {
+                PendingTrinityReceiver::Plain(pending.with_session_id(id), ck)
+            }
+            #[cfg(feature = "halo2")]
+            PendingTrinityReceiver::Halo2(pending) => {
+                PendingTrinityReceiver::Halo2(pending.with_session_id(id))
+            }
+        }
+    }
+
+    pub fn commitment(&self) -> TrinityCom {
+        match self {
+            #[cfg(feature = "plain")]
+            PendingTrinityReceiver::Plain(pending, _) => {
+                TrinityCom::Plain(pending.commitment(), pending.domain_fingerprint())
+            }
+            #[cfg(feature = "halo2")]
+            PendingTrinityReceiver::Halo2(pending) => {
+                TrinityCom::Halo2(pending.commitment(), pending.domain_fingerprint())
+            }
+        }
+    }
+
+    /// Compute the openings, giving the receiver `create_ot_receiver` would
+    /// have returned for the same commitment
+    pub fn prepare_openings<Ctx>(self) -> Result<KZGOTReceiver<Ctx>, TrinityError> {
+        let trinity_receiver = match self {
+            #[cfg(feature = "plain")]
+            PendingTrinityReceiver::Plain(pending, ck) => {
+                TrinityReceiver::Plain(pending.prepare_openings_with_bit_proof(ck.as_ref()))
+            }
+            #[cfg(feature = "halo2")]
+            PendingTrinityReceiver::Halo2(pending) => {
+                TrinityReceiver::Halo2(pending.prepare_openings().map_err(TrinityError::Openings)?)
+            }
+        };
+        Ok(KZGOTReceiver {
+            trinity_receiver,
+            _phantom: PhantomData,
+        })
+    }
+}
+
 impl<'a> TrinitySender<'a> {
-    pub fn new(params: &'a TrinityParams, com: TrinityCom) -> Self {
+    pub fn new(params: &'a TrinityParams, com: TrinityCom) -> Result<Self, TrinityError> {
         match (params, com) {
-            (TrinityParams::Plain(ck), TrinityCom::Plain(com)) => {
-                TrinitySender::Plain(PlainOTSender::new(ck.as_ref(), com))
-            }
-            (TrinityParams::Halo2(params_arc), TrinityCom::Halo2(com)) => {
-                TrinitySender::Halo2(Halo2OTSender::new(params_arc.as_ref().clone().params, com))
+            #[cfg(feature = "plain")]
+            (TrinityParams::Plain(ck), TrinityCom::Plain(com, domain)) => Ok(TrinitySender::Plain(
+                PlainOTSender::new(ck.as_ref(), com, domain),
+            )),
+            #[cfg(feature = "halo2")]
+            (TrinityParams::Halo2(params_arc), TrinityCom::Halo2(com, domain)) => {
+                Ok(TrinitySender::Halo2(
+                    Halo2OTSender::new(params_arc.as_ref().clone().params, com, domain),
+                    PhantomData,
+                ))
             }
-            _ => panic!("Mismatched commitment type"),
+            #[allow(unreachable_patterns)]
+            _ => Err(TrinityError::MismatchedCommitmentType),
         }
     }
 
-    // pub fn new_from_params(params: LaconicParams, com: TrinityCom) -> Self {
-    //     match com {
-    //         TrinityCom::Plain(com) => todo!(),
-    //         TrinityCom::Halo2(com) => TrinitySender::Halo2(Halo2OTSender::new_from(params, com)),
-    //     }
-    // }
+    /// Encrypt under session `id`, see `Trinity::with_session_id`
+    pub fn with_session_id(self, id: [u8; 32]) -> Self {
+        match self {
+            #[cfg(feature = "plain")]
+            TrinitySender::Plain(sender) => TrinitySender::Plain(sender.with_session_id(id)),
+            #[cfg(feature = "halo2")]
+            TrinitySender::Halo2(sender, lifetime) => {
+                TrinitySender::Halo2(sender.with_session_id(id), lifetime)
+            }
+        }
+    }
 
-    pub fn send<R: Rng>(
+    /// Fails with `DomainMismatch` if the receiver committed over another
+    /// domain than these params
+    pub fn send<R: Rng, const N: usize>(
         &self,
         rng: &mut R,
         i: usize,
-        m0: [u8; MSG_SIZE],
-        m1: [u8; MSG_SIZE],
-    ) -> TrinityMsg {
+        m0: [u8; N],
+        m1: [u8; N],
+    ) -> Result<TrinityMsg<N>, TrinityError> {
         match self {
-            TrinitySender::Plain(sender) => TrinityMsg::Plain(sender.send(rng, i, m0, m1)),
-            TrinitySender::Halo2(sender) => TrinityMsg::Halo2(sender.send(rng, i, m0, m1)),
+            #[cfg(feature = "plain")]
+            TrinitySender::Plain(sender) => sender
+                .send(rng, i, m0, m1)
+                .map(TrinityMsg::Plain)
+                .map_err(|e| match e {
+                    laconic_ot::SendError::DomainMismatch => TrinityError::DomainMismatch,
+                }),
+            #[cfg(feature = "halo2")]
+            TrinitySender::Halo2(sender, _) => sender
+                .send(rng, i, m0, m1)
+                .map(TrinityMsg::Halo2)
+                .map_err(|e| match e {
+                    halo2_we_kzg::SendError::DomainMismatch => TrinityError::DomainMismatch,
+                }),
         }
     }
 }
@@ -471,8 +2044,65 @@ impl<'a> TrinitySender<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "halo2")]
+    use halo2curves::serde::SerdeObject;
     use rand::rngs::OsRng;
 
+    #[test]
+    fn test_choice_pack_unpack_roundtrip() {
+        // 12 bits: the second byte is only half used and zero-padded
+        let choices: Vec<TrinityChoice> = [1, 0, 1, 1, 0, 0, 0, 1, 1, 0, 1, 0]
+            .iter()
+            .map(|&b| TrinityChoice::from(b == 1))
+            .collect();
+        let packed = TrinityChoice::pack(&choices);
+        assert_eq!(packed, vec![0b1000_1101, 0b0000_0101]);
+        assert_eq!(TrinityChoice::unpack(&packed, 12), choices);
+
+        // Same order as the evaluator input bits
+        let bits = Lsb0Bits::from_u16(0x1234);
+        let choices = TrinityChoice::unpack(&bits.to_bytes(), bits.len());
+        assert_eq!(TrinityChoice::pack(&choices), bits.to_bytes());
+        assert!(choices
+            .iter()
+            .zip(bits.as_slice())
+            .all(|(&c, &b)| c == TrinityChoice::from(b)));
+
+        assert!(TrinityChoice::pack(&[]).is_empty());
+        assert!(TrinityChoice::unpack(&[0xff], 0).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "can't hold")]
+    fn test_choice_unpack_rejects_short_input() {
+        TrinityChoice::unpack(&[0xff], 9);
+    }
+
+    #[test]
+    fn test_selected_branch_matches_committed_bits() {
+        let bits = TrinityChoice::unpack(&[0b1001], 4);
+
+        let modes = [
+            #[cfg(feature = "plain")]
+            KZGType::Plain,
+            #[cfg(feature = "halo2")]
+            KZGType::Halo2,
+        ];
+        for mode in modes {
+            let trinity = Trinity::setup(mode, 4);
+            let receiver = trinity.create_ot_receiver::<()>(&bits).unwrap();
+
+            for (i, &bit) in bits.iter().enumerate() {
+                assert_eq!(receiver.trinity_receiver.selected_branch(i), Ok(bit));
+            }
+            assert_eq!(
+                receiver.trinity_receiver.selected_branch(4),
+                Err(TrinityError::IndexOutOfRange { index: 4, len: 4 })
+            );
+        }
+    }
+
+    #[cfg(feature = "plain")]
     #[test]
     fn test_plain_laconic_ot() {
         let rng = &mut OsRng;
@@ -492,16 +2122,19 @@ mod tests {
             .create_ot_receiver::<()>(&bits)
             .expect("Error while create the ot receiver.");
         let commitment = ot_receiver.trinity_receiver.commitment();
-        let ot_sender = trinity.create_ot_sender::<()>(commitment);
+        let ot_sender = trinity
+            .create_ot_sender::<()>(commitment)
+            .expect("Error while create the ot sender.");
 
         let m0 = [0u8; MSG_SIZE];
         let m1 = [1u8; MSG_SIZE];
 
-        let msg = ot_sender.trinity_sender.send(rng, 0, m0, m1);
-        let res = ot_receiver.trinity_receiver.recv(0, msg);
+        let msg = ot_sender.trinity_sender.send(rng, 0, m0, m1).unwrap();
+        let res = ot_receiver.trinity_receiver.recv(0, msg).unwrap();
         assert_eq!(res, m0);
     }
 
+    #[cfg(feature = "halo2")]
     #[test]
     fn test_halo2_laconic_ot() {
         let rng = &mut OsRng;
@@ -521,13 +2154,987 @@ mod tests {
             .create_ot_receiver::<()>(&bits)
             .expect("Error while create the ot receiver.");
         let commitment = ot_receiver.trinity_receiver.commitment();
-        let ot_sender = trinity.create_ot_sender::<()>(commitment);
+        let ot_sender = trinity
+            .create_ot_sender::<()>(commitment)
+            .expect("Error while create the ot sender.");
 
         let m0 = [0u8; MSG_SIZE];
         let m1 = [1u8; MSG_SIZE];
 
-        let msg = ot_sender.trinity_sender.send(rng, 0, m0, m1);
-        let res = ot_receiver.trinity_receiver.recv(0, msg);
+        let msg = ot_sender.trinity_sender.send(rng, 0, m0, m1).unwrap();
+        let res = ot_receiver.trinity_receiver.recv(0, msg).unwrap();
         assert_eq!(res, m0);
     }
+
+    #[cfg(all(feature = "plain", feature = "halo2"))]
+    #[test]
+    fn test_recv_rejects_mismatched_message() {
+        let rng = &mut OsRng;
+        let bits = TrinityChoice::unpack(&[0b1001], 4);
+
+        let plain = Trinity::setup(KZGType::Plain, 4);
+        let plain_receiver = plain.create_ot_receiver::<()>(&bits).unwrap();
+
+        let halo2 = Trinity::setup(KZGType::Halo2, 4);
+        let halo2_receiver = halo2.create_ot_receiver::<()>(&bits).unwrap();
+        let halo2_sender = halo2
+            .create_ot_sender::<()>(halo2_receiver.trinity_receiver.commitment())
+            .unwrap();
+
+        let msg = halo2_sender
+            .trinity_sender
+            .send(rng, 0, [0u8; MSG_SIZE], [1u8; MSG_SIZE])
+            .unwrap();
+        assert_eq!(
+            plain_receiver.trinity_receiver.recv(0, msg),
+            Err(TrinityError::MismatchedCommitmentType)
+        );
+    }
+
+    #[cfg(feature = "halo2")]
+    #[test]
+    fn test_verify_halo2_commitment() {
+        let trinity = Trinity::setup(KZGType::Halo2, 4);
+        let bits = vec![
+            TrinityChoice::One,
+            TrinityChoice::Zero,
+            TrinityChoice::One,
+            TrinityChoice::One,
+        ];
+
+        let ot_receiver = trinity
+            .create_ot_receiver::<()>(&bits)
+            .expect("Error while create the ot receiver.");
+        let commitment = ot_receiver.trinity_receiver.commitment();
+
+        let proof = match &ot_receiver.trinity_receiver {
+            TrinityReceiver::Halo2(recv) => recv.proof.clone(),
+            _ => unreachable!(),
+        };
+
+        assert_eq!(
+            trinity.verify_commitment_proof(&commitment, &proof, bits.len()),
+            Ok(())
+        );
+
+        let mut tampered = proof.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert_eq!(
+            trinity.verify_commitment_proof(&commitment, &tampered, bits.len()),
+            Err(TrinityError::InvalidProof)
+        );
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn test_plain_commitment_proof_verifies_for_garbler() {
+        let trinity = Trinity::setup(KZGType::Plain, 4);
+        let bits = vec![TrinityChoice::One, TrinityChoice::Zero, TrinityChoice::One];
+        let ot_receiver = trinity.create_ot_receiver::<()>(&bits).unwrap();
+        let (commitment, proof) = ot_receiver.trinity_receiver.commitment_with_proof();
+
+        let json =
+            serde_json::to_vec(&SerializableTrinityCom::with_proof(commitment, proof)).unwrap();
+        let (commitment, proof) = TrinityCom::deserialize_with_proof(&json).unwrap();
+        let proof = proof.unwrap();
+
+        let garbler = Trinity::from_sender_bytes(&trinity.to_sender_bytes()).unwrap();
+        assert_eq!(
+            garbler.verify_commitment_proof(&commitment, &proof, bits.len()),
+            Ok(())
+        );
+        assert_eq!(
+            garbler.verify_commitment_proof(&commitment, &[], bits.len()),
+            Err(TrinityError::InvalidProof)
+        );
+        // The proof only covers the committed bits
+        let other = trinity.create_ot_receiver::<()>(&bits).unwrap();
+        assert_eq!(
+            garbler.verify_commitment_proof(&other.trinity_receiver.commitment(), &proof, 3),
+            Err(TrinityError::InvalidProof)
+        );
+    }
+
+    #[test]
+    fn test_sender_rejects_other_domain() {
+        let bits = vec![TrinityChoice::One, TrinityChoice::Zero];
+        let modes = [
+            #[cfg(feature = "plain")]
+            KZGType::Plain,
+            #[cfg(feature = "halo2")]
+            KZGType::Halo2,
+        ];
+        for mode in modes {
+            let receiver = Trinity::setup_with_degree(mode, MIN_SETUP_DEGREE)
+                .unwrap()
+                .create_ot_receiver::<()>(&bits)
+                .unwrap();
+            let com = TrinityCom::from_tagged_bytes(
+                &receiver.trinity_receiver.commitment().to_tagged_bytes(),
+            )
+            .unwrap();
+            assert!(com == receiver.trinity_receiver.commitment());
+
+            let sender = Trinity::setup_with_degree(mode, MIN_SETUP_DEGREE + 1)
+                .unwrap()
+                .create_ot_sender::<()>(com)
+                .unwrap();
+            assert!(matches!(
+                sender
+                    .trinity_sender
+                    .send(&mut OsRng, 0, [0u8; 32], [1u8; 32]),
+                Err(TrinityError::DomainMismatch)
+            ));
+        }
+    }
+
+    #[cfg(feature = "halo2")]
+    #[test]
+    fn test_halo2_commitment_with_proof_roundtrip() {
+        let trinity = Trinity::setup(KZGType::Halo2, 4);
+        let bits = vec![TrinityChoice::One, TrinityChoice::Zero];
+
+        let ot_receiver = trinity
+            .create_ot_receiver::<()>(&bits)
+            .expect("Error while create the ot receiver.");
+        let (commitment, proof) = ot_receiver.trinity_receiver.commitment_with_proof();
+        assert!(!proof.is_empty());
+
+        let serializable = SerializableTrinityCom::with_proof(commitment, proof.clone());
+        let json = serde_json::to_vec(&serializable).unwrap();
+        let (deserialized, deserialized_proof) = TrinityCom::deserialize_with_proof(&json).unwrap();
+
+        match (commitment, deserialized) {
+            (TrinityCom::Halo2(a, _), TrinityCom::Halo2(b, _)) => assert_eq!(a, b),
+            _ => panic!("Expected Halo2 commitment"),
+        }
+        assert_eq!(deserialized_proof, Some(proof));
+
+        // Commitments serialized without a proof still deserialize
+        let (bare, bare_proof) =
+            TrinityCom::deserialize_with_proof(&commitment.serialize()).unwrap();
+        assert!(bare == commitment);
+        assert_eq!(bare_proof, None);
+
+        // Without a domain a sender could not check the commitment
+        let com = match commitment {
+            TrinityCom::Halo2(com, _) => bincode::serialize(&com).unwrap(),
+            #[allow(unreachable_patterns)]
+            _ => unreachable!(),
+        };
+        let legacy = SerializableTrinityCom::Halo2(SerializableHalo2Com::Bare(com));
+        let json = serde_json::to_vec(&legacy).unwrap();
+        assert!(matches!(
+            TrinityCom::deserialize(&json),
+            Err(TrinityError::DeserializeCommitment)
+        ));
+    }
+
+    #[test]
+    fn test_commit_only_matches_create_ot_receiver() {
+        let rng = &mut OsRng;
+
+        let modes = [
+            #[cfg(feature = "plain")]
+            KZGType::Plain,
+            #[cfg(feature = "halo2")]
+            KZGType::Halo2,
+        ];
+        for mode in modes {
+            let trinity = Trinity::setup(mode, 4);
+
+            // Four bits fill the Plain domain, which would otherwise get
+            // random padding and a different commitment each time
+            let bits = vec![
+                TrinityChoice::One,
+                TrinityChoice::Zero,
+                TrinityChoice::Zero,
+                TrinityChoice::One,
+            ];
+
+            let pending = trinity.commit_only(&bits).unwrap();
+            let full = trinity.create_ot_receiver::<()>(&bits).unwrap();
+            assert_eq!(pending.commitment(), full.trinity_receiver.commitment());
+
+            let ot_receiver = pending.prepare_openings::<()>().unwrap();
+            let ot_sender = trinity
+                .create_ot_sender::<()>(ot_receiver.trinity_receiver.commitment())
+                .unwrap();
+            let msg = ot_sender
+                .trinity_sender
+                .send(rng, 3, [0u8; 32], [1u8; 32])
+                .unwrap();
+            assert_eq!(
+                ot_receiver.trinity_receiver.recv(3, msg).unwrap(),
+                [1u8; 32]
+            );
+        }
+    }
+
+    #[test]
+    fn test_laconic_ot_32_byte_messages() {
+        let rng = &mut OsRng;
+
+        let modes = [
+            #[cfg(feature = "plain")]
+            KZGType::Plain,
+            #[cfg(feature = "halo2")]
+            KZGType::Halo2,
+        ];
+        for mode in modes {
+            let trinity = Trinity::setup(mode, 4);
+
+            let bits = vec![
+                TrinityChoice::Zero,
+                TrinityChoice::One,
+                TrinityChoice::Zero,
+                TrinityChoice::One,
+            ];
+
+            let ot_receiver = trinity
+                .create_ot_receiver::<()>(&bits)
+                .expect("Error while create the ot receiver.");
+            let commitment = ot_receiver.trinity_receiver.commitment();
+            let ot_sender = trinity
+                .create_ot_sender::<()>(commitment)
+                .expect("Error while create the ot sender.");
+
+            let m0 = [0u8; 32];
+            let m1 = [1u8; 32];
+
+            let msg = ot_sender.trinity_sender.send(rng, 1, m0, m1).unwrap();
+
+            // Round-trip through the serializable form as well
+            let msg: TrinityMsg<32> = crate::SerializableTrinityMsg::from(msg)
+                .try_into()
+                .expect("Failed to convert 32-byte message");
+
+            let res = ot_receiver.trinity_receiver.recv(1, msg).unwrap();
+            assert_eq!(res, m1);
+        }
+    }
+
+    #[test]
+    fn test_full_params_bytes_roundtrip() {
+        let modes = [
+            #[cfg(feature = "plain")]
+            (KZGType::Plain, 0u8),
+            #[cfg(feature = "halo2")]
+            (KZGType::Halo2, 1u8),
+        ];
+        for (mode, tag) in modes {
+            let trinity = Trinity::setup(mode, 4);
+            let bytes = trinity.to_full_params_bytes();
+            assert!(bytes.starts_with(&FULL_PARAMS_MAGIC));
+            assert_eq!(bytes[FULL_PARAMS_MAGIC.len()], FULL_PARAMS_VERSION);
+            assert_eq!(bytes[FULL_PARAMS_MAGIC.len() + 1], tag);
+
+            let restored = Trinity::from_full_params_bytes(&bytes).unwrap();
+            assert!(matches!(restored.params, TrinityInnerParams::Full(_)));
+            assert_eq!(restored.to_full_params_bytes(), bytes);
+
+            // The reloaded setup keeps the session id and still works for the
+            // evaluator, against a sender on the original params
+            assert_eq!(restored.session_id, trinity.session_id);
+            let bits = [TrinityChoice::One, TrinityChoice::Zero];
+            let receiver = restored.create_ot_receiver::<()>(&bits).unwrap();
+            let sender = trinity
+                .create_ot_sender::<()>(receiver.trinity_receiver.commitment())
+                .unwrap();
+            for (i, bit) in bits.iter().enumerate() {
+                let (m0, m1) = ([i as u8; MSG_SIZE], [i as u8 + 10; MSG_SIZE]);
+                let msg = sender.trinity_sender.send(&mut OsRng, i, m0, m1).unwrap();
+                let expected = if *bit == TrinityChoice::One { m1 } else { m0 };
+                assert_eq!(receiver.trinity_receiver.recv(i, msg).unwrap(), expected);
+            }
+
+            // Bytes without the header still load, with id zero
+            let params_start = FULL_PARAMS_MAGIC.len() + 2 + 32;
+            let legacy = [&[tag][..], &bytes[params_start..]].concat();
+            let from_legacy = Trinity::from_full_params_bytes(&legacy).unwrap();
+            assert_eq!(from_legacy.session_id, [0; 32]);
+            assert_eq!(
+                from_legacy.to_full_params_bytes(),
+                trinity
+                    .clone()
+                    .with_session_id([0; 32])
+                    .to_full_params_bytes()
+            );
+
+            let mut bad_version = bytes.clone();
+            bad_version[FULL_PARAMS_MAGIC.len()] = 99;
+            assert_eq!(
+                Trinity::from_full_params_bytes(&bad_version).err(),
+                Some(TrinityError::UnsupportedVersion(99))
+            );
+            assert_eq!(
+                Trinity::from_full_params_bytes(&bytes[..params_start - 1]).err(),
+                Some(TrinityError::TruncatedInput)
+            );
+        }
+
+        assert_eq!(
+            Trinity::from_full_params_bytes(&[]).err(),
+            Some(TrinityError::EmptyBytes)
+        );
+        assert_eq!(
+            Trinity::from_full_params_bytes(&[7]).err(),
+            Some(TrinityError::InvalidTagByte(7))
+        );
+    }
+
+    #[test]
+    fn test_capacity_bounds_receiver_input() {
+        let modes = [
+            #[cfg(feature = "plain")]
+            (KZGType::Plain, 4),
+            #[cfg(feature = "halo2")]
+            (KZGType::Halo2, 1 << 8),
+        ];
+        for (mode, capacity) in modes {
+            let trinity = Trinity::setup(mode, 4);
+            assert_eq!(trinity.capacity(), capacity);
+
+            // Garbler-only params report the same capacity
+            let garbler = Trinity::from_sender_bytes(&trinity.to_sender_bytes()).unwrap();
+            assert_eq!(garbler.capacity(), capacity);
+
+            let too_long = vec![TrinityChoice::One; capacity + 1];
+            assert_eq!(
+                trinity.create_ot_receiver::<()>(&too_long).err(),
+                Some(TrinityError::InputTooLong {
+                    len: capacity + 1,
+                    capacity
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_setup_with_rng_is_reproducible() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let modes = || {
+            [
+                #[cfg(feature = "plain")]
+                KZGType::Plain,
+                #[cfg(feature = "halo2")]
+                KZGType::Halo2,
+            ]
+        };
+        for (first, second) in modes().into_iter().zip(modes()) {
+            let a = Trinity::setup_with_rng(&mut StdRng::seed_from_u64(7), first, 4);
+            let b = Trinity::setup_with_rng(&mut StdRng::seed_from_u64(7), second, 4);
+            assert_eq!(a.to_sender_bytes(), b.to_sender_bytes());
+            assert_eq!(a.randomness, Randomness::Injected);
+            assert!(a.is_deterministic());
+            assert!(!Trinity::setup(first, 4).is_deterministic());
+        }
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn test_setup_with_degree() {
+        let trinity = Trinity::setup_with_degree(KZGType::Plain, 5).unwrap();
+        assert_eq!(trinity.capacity(), 32);
+
+        for k in [MIN_SETUP_DEGREE - 1, MAX_SETUP_DEGREE + 1] {
+            assert_eq!(
+                Trinity::setup_with_degree(KZGType::Plain, k).err(),
+                Some(TrinityError::InvalidDegree(k))
+            );
+        }
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn test_capacity_boundary_is_accepted() {
+        let trinity = Trinity::setup(KZGType::Plain, 4);
+        let bits = vec![TrinityChoice::One; trinity.capacity()];
+        assert!(trinity.create_ot_receiver::<()>(&bits).is_ok());
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn test_debug_is_short_and_names_mode() {
+        let trinity = Trinity::setup(KZGType::Plain, 64);
+        let full = format!("{:?}", trinity);
+        assert!(full.len() < 200, "debug output too long: {}", full);
+        assert!(full.contains("Plain"));
+        assert!(full.contains("message_length: 64"));
+
+        // A garbler-only setup from the same params shows the same fingerprint
+        let garbler = Trinity::from_sender_bytes(&trinity.to_sender_bytes()).unwrap();
+        let fingerprint = trinity.to_sender_params().unwrap().fingerprint();
+        assert_eq!(fingerprint.len(), 16);
+        assert!(full.contains(&fingerprint));
+        assert!(format!("{:?}", garbler).contains(&fingerprint));
+
+        // Another setup of the same size does not
+        let other = Trinity::setup(KZGType::Plain, 64);
+        assert_ne!(other.to_sender_params().unwrap().fingerprint(), fingerprint);
+
+        let setup = crate::SetupParams {
+            trinity: Arc::new(trinity),
+        };
+        assert!(format!("{:?}", setup).len() < 250);
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn test_sender_bytes_header() {
+        let trinity = Trinity::setup(KZGType::Plain, 4);
+        let bytes = trinity.to_sender_bytes();
+        assert!(bytes.starts_with(&SENDER_BYTES_MAGIC));
+
+        let restored = Trinity::from_sender_bytes(&bytes).unwrap();
+        assert_eq!(restored.to_sender_bytes(), bytes);
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] ^= 0xff;
+        assert_eq!(
+            Trinity::from_sender_bytes(&bad_magic).err(),
+            Some(TrinityError::BadMagic)
+        );
+
+        let mut bad_version = bytes.clone();
+        bad_version[SENDER_BYTES_MAGIC.len()] = 99;
+        assert_eq!(
+            Trinity::from_sender_bytes(&bad_version).err(),
+            Some(TrinityError::UnsupportedVersion(99))
+        );
+
+        let mut bad_tag = bytes.clone();
+        bad_tag[SENDER_BYTES_MAGIC.len() + 1] = 7;
+        assert_eq!(
+            Trinity::from_sender_bytes(&bad_tag).err(),
+            Some(TrinityError::InvalidTagByte(7))
+        );
+
+        // The session id follows the tag and travels to the garbler
+        let id_start = SENDER_BYTES_MAGIC.len() + 2;
+        assert_eq!(bytes[id_start..id_start + 32], trinity.session_id);
+        assert_eq!(restored.session_id, trinity.session_id);
+        assert_eq!(
+            Trinity::from_sender_bytes(&bytes[..id_start + 31]).err(),
+            Some(TrinityError::TruncatedInput)
+        );
+
+        // Version 1 bytes have no session id and still load, with id zero
+        let v1 = [&SENDER_BYTES_MAGIC[..], &[1, 0], &bytes[id_start + 32..]].concat();
+        let from_v1 = Trinity::from_sender_bytes(&v1).unwrap();
+        assert_eq!(from_v1.session_id, [0; 32]);
+        assert_eq!(
+            from_v1.to_sender_bytes(),
+            trinity.clone().with_session_id([0; 32]).to_sender_bytes()
+        );
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn test_plain_receiver_is_owned_and_cloneable() {
+        // Holds the receiver by value, with no lifetime tied to the params
+        struct Evaluator {
+            receiver: TrinityReceiver,
+        }
+
+        let bits = [TrinityChoice::Zero, TrinityChoice::One];
+        let (evaluator, garbler_bytes) = {
+            let trinity = Trinity::setup(KZGType::Plain, 4);
+            let receiver = trinity.create_ot_receiver::<()>(&bits).unwrap();
+            let evaluator = Evaluator {
+                receiver: receiver.trinity_receiver,
+            };
+            (evaluator, trinity.to_sender_bytes())
+        };
+        let copy = evaluator.receiver.clone();
+
+        let garbler = Trinity::from_sender_bytes(&garbler_bytes).unwrap();
+        let sender = garbler
+            .create_ot_sender::<()>(evaluator.receiver.commitment())
+            .unwrap();
+        let (m0, m1) = ([5; MSG_SIZE], [6; MSG_SIZE]);
+        let msg = sender.trinity_sender.send(&mut OsRng, 1, m0, m1).unwrap();
+        assert_eq!(evaluator.receiver.recv(1, msg).unwrap(), m1);
+        assert_eq!(copy.recv(1, msg).unwrap(), m1);
+    }
+
+    #[test]
+    fn test_session_id_separates_sessions() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let modes = [
+            #[cfg(feature = "plain")]
+            KZGType::Plain,
+            #[cfg(feature = "halo2")]
+            KZGType::Halo2,
+        ];
+        for mode in modes {
+            let trinity = Trinity::setup(mode, 4);
+            assert_ne!(trinity.session_id, Trinity::setup(mode, 4).session_id);
+
+            let bits = [TrinityChoice::One, TrinityChoice::Zero];
+            let receiver = trinity.create_ot_receiver::<()>(&bits).unwrap();
+            let com = receiver.trinity_receiver.commitment();
+            let ids = [[1; 32], [2; 32]];
+            let sessions = ids.map(|id| trinity.clone().with_session_id(id));
+            let receivers = ids.map(|id| receiver.trinity_receiver.clone().with_session_id(id));
+
+            // Same commitment, messages and sender randomness in both
+            let (m0, m1) = ([3; MSG_SIZE], [4; MSG_SIZE]);
+            let msgs = sessions.each_ref().map(|session| {
+                // The garbler learns the id from the sender bytes
+                let garbler = Trinity::from_sender_bytes(&session.to_sender_bytes()).unwrap();
+                let sender = garbler.create_ot_sender::<()>(com).unwrap();
+                sender
+                    .trinity_sender
+                    .send(&mut StdRng::seed_from_u64(5), 0, m0, m1)
+                    .unwrap()
+            });
+            assert_ne!(msgs[0].to_bytes(), msgs[1].to_bytes());
+
+            for (receiver, msg) in receivers.iter().zip(msgs) {
+                assert_eq!(receiver.recv(0, msg).unwrap(), m1);
+            }
+            assert_ne!(receivers[0].recv(0, msgs[1]).unwrap(), m1);
+        }
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn test_sender_bytes_compressed_roundtrip() {
+        let trinity = Trinity::setup(KZGType::Plain, 64);
+        let uncompressed = trinity.to_sender_bytes();
+        let compressed = trinity.to_sender_bytes_compressed();
+        assert_eq!(compressed[SENDER_BYTES_MAGIC.len() + 1], 2);
+        assert!(compressed.len() * 10 < uncompressed.len() * 6);
+
+        // Either encoding restores the same params
+        for bytes in [&uncompressed, &compressed] {
+            let restored = Trinity::from_sender_bytes(bytes).unwrap();
+            assert_eq!(restored.to_sender_bytes(), uncompressed);
+            assert_eq!(restored.to_sender_bytes_compressed(), compressed);
+        }
+
+        let truncated = &compressed[..compressed.len() - 1];
+        assert_eq!(
+            Trinity::from_sender_bytes(truncated).err(),
+            Some(TrinityError::DeserializeCommitmentKey)
+        );
+    }
+
+    #[cfg(feature = "halo2")]
+    #[test]
+    fn test_halo2_params_bytes_reject_malformed_input() {
+        let trinity = Trinity::setup(KZGType::Halo2, 4);
+
+        // Truncated anywhere, the header included
+        let sender = trinity.to_sender_bytes();
+        for len in 0..sender.len() {
+            assert!(Trinity::from_sender_bytes(&sender[..len]).is_err());
+        }
+        let verifier = trinity.to_verifier_bytes(8).unwrap();
+        for len in [0, 1, 4, 5, 100, verifier.len() - 1] {
+            assert!(Trinity::from_verifier_bytes(&verifier[..len]).is_err());
+        }
+        let full = trinity.to_full_params_bytes();
+        for len in [0, 1, 32, 33, 40, full.len() - 1] {
+            assert!(Trinity::from_full_params_bytes(&full[..len]).is_err());
+        }
+
+        // Unknown tags
+        let mut bad_tag = full.clone();
+        bad_tag[FULL_PARAMS_MAGIC.len() + 1] = 9;
+        assert_eq!(
+            Trinity::from_full_params_bytes(&bad_tag).err(),
+            Some(TrinityError::InvalidTagByte(9))
+        );
+        let mut bad_tag = verifier.clone();
+        bad_tag[0] = 0;
+        assert_eq!(
+            Trinity::from_verifier_bytes(&bad_tag).err(),
+            Some(TrinityError::InvalidTagByte(0))
+        );
+
+        // A k outside the setup range, which would size `1 << k` buffers
+        let k_start = SENDER_BYTES_MAGIC.len() + 2 + 32;
+        for k in [MIN_SETUP_DEGREE - 1, MAX_SETUP_DEGREE + 1] {
+            let mut bad_k = sender.clone();
+            bad_k[k_start..k_start + 4].copy_from_slice(&k.to_le_bytes());
+            assert_eq!(
+                Trinity::from_sender_bytes(&bad_k).err(),
+                Some(TrinityError::InvalidDegree(k))
+            );
+        }
+        let mut bad_k = sender.clone();
+        bad_k[k_start..k_start + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(Trinity::from_sender_bytes(&bad_k).is_err());
+    }
+
+    #[cfg(feature = "halo2")]
+    #[test]
+    fn test_equal_commitments_dedup_in_hash_set() {
+        use std::collections::HashSet;
+
+        let g = Halo2G1Affine::generator();
+        let a = TrinityCom::Halo2(g.into(), [0; 32]);
+        // Same point, different projective representation
+        let b = TrinityCom::Halo2((g + g) - g, [0; 32]);
+        let c = TrinityCom::Halo2(g + g, [0; 32]);
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+
+        let set: HashSet<TrinityCom> = [a, b, c].into_iter().collect();
+        assert_eq!(set.len(), 2);
+
+        // Still equal after a serialization roundtrip
+        assert_eq!(TrinityCom::deserialize(&a.serialize()).unwrap(), a);
+    }
+
+    #[test]
+    fn test_try_serialize_propagates_serde_errors() {
+        struct Unserializable;
+
+        impl Serialize for Unserializable {
+            fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+                Err(serde::ser::Error::custom("refused"))
+            }
+        }
+
+        assert_eq!(
+            to_json(&Unserializable),
+            Err(TrinityError::Serialize("refused".to_string()))
+        );
+
+        #[cfg(feature = "halo2")]
+        {
+            let com = TrinityCom::Halo2(Halo2G1Affine::generator().into(), [0; 32]);
+            assert_eq!(com.try_serialize(), Ok(com.serialize()));
+        }
+    }
+
+    #[cfg(all(feature = "plain", feature = "halo2"))]
+    #[test]
+    fn test_create_ot_sender_mismatched_commitment() {
+        let trinity = Trinity::setup(KZGType::Plain, 4);
+        let halo2_com =
+            TrinityCom::Halo2(halo2curves::bn256::G1Affine::generator().into(), [0; 32]);
+
+        let result = trinity.create_ot_sender::<()>(halo2_com);
+        assert_eq!(
+            result.err(),
+            Some(TrinityError::ModeMismatch {
+                expected: KZGType::Plain,
+                got: KZGType::Halo2
+            })
+        );
+    }
+
+    #[test]
+    fn test_setup_for_circuit_covers_inputs() {
+        use mpz_circuits::{types::ValueType, CircuitBuilder};
+
+        let builder = CircuitBuilder::new();
+        let input = builder.add_input_by_type(ValueType::Array(Box::new(ValueType::Bit), 40));
+        builder.add_output(input);
+        let circuit = builder.build().unwrap();
+
+        let modes = [
+            #[cfg(feature = "plain")]
+            KZGType::Plain,
+            #[cfg(feature = "halo2")]
+            KZGType::Halo2,
+        ];
+        for mode in modes {
+            let trinity = Trinity::setup_for_circuit(mode, &circuit).unwrap();
+            assert_eq!(trinity.mode(), mode);
+            assert_eq!(trinity.capacity(), 64);
+
+            let bits = vec![TrinityChoice::One; 40];
+            assert!(trinity.create_ot_receiver::<()>(&bits).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_builder_sizes_params() {
+        use mpz_circuits::{types::ValueType, CircuitBuilder};
+
+        let builder = CircuitBuilder::new();
+        let input = builder.add_input_by_type(ValueType::Array(Box::new(ValueType::Bit), 40));
+        builder.add_output(input);
+        let circuit = builder.build().unwrap();
+
+        let modes = [
+            #[cfg(feature = "plain")]
+            KZGType::Plain,
+            #[cfg(feature = "halo2")]
+            KZGType::Halo2,
+        ];
+        for mode in modes {
+            let trinity = Trinity::builder()
+                .mode(mode)
+                .message_length(20)
+                .build()
+                .unwrap();
+            assert_eq!(trinity.mode(), mode);
+            assert_eq!(trinity.capacity(), 32);
+
+            let trinity = Trinity::builder()
+                .mode(mode)
+                .from_circuit(&circuit)
+                .build()
+                .unwrap();
+            assert_eq!(trinity.capacity(), 64);
+        }
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn test_builder_uses_given_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let build = || {
+            Trinity::builder()
+                .mode(KZGType::Plain)
+                .message_length(16)
+                .rng(StdRng::seed_from_u64(7))
+                .build()
+                .unwrap()
+        };
+        assert_eq!(
+            build().to_full_params_bytes(),
+            build().to_full_params_bytes()
+        );
+        assert!(build().is_deterministic());
+
+        let default_rng = Trinity::builder()
+            .mode(KZGType::Plain)
+            .message_length(16)
+            .build()
+            .unwrap();
+        assert!(!default_rng.is_deterministic());
+    }
+
+    #[test]
+    fn test_builder_rejects_bad_config() {
+        use mpz_circuits::{types::ValueType, CircuitBuilder};
+
+        let builder = CircuitBuilder::new();
+        let input = builder.add_input_by_type(ValueType::Array(Box::new(ValueType::Bit), 8));
+        builder.add_output(input);
+        let circuit = builder.build().unwrap();
+
+        #[cfg(feature = "plain")]
+        let mode = KZGType::Plain;
+        #[cfg(not(feature = "plain"))]
+        let mode = KZGType::Halo2;
+        let conflicting = Trinity::builder()
+            .mode(mode)
+            .message_length(8)
+            .from_circuit(&circuit)
+            .build();
+        assert!(matches!(
+            conflicting.err(),
+            Some(TrinityError::InvalidSetupConfig(_))
+        ));
+
+        let unsized_build = Trinity::builder().mode(mode).build();
+        assert!(matches!(
+            unsized_build.err(),
+            Some(TrinityError::InvalidSetupConfig(_))
+        ));
+        let no_mode = Trinity::builder().message_length(8).build();
+        assert!(matches!(
+            no_mode.err(),
+            Some(TrinityError::InvalidSetupConfig(_))
+        ));
+
+        assert!(matches!(
+            Trinity::builder()
+                .mode(mode)
+                .message_length(1 << (MAX_SETUP_DEGREE + 1))
+                .build()
+                .err(),
+            Some(TrinityError::InputTooLong { .. })
+        ));
+    }
+
+    #[test]
+    fn test_estimate_params_bytes() {
+        let k = 10;
+        let modes = [
+            #[cfg(feature = "plain")]
+            KZGType::Plain,
+            #[cfg(feature = "halo2")]
+            KZGType::Halo2,
+        ];
+        for mode in modes {
+            let actual = Trinity::setup_with_degree(mode, k as u32)
+                .unwrap()
+                .to_full_params_bytes()
+                .len();
+            assert_eq!(estimate_params_bytes(mode, k), actual, "{}", mode);
+        }
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn test_plain_g1_bytes_roundtrip() {
+        let trinity = Trinity::setup(KZGType::Plain, 4);
+        let receiver = trinity
+            .create_ot_receiver::<()>(&[TrinityChoice::One, TrinityChoice::Zero])
+            .unwrap();
+        let com = receiver.trinity_receiver.commitment();
+
+        let bytes = com.to_g1_bytes().unwrap();
+        assert_eq!(bytes.len(), 32);
+        let point = G1Affine::deserialize_compressed(&bytes[..]).unwrap();
+        assert!(TrinityCom::Plain(point.into(), com.domain()) == com);
+        // Same point bytes as the tagged encoding, between tag and domain
+        assert_eq!(com.to_tagged_bytes()[1..33], bytes);
+    }
+
+    #[cfg(feature = "halo2")]
+    #[test]
+    fn test_verifier_params_check_proof_only() {
+        let trinity = Trinity::setup(KZGType::Halo2, 4);
+        let bits = vec![TrinityChoice::One, TrinityChoice::Zero, TrinityChoice::One];
+        let ot_receiver = trinity.create_ot_receiver::<()>(&bits).unwrap();
+        let commitment = ot_receiver.trinity_receiver.commitment();
+        let proof = match &ot_receiver.trinity_receiver {
+            TrinityReceiver::Halo2(recv) => recv.proof.clone(),
+            #[allow(unreachable_patterns)]
+            _ => unreachable!(),
+        };
+
+        let verifier_bytes = trinity.to_verifier_bytes(bits.len()).unwrap();
+        let verifier = Trinity::from_verifier_bytes(&verifier_bytes).unwrap();
+        assert_eq!(verifier.mode(), KZGType::Halo2);
+        assert_eq!(verifier.capacity(), trinity.capacity());
+        assert_eq!(
+            verifier.to_verifier_bytes(bits.len()).unwrap(),
+            verifier_bytes
+        );
+        assert_eq!(
+            verifier.to_verifier_bytes(bits.len() + 1).err(),
+            Some(TrinityError::InvalidProofLength)
+        );
+
+        assert_eq!(
+            verifier.verify_commitment_proof(&commitment, &proof, bits.len()),
+            Ok(())
+        );
+        let mut tampered = proof.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert_eq!(
+            verifier.verify_commitment_proof(&commitment, &tampered, bits.len()),
+            Err(TrinityError::InvalidProof)
+        );
+
+        assert_eq!(
+            verifier.verify_commitment_proof(&commitment, &proof, bits.len() + 1),
+            Err(TrinityError::InvalidProofLength)
+        );
+
+        // No receiver, and no powers of `s` to compute its openings from:
+        // the bytes don't contain the full params' `g[1]`
+        assert_eq!(
+            verifier.create_ot_receiver::<()>(&bits).err(),
+            Some(TrinityError::CreateReceiverFromVerifierParams)
+        );
+        let g1 = match &trinity.params {
+            TrinityInnerParams::Full(TrinityParams::Halo2(params)) => {
+                params.params.g[1].to_raw_bytes()
+            }
+            #[allow(unreachable_patterns)]
+            _ => unreachable!(),
+        };
+        assert!(!verifier_bytes.windows(g1.len()).any(|w| w == g1.as_slice()));
+        match &verifier.params {
+            TrinityInnerParams::Verifier(params) => assert_eq!(params.kzg_params().g.len(), 1),
+            _ => unreachable!(),
+        }
+
+        let garbler = Trinity::from_sender_bytes(&trinity.to_sender_bytes()).unwrap();
+        assert_eq!(
+            garbler.to_verifier_bytes(bits.len()).err(),
+            Some(TrinityError::NoVerifierParams)
+        );
+        assert_eq!(
+            Trinity::from_verifier_bytes(&[0]).err(),
+            Some(TrinityError::InvalidTagByte(0))
+        );
+    }
+
+    #[cfg(all(feature = "plain", feature = "halo2"))]
+    #[test]
+    fn test_halo2_sender_bytes_reject_plain_commitment() {
+        let plain = Trinity::setup(KZGType::Plain, 4);
+        let receiver = plain
+            .create_ot_receiver::<()>(&[TrinityChoice::One])
+            .unwrap();
+        let plain_com = receiver.trinity_receiver.commitment();
+
+        let halo2 = Trinity::setup(KZGType::Halo2, 4);
+        let garbler = Trinity::from_sender_bytes(&halo2.to_sender_bytes()).unwrap();
+        assert_eq!(garbler.mode(), KZGType::Halo2);
+
+        let result = garbler.create_ot_sender::<()>(plain_com);
+        assert_eq!(
+            result.err(),
+            Some(TrinityError::ModeMismatch {
+                expected: KZGType::Halo2,
+                got: KZGType::Plain
+            })
+        );
+    }
+
+    #[cfg(all(feature = "plain", feature = "halo2"))]
+    #[test]
+    fn test_sender_checks_commitment_against_params_mode() {
+        let plain = Trinity::setup(KZGType::Plain, 4);
+        let receiver = plain
+            .create_ot_receiver::<()>(&[TrinityChoice::Zero])
+            .unwrap();
+        let plain_com = receiver.trinity_receiver.commitment();
+
+        let halo2 = Trinity::setup(KZGType::Halo2, 4);
+        let sender_params = halo2.to_sender_params().unwrap();
+        assert_eq!(sender_params.mode(), KZGType::Halo2);
+        assert_eq!(plain.to_sender_params().unwrap().mode(), KZGType::Plain);
+
+        // `mode` is a public field and can disagree with the params
+        let mislabeled = Trinity {
+            mode: KZGType::Plain,
+            params: TrinityInnerParams::Sender(sender_params),
+            randomness: Randomness::Secure,
+            session_id: [0; 32],
+        };
+        assert_eq!(
+            mislabeled.create_ot_sender::<()>(plain_com).err(),
+            Some(TrinityError::ModeMismatch {
+                expected: KZGType::Halo2,
+                got: KZGType::Plain
+            })
+        );
+    }
+
+    #[cfg(all(feature = "plain", feature = "halo2"))]
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!("Plain".parse::<KZGType>(), Ok(KZGType::Plain));
+        assert_eq!("Halo2".parse::<KZGType>(), Ok(KZGType::Halo2));
+        assert_eq!("halo2".parse::<KZGType>(), Ok(KZGType::Halo2));
+        assert_eq!(
+            "Groth16".parse::<KZGType>(),
+            Err(ParseModeError::Unknown("Groth16".to_string()))
+        );
+
+        for mode in [KZGType::Plain, KZGType::Halo2] {
+            assert_eq!(mode.to_string().parse::<KZGType>(), Ok(mode));
+        }
+    }
 }
@@ -0,0 +1,253 @@
+//! Conversions between integer values and the LSB0 bit vectors fed to and
+//! produced by circuits
+
+use std::io::{Error, ErrorKind};
+
+use mpz_circuits::types::ValueType;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::evaluate::{decode_outputs, pack_lsb0};
+
+/// Bits in LSB0 order, the order circuits take their inputs in: bit `i` of
+/// a value sits at index `i`, and bytes are laid out one after the other.
+/// Building these through the constructors below rather than from a bare
+/// `Vec<bool>` keeps MSB0 bits from reaching a circuit by accident.
+///
+/// The bits are usually a party's private input, so they are wiped on drop.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Lsb0Bits(Vec<bool>);
+
+impl Lsb0Bits {
+    /// Wrap bits the caller already has in LSB0 order
+    pub fn new(bits: Vec<bool>) -> Self {
+        Lsb0Bits(bits)
+    }
+
+    pub fn from_u8(value: u8) -> Self {
+        Lsb0Bits((0..8).map(|i| (value >> i) & 1 == 1).collect())
+    }
+
+    pub fn from_u16(value: u16) -> Self {
+        Lsb0Bits((0..16).map(|i| (value >> i) & 1 == 1).collect())
+    }
+
+    /// Eight bits per byte, LSB0 within each byte
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Lsb0Bits(
+            bytes
+                .iter()
+                .flat_map(|&byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+                .collect(),
+        )
+    }
+
+    /// `None` unless there are exactly 8 bits
+    pub fn to_u8(&self) -> Option<u8> {
+        (self.0.len() == 8).then(|| pack_lsb0(&self.0)[0])
+    }
+
+    /// `None` unless there are exactly 16 bits
+    pub fn to_u16(&self) -> Option<u16> {
+        (self.0.len() == 16).then(|| {
+            let bytes = pack_lsb0(&self.0);
+            u16::from_le_bytes([bytes[0], bytes[1]])
+        })
+    }
+
+    /// Inverse of `from_bytes`, zero-padding the last byte
+    pub fn to_bytes(&self) -> Vec<u8> {
+        pack_lsb0(&self.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[bool] {
+        &self.0
+    }
+
+    pub fn into_inner(mut self) -> Vec<bool> {
+        std::mem::take(&mut self.0)
+    }
+}
+
+impl Zeroize for Lsb0Bits {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for Lsb0Bits {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Lsb0Bits {}
+
+/// Pack one integer per scalar of `types` into LSB0 bits. Arrays take one
+/// value per element, e.g. `Array(U8, 4)` consumes four values.
+pub fn pack_inputs(values: &[u64], types: &[ValueType]) -> Result<Vec<bool>, Error> {
+    let widths = scalar_widths(types)?;
+    if values.len() != widths.len() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Expected {} input values, got {}",
+                widths.len(),
+                values.len()
+            ),
+        ));
+    }
+
+    let mut bits = Vec::with_capacity(widths.iter().sum());
+    for (&value, &width) in values.iter().zip(&widths) {
+        if width < 64 && value >> width != 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Value {} does not fit in {} bits", value, width),
+            ));
+        }
+        bits.extend((0..width).map(|i| (value >> i) & 1 == 1));
+    }
+    Ok(bits)
+}
+
+/// Inverse of `pack_inputs`: one integer per scalar of `types`
+pub fn unpack_outputs(bits: &[bool], types: &[ValueType]) -> Result<Vec<u64>, Error> {
+    decode_outputs(bits, &scalar_widths(types)?)
+}
+
+/// Integer type of the given bit width, for callers (like JS) that describe
+/// inputs by width rather than by `ValueType`
+pub fn value_type_for_width(width: usize) -> Result<ValueType, Error> {
+    match width {
+        1 => Ok(ValueType::Bit),
+        8 => Ok(ValueType::U8),
+        16 => Ok(ValueType::U16),
+        32 => Ok(ValueType::U32),
+        64 => Ok(ValueType::U64),
+        _ => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Unsupported input width {}", width),
+        )),
+    }
+}
+
+fn scalar_widths(types: &[ValueType]) -> Result<Vec<usize>, Error> {
+    let mut widths = Vec::new();
+    for ty in types {
+        push_scalar_widths(ty, &mut widths)?;
+    }
+    Ok(widths)
+}
+
+fn push_scalar_widths(ty: &ValueType, widths: &mut Vec<usize>) -> Result<(), Error> {
+    match ty {
+        ValueType::Bit => widths.push(1),
+        ValueType::U8 => widths.push(8),
+        ValueType::U16 => widths.push(16),
+        ValueType::U32 => widths.push(32),
+        ValueType::U64 => widths.push(64),
+        ValueType::Array(elem, len) => {
+            for _ in 0..*len {
+                push_scalar_widths(elem, widths)?;
+            }
+        }
+        other => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unsupported value type {:?}", other),
+            ))
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_scalars() {
+        assert_eq!(pack_inputs(&[1], &[ValueType::Bit]).unwrap(), vec![true]);
+        assert_eq!(
+            pack_inputs(&[0b0000_0110], &[ValueType::U8]).unwrap(),
+            vec![false, true, true, false, false, false, false, false]
+        );
+
+        let bits = pack_inputs(&[0x0102], &[ValueType::U16]).unwrap();
+        assert_eq!(bits.len(), 16);
+        assert!(bits[1] && bits[8]);
+        assert_eq!(bits.iter().filter(|&&b| b).count(), 2);
+    }
+
+    #[test]
+    fn pack_arrays_roundtrip() {
+        let types = [
+            ValueType::Array(Box::new(ValueType::Bit), 3),
+            ValueType::Array(Box::new(ValueType::U8), 2),
+            ValueType::U16,
+        ];
+        let values = [1, 0, 1, 200, 7, 40_000];
+
+        let bits = pack_inputs(&values, &types).unwrap();
+        assert_eq!(bits.len(), 3 + 16 + 16);
+        assert_eq!(unpack_outputs(&bits, &types).unwrap(), values);
+    }
+
+    #[test]
+    fn pack_rejects_bad_input() {
+        // Value too wide for its type
+        assert!(pack_inputs(&[256], &[ValueType::U8]).is_err());
+        // Wrong number of values
+        assert!(pack_inputs(&[1, 2], &[ValueType::U8]).is_err());
+        assert!(value_type_for_width(12).is_err());
+    }
+
+    #[test]
+    fn lsb0_bits_roundtrip_u8() {
+        let bits = Lsb0Bits::from_u8(0b0000_0110);
+        assert_eq!(
+            bits.as_slice(),
+            [false, true, true, false, false, false, false, false]
+        );
+        for value in [0, 1, 0x80, 0xa5, u8::MAX] {
+            assert_eq!(Lsb0Bits::from_u8(value).to_u8(), Some(value));
+        }
+        assert_eq!(Lsb0Bits::from_u16(1).to_u8(), None);
+    }
+
+    #[test]
+    fn lsb0_bits_roundtrip_u16() {
+        let bits = Lsb0Bits::from_u16(0x0102);
+        assert!(bits.as_slice()[1] && bits.as_slice()[8]);
+        assert_eq!(
+            bits.as_slice(),
+            pack_inputs(&[0x0102], &[ValueType::U16]).unwrap()
+        );
+        for value in [0, 10, 0x8001, 40_000, u16::MAX] {
+            assert_eq!(Lsb0Bits::from_u16(value).to_u16(), Some(value));
+        }
+        assert_eq!(Lsb0Bits::from_u8(1).to_u16(), None);
+    }
+
+    #[test]
+    fn lsb0_bits_roundtrip_bytes() {
+        let bytes = [0x02, 0x01, 0xff, 0x00];
+        let bits = Lsb0Bits::from_bytes(&bytes);
+        assert_eq!(bits.len(), 32);
+        assert_eq!(bits.to_bytes(), bytes);
+        // Two bytes are the little-endian encoding of a u16
+        assert_eq!(
+            Lsb0Bits::from_bytes(&bytes[..2]),
+            Lsb0Bits::from_u16(0x0102)
+        );
+        assert!(Lsb0Bits::from_bytes(&[]).is_empty());
+    }
+}
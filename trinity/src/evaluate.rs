@@ -1,42 +1,46 @@
-use std::io::Error;
+use std::io::{Error, ErrorKind};
 use std::sync::Arc;
 
 use mpz_circuits::Circuit;
 use mpz_core::Block;
 use mpz_garble_core::{evaluate_garbled_circuits, EvaluatorOutput, GarbledCircuit, Mac};
-
-use itybity::FromBitIterator;
+use zeroize::Zeroizing;
 
 use crate::commit::{TrinityChoice, TrinityCom, TrinityMsg};
-use crate::garble::{GarbledBundle, SerializableGarbledCircuit};
+use crate::encoding::Lsb0Bits;
+use crate::garble::{decode_output_pointers, GarbledBundle, SerializableGarbledCircuit};
 use crate::ot::KZGOTReceiver;
 use crate::SetupParams;
 
-pub struct EvaluatorBundle<'a> {
-    pub ot_receiver: KZGOTReceiver<'a, ()>,
+pub struct EvaluatorBundle {
+    pub ot_receiver: KZGOTReceiver<()>,
     pub receiver_commitment: TrinityCom,
 }
 
 pub fn ev_commit(
-    ev_inputs: Vec<bool>,
+    ev_inputs: Lsb0Bits,
     setup_params: &SetupParams,
 ) -> Result<EvaluatorBundle, Error> {
-    let ev_trinity: Vec<TrinityChoice> = ev_inputs
-        .into_iter()
-        .map(|b| {
-            if b {
-                TrinityChoice::One
-            } else {
-                TrinityChoice::Zero
-            }
-        })
-        .collect();
+    ev_commit_with_progress(ev_inputs, setup_params, None)
+}
+
+/// Like `ev_commit`, reporting the completed fraction of the receiver setup
+/// to `progress`
+pub fn ev_commit_with_progress(
+    ev_inputs: Lsb0Bits,
+    setup_params: &SetupParams,
+    progress: Option<&dyn Fn(f32)>,
+) -> Result<EvaluatorBundle, Error> {
+    // The packed bytes and the mapped choices are wiped when this returns,
+    // `ev_inputs` wipes itself
+    let ev_bytes = Zeroizing::new(ev_inputs.to_bytes());
+    let ev_trinity = Zeroizing::new(TrinityChoice::unpack(&ev_bytes, ev_inputs.len()));
 
     // === Evaluator: prepare OT receiver and commitment ===
     let ot_receiver = setup_params
         .trinity
-        .create_ot_receiver::<()>(&ev_trinity)
-        .expect("Error while create the ot receiver.");
+        .create_ot_receiver_with_progress::<()>(&ev_trinity, progress)
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, e.to_string()))?;
     let receiver_commitment = ot_receiver.trinity_receiver.commitment();
 
     Ok(EvaluatorBundle {
@@ -45,25 +49,62 @@ pub fn ev_commit(
     })
 }
 
+/// Evaluate `garbler_bundle` on the evaluator's labels from OT.
+///
+/// The bundle is consumed: it was garbled for this evaluator's commitment
+/// alone, see `GarbledBundle`.
 pub fn evaluate_circuit(
     circuit: Arc<Circuit>,
     garbler_bundle: GarbledBundle,
-    evaluator_bits: Vec<bool>,
-    ot_receiver: KZGOTReceiver<'_, ()>,
+    evaluator_bits: Lsb0Bits,
+    ot_receiver: &KZGOTReceiver<()>,
 ) -> Result<Vec<bool>, Error> {
+    let (output, _) =
+        evaluate_circuit_with_macs(circuit, garbler_bundle, evaluator_bits, ot_receiver)?;
+    Ok(output)
+}
+
+/// Same as `evaluate_circuit`, also returning the output MACs the bits were
+/// decoded from, e.g. to let a third party re-check the decoding against the
+/// bundle's `decoding_bits` in a dispute.
+///
+/// The MACs are the evaluator's output labels: together with the decoding
+/// bits they show the output, and revealing them mid-protocol lets the
+/// garbler tie labels to values. Only hand them out once the protocol has
+/// completed.
+pub fn evaluate_circuit_with_macs(
+    circuit: Arc<Circuit>,
+    garbler_bundle: GarbledBundle,
+    evaluator_bits: Lsb0Bits,
+    ot_receiver: &KZGOTReceiver<()>,
+) -> Result<(Vec<bool>, Vec<Mac>), Error> {
+    garbler_bundle
+        .validate(&circuit, evaluator_bits.len())
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
+
     let evaluator_input_size = evaluator_bits.len();
-    let garbler_input_size = circuit.input_len() - evaluator_input_size;
+    let garbler_input_size = circuit
+        .input_len()
+        .checked_sub(evaluator_input_size)
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "Evaluator input is longer than the circuit input",
+            )
+        })?;
 
     let mut all_input_macs = garbler_bundle.all_input_macs.clone();
 
     // Replace the placeholder MACs with real ones from OT
     for i in 0..evaluator_input_size {
-        let serialized_ciphertext = &garbler_bundle.ciphertexts[i];
-        let ciphertext = TrinityMsg::try_from(serialized_ciphertext.clone())
-            .expect("Error while converting ciphertext.");
+        let ciphertext = TrinityMsg::from_bytes(&garbler_bundle.ciphertexts()[i])
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
 
         // Get MAC via OT
-        let decrypted = ot_receiver.trinity_receiver.recv(i, ciphertext);
+        let decrypted = ot_receiver
+            .trinity_receiver
+            .recv(i, ciphertext)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
         let block = Block::new(decrypted);
 
         // Replace the placeholder at the correct position
@@ -75,20 +116,133 @@ pub fn evaluate_circuit(
         SerializableGarbledCircuit::from(garbler_bundle.garbled_circuit).into();
 
     // Evaluate the circuit with these input MACs
-    let outputs =
-        evaluate_garbled_circuits(vec![(circuit, all_input_macs, garbled_circuit)]).unwrap();
+    let outputs = evaluate_garbled_circuits(vec![(circuit, all_input_macs, garbled_circuit)])
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
 
     let EvaluatorOutput {
         outputs: output_macs,
-    } = &outputs[0];
+    } = outputs
+        .into_iter()
+        .next()
+        .expect("one circuit was evaluated");
 
     // Create the final output using the decoding bits
-    let output: Vec<bool> = Vec::from_lsb0_iter(
-        output_macs
-            .iter()
-            .enumerate()
-            .map(|(i, mac)| mac.pointer() ^ garbler_bundle.decoding_bits[i]),
-    );
+    let pointers: Vec<bool> = output_macs.iter().map(|mac| mac.pointer()).collect();
+    let output = decode_output_pointers(&pointers, garbler_bundle.decoding_bits());
 
-    Ok(output)
+    Ok((output, output_macs))
+}
+
+/// Same as `evaluate_circuit`, but reassembles the LSB0 output bits into one
+/// integer per entry of `output_widths` (in bits, at most 64 each)
+pub fn evaluate_circuit_typed(
+    circuit: Arc<Circuit>,
+    garbler_bundle: GarbledBundle,
+    evaluator_bits: Lsb0Bits,
+    ot_receiver: &KZGOTReceiver<()>,
+    output_widths: &[usize],
+) -> Result<Vec<u64>, Error> {
+    let output = evaluate_circuit(circuit, garbler_bundle, evaluator_bits, ot_receiver)?;
+    decode_outputs(&output, output_widths)
+}
+
+/// Split LSB0 output bits into consecutive integers of the given bit widths.
+/// Widths don't need to be a multiple of 8, but must sum to `bits.len()`
+pub fn decode_outputs(bits: &[bool], output_widths: &[usize]) -> Result<Vec<u64>, Error> {
+    let total: usize = output_widths.iter().sum();
+    if total != bits.len() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Output widths sum to {} bits, circuit produced {}",
+                total,
+                bits.len()
+            ),
+        ));
+    }
+    if let Some(width) = output_widths.iter().find(|&&w| w > 64) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Output width {} exceeds 64 bits", width),
+        ));
+    }
+
+    let mut offset = 0;
+    let values = output_widths
+        .iter()
+        .map(|&width| {
+            let value = bits[offset..offset + width]
+                .iter()
+                .enumerate()
+                .fold(0u64, |acc, (i, &bit)| acc | (u64::from(bit) << i));
+            offset += width;
+            value
+        })
+        .collect();
+
+    Ok(values)
+}
+
+/// Pack LSB0 output bits into bytes, the first bit landing in the lowest bit
+/// of the first byte. The last byte is zero-padded.
+pub fn pack_lsb0(bits: &[bool]) -> Vec<u8> {
+    bits.chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |acc, (i, &bit)| acc | (u8::from(bit) << i))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_outputs, pack_lsb0};
+
+    fn to_bits(value: u64, width: usize) -> Vec<bool> {
+        (0..width).map(|i| (value >> i) & 1 == 1).collect()
+    }
+
+    #[test]
+    fn pack_pads_last_byte() {
+        assert_eq!(pack_lsb0(&to_bits(0x2ab, 10)), vec![0xab, 0x02]);
+        assert!(pack_lsb0(&[]).is_empty());
+    }
+
+    #[test]
+    fn decode_single_output() {
+        assert_eq!(decode_outputs(&to_bits(10, 16), &[16]).unwrap(), vec![10]);
+    }
+
+    #[test]
+    fn decode_multiple_outputs_with_odd_widths() {
+        let mut bits = to_bits(5, 3);
+        bits.extend(to_bits(1000, 13));
+        bits.extend(to_bits(1, 1));
+
+        assert_eq!(
+            decode_outputs(&bits, &[3, 13, 1]).unwrap(),
+            vec![5, 1000, 1]
+        );
+    }
+
+    #[test]
+    fn decode_rejects_width_mismatch() {
+        assert!(decode_outputs(&to_bits(10, 16), &[8]).is_err());
+        assert!(decode_outputs(&to_bits(0, 65), &[65]).is_err());
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn ev_commit_rejects_input_past_capacity() {
+        use super::ev_commit;
+        use crate::{commit::KZGType, encoding::Lsb0Bits, two_pc::setup_with_degree};
+        use std::io::ErrorKind;
+
+        let setup = setup_with_degree(KZGType::Plain, 4).unwrap();
+        let too_long = Lsb0Bits::new(vec![true; setup.trinity.capacity() + 1]);
+        let err = ev_commit(too_long, &setup).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
 }
@@ -1,3 +1,4 @@
+use std::io::{Error, ErrorKind, Read, Write};
 use std::sync::Arc;
 
 use mpz_circuits::Circuit;
@@ -6,10 +7,8 @@ use mpz_garble_core::{Delta, EncryptedGate, GarbledCircuit, Generator, Generator
 use rand::{rngs::StdRng, Rng};
 use serde::{Deserialize, Serialize};
 
-use crate::{
-    commit::{Trinity, TrinityCom, TrinityMsg},
-    SerializableTrinityMsg,
-};
+use crate::commit::{Randomness, Trinity, TrinityCom, TrinityError, TrinityMsg};
+use crate::encoding::Lsb0Bits;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SerializableGarbledCircuit {
@@ -32,23 +31,469 @@ impl From<SerializableGarbledCircuit> for GarbledCircuit {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Everything the evaluator needs from the garbler, in its wire format.
+///
+/// `to_bytes` / `from_bytes` are the stable encoding used across the WASM
+/// boundary, so callers should not depend on the field layout directly.
+///
+/// A bundle is garbled for one evaluator and is evaluated once: evaluation
+/// takes it by value and it is deliberately not `Clone`, so it can't be
+/// handed to a second evaluation by accident.
+///
+/// ```compile_fail
+/// use trinity::GarbledBundle;
+///
+/// fn evaluate_twice(bundle: GarbledBundle) -> (GarbledBundle, GarbledBundle) {
+///     (bundle.clone(), bundle)
+/// }
+/// ```
+#[derive(Debug, Serialize, Deserialize)]
 pub struct GarbledBundle {
-    pub ciphertexts: Vec<SerializableTrinityMsg>,
+    pub ciphertexts: Vec<Vec<u8>>, // TrinityMsg::to_bytes
     pub garbled_circuit: SerializableGarbledCircuit,
     pub decoding_bits: Vec<bool>,
     pub all_input_macs: Vec<Mac>,
+    /// `compute_digest` as computed by the garbler, checked by `validate`.
+    /// `None` skips the check.
+    pub digest: Option<[u8; 32]>,
+}
+
+impl GarbledBundle {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode::serialize(self).expect("Failed to serialize GarbledBundle")
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(bytes)
+    }
+
+    /// Number of OT ciphertexts in a serialized bundle. Reads the bincode
+    /// length prefix of `ciphertexts`, which must stay the first field, so
+    /// the rest of the bundle is never deserialized.
+    pub fn ciphertext_count_from_bytes(bytes: &[u8]) -> Option<usize> {
+        let prefix: [u8; 8] = bytes.get(..8)?.try_into().ok()?;
+        usize::try_from(u64::from_le_bytes(prefix)).ok()
+    }
+
+    /// OT ciphertexts for the evaluator input bits, one `TrinityMsg::to_bytes`
+    /// encoding per bit
+    pub fn ciphertexts(&self) -> &[Vec<u8>] {
+        &self.ciphertexts
+    }
+
+    /// Iterate the OT ciphertexts in evaluator input order
+    ///
+    /// ```
+    /// use trinity::GarbledBundle;
+    ///
+    /// fn ciphertext_sizes(bundle: &GarbledBundle) -> Vec<usize> {
+    ///     bundle.iter_ciphertexts().map(|ciphertext| ciphertext.len()).collect()
+    /// }
+    /// ```
+    pub fn iter_ciphertexts(&self) -> impl Iterator<Item = &[u8]> {
+        self.ciphertexts.iter().map(Vec::as_slice)
+    }
+
+    /// Number of OT ciphertexts, one per evaluator input bit
+    pub fn num_ciphertexts(&self) -> usize {
+        self.ciphertexts.len()
+    }
+
+    /// Bits to XOR onto the output MAC pointers, see `decode_output_pointers`
+    pub fn decoding_bits(&self) -> &[bool] {
+        &self.decoding_bits
+    }
+
+    /// Blake3 transcript of the gates, decoding bits, input MACs and OT
+    /// ciphertexts, see `bundle_digest`
+    pub fn compute_digest(&self) -> [u8; 32] {
+        let mut gates = blake3::Hasher::new();
+        absorb_gates(&mut gates, &self.garbled_circuit.gates);
+        bundle_digest(
+            gates.finalize().as_bytes(),
+            &self.decoding_bits,
+            &self.all_input_macs,
+            &self.ciphertexts,
+        )
+    }
+
+    /// Check that the bundle has the shape `circuit` expects before
+    /// evaluating it: one encrypted gate per AND gate, one MAC per input bit,
+    /// one OT ciphertext per evaluator input bit and one decoding bit per
+    /// output bit. If the bundle carries a digest, its contents must also
+    /// hash to it.
+    ///
+    /// The digest detects corruption in transit, e.g. a flipped ciphertext
+    /// byte, up front instead of as a failed evaluation. It does not
+    /// authenticate the garbler, who computes it.
+    pub fn validate(
+        &self,
+        circuit: &Circuit,
+        evaluator_input_size: usize,
+    ) -> Result<(), ValidateError> {
+        let gates = self.garbled_circuit.gates.len();
+        if gates != circuit.and_count() {
+            return Err(ValidateError::GateCount(circuit.and_count(), gates));
+        }
+        if self.all_input_macs.len() != circuit.input_len() {
+            return Err(ValidateError::InputMacCount(
+                circuit.input_len(),
+                self.all_input_macs.len(),
+            ));
+        }
+        if self.ciphertexts.len() != evaluator_input_size {
+            return Err(ValidateError::CiphertextCount(
+                evaluator_input_size,
+                self.ciphertexts.len(),
+            ));
+        }
+        if self.decoding_bits.len() != circuit.output_len() {
+            return Err(ValidateError::DecodingBitCount(
+                circuit.output_len(),
+                self.decoding_bits.len(),
+            ));
+        }
+        if let Some(digest) = self.digest {
+            if digest != self.compute_digest() {
+                return Err(ValidateError::Integrity);
+            }
+        }
+        Ok(())
+    }
 }
 
+/// A garbled bundle that does not fit the circuit it is evaluated against.
+/// The count variants hold the expected and the actual count.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValidateError {
+    GateCount(usize, usize),
+    InputMacCount(usize, usize),
+    CiphertextCount(usize, usize),
+    DecodingBitCount(usize, usize),
+    /// The contents do not hash to the bundle's digest
+    Integrity,
+}
+
+impl std::fmt::Display for ValidateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (what, expected, actual) = match self {
+            ValidateError::GateCount(e, a) => ("encrypted gates", e, a),
+            ValidateError::InputMacCount(e, a) => ("input MACs", e, a),
+            ValidateError::CiphertextCount(e, a) => ("OT ciphertexts", e, a),
+            ValidateError::DecodingBitCount(e, a) => ("decoding bits", e, a),
+            ValidateError::Integrity => {
+                return write!(f, "Garbled bundle does not match its digest")
+            }
+        };
+        write!(
+            f,
+            "Garbled bundle has {} {}, circuit expects {}",
+            actual, what, expected
+        )
+    }
+}
+
+impl std::error::Error for ValidateError {}
+
+/// Feed encrypted gates to a transcript, one call per streamed batch
+fn absorb_gates(hasher: &mut blake3::Hasher, gates: &[EncryptedGate]) {
+    for gate in gates {
+        bincode::serialize_into(&mut *hasher, gate).expect("hashing cannot fail");
+    }
+}
+
+/// Digest of a bundle given the hash of its gate stream. The gates come
+/// first since they are garbled before any OT, so a streaming garbler can
+/// hash them on the fly and reuse the result for every evaluator.
+fn bundle_digest(
+    gates_digest: &[u8; 32],
+    decoding_bits: &[bool],
+    all_input_macs: &[Mac],
+    ciphertexts: &[Vec<u8>],
+) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(gates_digest);
+    bincode::serialize_into(&mut hasher, &(decoding_bits, all_input_macs, ciphertexts))
+        .expect("hashing cannot fail");
+    *hasher.finalize().as_bytes()
+}
+
+/// Turn the pointer bits of the evaluator's output MACs into plaintext output
+/// bits. Lets evaluation and decoding happen in different places.
+///
+/// Panics if the two slices differ in length.
+pub fn decode_output_pointers(output_pointers: &[bool], decoding_bits: &[bool]) -> Vec<bool> {
+    assert_eq!(
+        output_pointers.len(),
+        decoding_bits.len(),
+        "one decoding bit is needed per output"
+    );
+    output_pointers
+        .iter()
+        .zip(decoding_bits)
+        .map(|(pointer, bit)| pointer ^ bit)
+        .collect()
+}
+
+/// Stream OT ciphertexts to `w` as a u64 count followed by one
+/// length-prefixed `TrinityMsg::to_bytes` entry per message (integers are
+/// little-endian)
+pub fn write_ciphertexts<W: Write, const N: usize>(
+    mut w: W,
+    msgs: &[TrinityMsg<N>],
+) -> Result<(), Error> {
+    w.write_all(&(msgs.len() as u64).to_le_bytes())?;
+    for msg in msgs {
+        let bytes = msg.to_bytes();
+        w.write_all(&(bytes.len() as u64).to_le_bytes())?;
+        w.write_all(&bytes)?;
+    }
+    Ok(())
+}
+
+/// Errors returned by `read_ciphertexts`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeserializeError {
+    /// The stream ended inside the count, a length prefix or a message
+    Truncated,
+    /// Reading from the stream failed
+    Io(String),
+    /// A complete entry is not a valid `TrinityMsg`
+    Message(TrinityError),
+}
+
+impl std::fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeserializeError::Truncated => write!(f, "Ciphertext stream is truncated"),
+            DeserializeError::Io(e) => write!(f, "Failed to read ciphertexts: {}", e),
+            DeserializeError::Message(e) => write!(f, "Invalid ciphertext: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DeserializeError {}
+
+/// Read back ciphertexts written by `write_ciphertexts`.
+///
+/// The input comes from the peer, so neither the count nor the length
+/// prefixes are trusted for allocation: buffers only grow as bytes arrive.
+pub fn read_ciphertexts<R: Read, const N: usize>(
+    mut r: R,
+) -> Result<Vec<TrinityMsg<N>>, DeserializeError> {
+    let count = read_u64(&mut r)?;
+
+    let mut msgs = Vec::new();
+    for _ in 0..count {
+        let len = read_u64(&mut r)?;
+        let mut bytes = Vec::new();
+        r.by_ref()
+            .take(len)
+            .read_to_end(&mut bytes)
+            .map_err(io_error)?;
+        if (bytes.len() as u64) < len {
+            return Err(DeserializeError::Truncated);
+        }
+
+        msgs.push(TrinityMsg::from_bytes(&bytes).map_err(DeserializeError::Message)?);
+    }
+    Ok(msgs)
+}
+
+fn read_u64<R: Read>(r: &mut R) -> Result<u64, DeserializeError> {
+    let mut buf = [0u8; 8];
+    r.read_exact(&mut buf).map_err(io_error)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn io_error(e: Error) -> DeserializeError {
+    match e.kind() {
+        ErrorKind::UnexpectedEof => DeserializeError::Truncated,
+        _ => DeserializeError::Io(e.to_string()),
+    }
+}
+
+/// Errors returned by `generate_garbled_circuit`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GarbleError {
+    /// The garbler provided more input bits than the circuit takes
+    TooManyGarblerInputs { got: usize, max: usize },
+    /// The OT sender could not be built from the evaluator commitment
+    OtSender(TrinityError),
+    /// The generator rejected the circuit or input keys
+    Generation(String),
+    /// The generator could not produce the output keys
+    Finish(String),
+}
+
+impl std::fmt::Display for GarbleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GarbleError::TooManyGarblerInputs { got, max } => write!(
+                f,
+                "Garbler has {} input bits, circuit only takes {}",
+                got, max
+            ),
+            GarbleError::OtSender(e) => write!(f, "OT sender failed: {}", e),
+            GarbleError::Generation(e) => write!(f, "Failed to garble circuit: {}", e),
+            GarbleError::Finish(e) => write!(f, "Failed to finish garbling: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for GarbleError {}
+
 pub fn generate_garbled_circuit(
     circ: Arc<Circuit>,
-    garbler_bits: Vec<bool>,
+    garbler_bits: Lsb0Bits,
+    rng: &mut StdRng,
+    delta: Delta,
+    trinity: &Trinity,
+    receiver_commitment: TrinityCom,
+) -> Result<GarbledBundle, GarbleError> {
+    let garbling = garble_circuit(circ, garbler_bits, rng, delta)?;
+    let ciphertexts = ot_encrypt_labels(&garbling.key_pairs, rng, trinity, receiver_commitment)?;
+
+    Ok(garbling.into_bundle(ciphertexts))
+}
+
+/// Same as `generate_garbled_circuit`, drawing `delta`, the wire labels and
+/// the OT randomness from `randomness`
+pub fn generate_garbled_circuit_with_randomness(
+    circ: Arc<Circuit>,
+    garbler_bits: Lsb0Bits,
+    randomness: Randomness,
+    trinity: &Trinity,
+    receiver_commitment: TrinityCom,
+) -> Result<GarbledBundle, GarbleError> {
+    let mut rng = randomness.rng();
+    let delta = Delta::random(&mut rng);
+    generate_garbled_circuit(
+        circ,
+        garbler_bits,
+        &mut rng,
+        delta,
+        trinity,
+        receiver_commitment,
+    )
+}
+
+/// Same as `generate_garbled_circuit`, but hands each batch of encrypted
+/// gates to `sink` as soon as it is garbled instead of buffering the whole
+/// circuit, so they can be streamed out while garbling continues.
+///
+/// The returned bundle has no gates; concatenating the batches passed to
+/// `sink` in order gives `garbled_circuit.gates`.
+pub fn generate_garbled_circuit_streaming<F: FnMut(&[EncryptedGate])>(
+    circ: Arc<Circuit>,
+    garbler_bits: Lsb0Bits,
     rng: &mut StdRng,
     delta: Delta,
     trinity: &Trinity,
     receiver_commitment: TrinityCom,
-) -> GarbledBundle {
+    sink: F,
+) -> Result<GarbledBundle, GarbleError> {
+    let garbling = garble_circuit_streaming(circ, garbler_bits, rng, delta, sink)?;
+    let ciphertexts = ot_encrypt_labels(&garbling.key_pairs, rng, trinity, receiver_commitment)?;
+
+    Ok(garbling.into_bundle(ciphertexts))
+}
+
+/// The two labels of one evaluator input wire, for bit 0 and bit 1
+pub type LabelPair = ([u8; 16], [u8; 16]);
+
+/// The commitment-independent part of a garbled bundle: a garbler serving
+/// several evaluators over the same circuit computes this once with
+/// `garble_circuit` and only repeats `ot_encrypt_labels` per evaluator.
+///
+/// `key_pairs` hold both labels of every evaluator input and must never be
+/// sent to an evaluator.
+///
+/// Every bundle cut from one garbling shares its labels and delta. Two
+/// bundles for different inputs reaching the same party (or colluding
+/// evaluators) reveal both labels of a wire, which gives away delta and the
+/// garbler's input. Nothing here can tell two commitments from one party
+/// apart, so only reuse a garbling across evaluators that are known not to
+/// share their bundles; otherwise garble afresh per evaluation.
+#[derive(Clone, Debug)]
+pub struct Garbling {
+    pub garbled_circuit: SerializableGarbledCircuit,
+    pub decoding_bits: Vec<bool>,
+    /// Garbler MACs followed by placeholders for the evaluator inputs
+    pub all_input_macs: Vec<Mac>,
+    pub key_pairs: Vec<LabelPair>,
+    /// Hash of the gates as they were garbled, for `GarbledBundle::digest`
+    pub gates_digest: [u8; 32],
+}
+
+impl Garbling {
+    /// Bundle for the evaluator whose labels were encrypted into
+    /// `ciphertexts` by `ot_encrypt_labels`
+    pub fn bundle(&self, ciphertexts: Vec<Vec<u8>>) -> GarbledBundle {
+        GarbledBundle {
+            digest: Some(self.digest(&ciphertexts)),
+            ciphertexts,
+            garbled_circuit: self.garbled_circuit.clone(),
+            decoding_bits: self.decoding_bits.clone(),
+            all_input_macs: self.all_input_macs.clone(),
+        }
+    }
+
+    /// Same as `bundle`, for a garbling that is not reused
+    pub fn into_bundle(self, ciphertexts: Vec<Vec<u8>>) -> GarbledBundle {
+        GarbledBundle {
+            digest: Some(self.digest(&ciphertexts)),
+            ciphertexts,
+            garbled_circuit: self.garbled_circuit,
+            decoding_bits: self.decoding_bits,
+            all_input_macs: self.all_input_macs,
+        }
+    }
+
+    fn digest(&self, ciphertexts: &[Vec<u8>]) -> [u8; 32] {
+        bundle_digest(
+            &self.gates_digest,
+            &self.decoding_bits,
+            &self.all_input_macs,
+            ciphertexts,
+        )
+    }
+}
+
+/// Garble `circ` with the garbler's inputs fixed, leaving the evaluator's
+/// labels to be transferred by `ot_encrypt_labels`
+pub fn garble_circuit(
+    circ: Arc<Circuit>,
+    garbler_bits: Lsb0Bits,
+    rng: &mut StdRng,
+    delta: Delta,
+) -> Result<Garbling, GarbleError> {
+    let mut gates = Vec::new();
+    let mut garbling = garble_circuit_streaming(circ, garbler_bits, rng, delta, |batch| {
+        gates.extend_from_slice(batch)
+    })?;
+    garbling.garbled_circuit = SerializableGarbledCircuit::from(GarbledCircuit { gates });
+
+    Ok(garbling)
+}
+
+fn garble_circuit_streaming<F: FnMut(&[EncryptedGate])>(
+    circ: Arc<Circuit>,
+    garbler_bits: Lsb0Bits,
+    rng: &mut StdRng,
+    delta: Delta,
+    mut sink: F,
+) -> Result<Garbling, GarbleError> {
+    // Either party may have no input at all, e.g. a circuit that only checks
+    // the evaluator's value against constants. More garbler bits than circuit
+    // inputs would underflow the evaluator input size below.
     let garbler_input_size = garbler_bits.len();
+    if garbler_input_size > circ.input_len() {
+        return Err(GarbleError::TooManyGarblerInputs {
+            got: garbler_input_size,
+            max: circ.input_len(),
+        });
+    }
     let evaluator_input_size = circ.input_len() - garbler_input_size;
 
     let input_keys = (0..circ.input_len())
@@ -60,71 +505,433 @@ pub fn generate_garbled_circuit(
     // Create MACs for garbler inputs only (keys + bits)
     for i in 0..garbler_input_size {
         let key = &input_keys[i];
-        let bit = garbler_bits[i];
+        let bit = garbler_bits.as_slice()[i];
         let mac = key.auth(bit, &delta);
         all_input_macs.push(mac);
     }
 
-    // Prepare OT for evaluator's inputs
-    let ot_sender = trinity.create_ot_sender::<()>(receiver_commitment);
-
-    // Create and collect OT ciphertexts (ONLY for evaluator's inputs)
-    // Here we need to send message by label in order for the OT receiver to choose
-    // the correct label
-    // The garbler's input keys are already known, so we can use them directly
-    let ciphertexts: Vec<TrinityMsg> = (0..evaluator_input_size)
-        .map(|i| {
-            let key_idx = garbler_input_size + i;
-            let key = &input_keys[key_idx];
+    // Add placeholder MACs for evaluator inputs (these will be replaced during evaluation)
+    for _ in 0..evaluator_input_size {
+        all_input_macs.push(Mac::from(Block::ZERO));
+    }
 
-            // Create the two possible labels for this bit
+    // The two possible labels for each evaluator input bit, one of which the
+    // evaluator will choose via OT
+    let key_pairs = input_keys[garbler_input_size..]
+        .iter()
+        .map(|key| {
             let zero_label = key.clone();
             let one_label = Key::from(*key.as_block() ^ delta.as_block());
 
-            // Convert to bytes for OT
             let m0: [u8; 16] = zero_label.as_block().to_bytes().try_into().unwrap();
             let m1: [u8; 16] = one_label.as_block().to_bytes().try_into().unwrap();
-
-            // Send via OT - this is where evaluator will choose which to receive
-            ot_sender.trinity_sender.send(rng, i, m0, m1)
+            (m0, m1)
         })
         .collect();
 
-    let serialized_ciphertexts: Vec<SerializableTrinityMsg> = ciphertexts
-        .iter()
-        .map(|msg| SerializableTrinityMsg::from(msg.clone()))
-        .collect();
-
-    // Add placeholder MACs for evaluator inputs (these will be replaced during evaluation)
-    for _ in 0..evaluator_input_size {
-        all_input_macs.push(Mac::from(Block::ZERO));
-    }
-
     // Garble the circuit
     let mut generator = Generator::default();
     let mut gen_iter = generator
         .generate_batched(&circ, delta, input_keys)
-        .unwrap();
+        .map_err(|e| GarbleError::Generation(e.to_string()))?;
 
-    let mut gates = Vec::new();
+    let mut gates_hasher = blake3::Hasher::new();
     for batch in gen_iter.by_ref() {
-        gates.extend(batch.into_array());
+        let batch = batch.into_array();
+        absorb_gates(&mut gates_hasher, &batch);
+        sink(&batch);
     }
 
-    let garbled_circuit = SerializableGarbledCircuit::from(GarbledCircuit { gates });
-
     let GeneratorOutput {
         outputs: output_keys,
-    } = gen_iter.finish().unwrap();
+    } = gen_iter
+        .finish()
+        .map_err(|e| GarbleError::Finish(e.to_string()))?;
 
     // Include decoding bits for the output keys
     // These are the bits that will be used to decode the output
     let decoding_bits: Vec<bool> = output_keys.iter().map(|key| key.pointer()).collect();
 
-    GarbledBundle {
-        ciphertexts: serialized_ciphertexts,
-        garbled_circuit,
+    Ok(Garbling {
+        garbled_circuit: SerializableGarbledCircuit { gates: Vec::new() },
         decoding_bits,
         all_input_macs,
+        key_pairs,
+        gates_digest: *gates_hasher.finalize().as_bytes(),
+    })
+}
+
+/// Encrypt each label pair of `garble_circuit` to `receiver_commitment`, so
+/// that the evaluator can only open the label of its committed bit.
+/// Returns the ciphertexts in `TrinityMsg::to_bytes` form, ready for
+/// `Garbling::bundle`.
+pub fn ot_encrypt_labels(
+    key_pairs: &[LabelPair],
+    rng: &mut StdRng,
+    trinity: &Trinity,
+    receiver_commitment: TrinityCom,
+) -> Result<Vec<Vec<u8>>, GarbleError> {
+    if key_pairs.is_empty() {
+        // Nothing to transfer, skip OT entirely
+        return Ok(Vec::new());
+    }
+
+    let ot_sender = trinity
+        .create_ot_sender::<()>(receiver_commitment)
+        .map_err(GarbleError::OtSender)?;
+
+    key_pairs
+        .iter()
+        .enumerate()
+        .map(|(i, &(m0, m1))| {
+            let msg = ot_sender.trinity_sender.send(rng, i, m0, m1);
+            msg.map(|msg| msg.to_bytes()).map_err(GarbleError::OtSender)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(bytes: &[u8]) -> Result<Vec<TrinityMsg>, DeserializeError> {
+        read_ciphertexts(bytes)
+    }
+
+    #[test]
+    fn ciphertext_count_read_from_header() {
+        let bundle = GarbledBundle {
+            ciphertexts: vec![vec![1, 2, 3]; 5],
+            garbled_circuit: SerializableGarbledCircuit { gates: Vec::new() },
+            decoding_bits: vec![true, false],
+            all_input_macs: Vec::new(),
+            digest: None,
+        };
+        let bytes = bundle.to_bytes();
+        assert_eq!(GarbledBundle::ciphertext_count_from_bytes(&bytes), Some(5));
+        assert_eq!(
+            GarbledBundle::ciphertext_count_from_bytes(&bytes[..7]),
+            None
+        );
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn garbling_rejects_too_many_garbler_bits() {
+        use crate::{commit::KZGType, evaluate::ev_commit, two_pc::setup};
+        use mpz_circuits::types::ValueType;
+        use rand::SeedableRng;
+
+        let circ = Circuit::parse(
+            "circuits/8bit_not.txt",
+            &[ValueType::Array(Box::new(ValueType::Bit), 8)],
+            &[ValueType::Array(Box::new(ValueType::Bit), 8)],
+        )
+        .unwrap();
+        let setup_bundle = setup(KZGType::Plain);
+        let commitment = ev_commit(Lsb0Bits::default(), &setup_bundle).unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let result = generate_garbled_circuit(
+            Arc::new(circ),
+            Lsb0Bits::new(vec![false; 9]),
+            &mut rng,
+            Delta::random(&mut StdRng::seed_from_u64(1)),
+            &setup_bundle.trinity,
+            commitment.receiver_commitment,
+        );
+        assert_eq!(
+            result.err(),
+            Some(GarbleError::TooManyGarblerInputs { got: 9, max: 8 })
+        );
+
+        // Same through a two-party circuit with 16 inputs in total
+        let circ = Circuit::parse(
+            "circuits/8bit_xor.txt",
+            &[
+                ValueType::Array(Box::new(ValueType::Bit), 8),
+                ValueType::Array(Box::new(ValueType::Bit), 8),
+            ],
+            &[ValueType::Array(Box::new(ValueType::Bit), 8)],
+        )
+        .unwrap();
+        let commitment = ev_commit(Lsb0Bits::default(), &setup_bundle).unwrap();
+        let result = generate_garbled_circuit(
+            Arc::new(circ),
+            Lsb0Bits::new(vec![true; 20]),
+            &mut rng,
+            Delta::random(&mut StdRng::seed_from_u64(2)),
+            &setup_bundle.trinity,
+            commitment.receiver_commitment,
+        );
+        assert_eq!(
+            result.err(),
+            Some(GarbleError::TooManyGarblerInputs { got: 20, max: 16 })
+        );
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn validate_reports_each_mismatch() {
+        use crate::{
+            commit::KZGType,
+            evaluate::{ev_commit, evaluate_circuit},
+            two_pc::setup,
+        };
+        use mpz_circuits::types::ValueType;
+        use rand::SeedableRng;
+
+        let bits = ValueType::Array(Box::new(ValueType::Bit), 16);
+        let circ = Circuit::parse(
+            "circuits/simple_16bit_add.txt",
+            &[bits.clone(), bits.clone()],
+            &[bits],
+        )
+        .unwrap();
+        let setup_bundle = setup(KZGType::Plain);
+        let commitment = ev_commit(Lsb0Bits::from_u16(0), &setup_bundle).unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let bundle = generate_garbled_circuit(
+            Arc::new(circ.clone()),
+            Lsb0Bits::from_u16(u16::MAX),
+            &mut rng,
+            Delta::random(&mut StdRng::seed_from_u64(1)),
+            &setup_bundle.trinity,
+            commitment.receiver_commitment,
+        )
+        .unwrap();
+        assert_eq!(bundle.validate(&circ, 16), Ok(()));
+
+        let mut bad = GarbledBundle::from_bytes(&bundle.to_bytes()).unwrap();
+        bad.garbled_circuit.gates.pop();
+        let gates = circ.and_count();
+        assert_eq!(
+            bad.validate(&circ, 16),
+            Err(ValidateError::GateCount(gates, gates - 1))
+        );
+
+        let mut bad = GarbledBundle::from_bytes(&bundle.to_bytes()).unwrap();
+        bad.all_input_macs.pop();
+        assert_eq!(
+            bad.validate(&circ, 16),
+            Err(ValidateError::InputMacCount(32, 31))
+        );
+
+        let mut bad = GarbledBundle::from_bytes(&bundle.to_bytes()).unwrap();
+        bad.ciphertexts.pop();
+        assert_eq!(
+            bad.validate(&circ, 16),
+            Err(ValidateError::CiphertextCount(16, 15))
+        );
+
+        let mut bad = GarbledBundle::from_bytes(&bundle.to_bytes()).unwrap();
+        bad.decoding_bits.push(false);
+        assert_eq!(
+            bad.validate(&circ, 16),
+            Err(ValidateError::DecodingBitCount(16, 17))
+        );
+
+        // Same shape, one flipped ciphertext byte
+        let mut bad = GarbledBundle::from_bytes(&bundle.to_bytes()).unwrap();
+        bad.ciphertexts[3][10] ^= 1;
+        assert_eq!(bad.validate(&circ, 16), Err(ValidateError::Integrity));
+        assert!(evaluate_circuit(
+            Arc::new(circ.clone()),
+            bad,
+            Lsb0Bits::from_u16(0),
+            &commitment.ot_receiver,
+        )
+        .is_err());
+
+        // Without a digest only the shape is checked
+        let mut unchecked = bundle;
+        unchecked.digest = None;
+        unchecked.ciphertexts[3][10] ^= 1;
+        assert_eq!(unchecked.validate(&circ, 16), Ok(()));
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn streamed_gates_reassemble_into_bundle() {
+        use crate::{commit::KZGType, evaluate::ev_commit, two_pc::setup};
+        use mpz_circuits::types::ValueType;
+        use rand::SeedableRng;
+
+        let bits = ValueType::Array(Box::new(ValueType::Bit), 16);
+        let circ = Arc::new(
+            Circuit::parse(
+                "circuits/simple_16bit_add.txt",
+                &[bits.clone(), bits.clone()],
+                &[bits],
+            )
+            .unwrap(),
+        );
+        let setup_bundle = setup(KZGType::Plain);
+        let commitment = ev_commit(Lsb0Bits::from_u16(u16::MAX), &setup_bundle).unwrap();
+        let delta = Delta::random(&mut StdRng::seed_from_u64(1));
+
+        let buffered = generate_garbled_circuit(
+            circ.clone(),
+            Lsb0Bits::from_u16(0),
+            &mut StdRng::seed_from_u64(0),
+            delta,
+            &setup_bundle.trinity,
+            commitment.receiver_commitment,
+        )
+        .unwrap();
+
+        let mut batches = 0;
+        let mut gates = Vec::new();
+        let mut streamed = generate_garbled_circuit_streaming(
+            circ,
+            Lsb0Bits::from_u16(0),
+            &mut StdRng::seed_from_u64(0),
+            delta,
+            &setup_bundle.trinity,
+            commitment.receiver_commitment,
+            |batch| {
+                batches += 1;
+                gates.extend_from_slice(batch);
+            },
+        )
+        .unwrap();
+        assert!(streamed.garbled_circuit.gates.is_empty());
+        assert!(batches > 0);
+
+        streamed.garbled_circuit.gates = gates;
+        assert_eq!(streamed.to_bytes(), buffered.to_bytes());
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn garbling_is_reused_across_evaluators() {
+        use crate::{
+            commit::KZGType, evaluate::ev_commit, evaluate::evaluate_circuit_typed, two_pc::setup,
+        };
+        use mpz_circuits::types::ValueType;
+        use rand::SeedableRng;
+
+        let bits = ValueType::Array(Box::new(ValueType::Bit), 16);
+        let circ = Arc::new(
+            Circuit::parse(
+                "circuits/simple_16bit_add.txt",
+                &[bits.clone(), bits.clone()],
+                &[bits],
+            )
+            .unwrap(),
+        );
+        let setup_bundle = setup(KZGType::Plain);
+        let mut rng = StdRng::seed_from_u64(0);
+        let delta = Delta::random(&mut rng);
+
+        let garbler_bits = Lsb0Bits::from_u16(6);
+        let garbling = garble_circuit(circ.clone(), garbler_bits, &mut rng, delta).unwrap();
+        assert_eq!(garbling.key_pairs.len(), 16);
+
+        for (evaluator_input, expected) in [(4u16, 10u64), (9u16, 15u64)] {
+            let evaluator_bits = Lsb0Bits::from_u16(evaluator_input);
+            let commitment = ev_commit(evaluator_bits.clone(), &setup_bundle).unwrap();
+
+            let ciphertexts = ot_encrypt_labels(
+                &garbling.key_pairs,
+                &mut rng,
+                &setup_bundle.trinity,
+                commitment.receiver_commitment,
+            )
+            .unwrap();
+            let bundle = garbling.bundle(ciphertexts);
+            assert_eq!(bundle.garbled_circuit.gates, garbling.garbled_circuit.gates);
+
+            let output = evaluate_circuit_typed(
+                circ.clone(),
+                bundle,
+                evaluator_bits,
+                &commitment.ot_receiver,
+                &[16],
+            )
+            .unwrap();
+            assert_eq!(output, vec![expected]);
+        }
+    }
+
+    #[test]
+    fn decode_output_pointers_xors_decoding_bits() {
+        let pointers = [true, false, true, false];
+        let decoding_bits = [true, true, false, false];
+        assert_eq!(
+            decode_output_pointers(&pointers, &decoding_bits),
+            vec![false, true, true, false]
+        );
+    }
+
+    #[cfg(feature = "halo2")]
+    #[test]
+    fn ciphertexts_stream_roundtrip() {
+        use halo2_we_kzg::Msg;
+        use halo2curves::bn256::G2Affine;
+
+        let g2 = G2Affine::generator();
+        let msgs: Vec<TrinityMsg> = (0..10_000u32)
+            .map(|i| {
+                let tag = i.to_le_bytes()[0];
+                TrinityMsg::Halo2(Msg {
+                    h: [(g2, [tag; 16]), (g2, [!tag; 16])],
+                })
+            })
+            .collect();
+
+        let mut bytes = Vec::new();
+        write_ciphertexts(&mut bytes, &msgs).unwrap();
+        let decoded: Vec<TrinityMsg> = read_ciphertexts(bytes.as_slice()).unwrap();
+
+        assert_eq!(decoded.len(), msgs.len());
+
+        // Cutting into the last message is reported, not a panic
+        assert_eq!(
+            read(&bytes[..bytes.len() - 1]).err(),
+            Some(DeserializeError::Truncated)
+        );
+
+        for (i, msg) in decoded.iter().enumerate() {
+            let tag = (i as u32).to_le_bytes()[0];
+            match msg {
+                TrinityMsg::Halo2(m) => {
+                    assert_eq!(m.h[0], (g2, [tag; 16]));
+                    assert_eq!(m.h[1], (g2, [!tag; 16]));
+                }
+                #[allow(unreachable_patterns)]
+                _ => panic!("Expected Halo2 message"),
+            }
+        }
+    }
+
+    #[test]
+    fn read_ciphertexts_rejects_empty_input() {
+        assert_eq!(read(&[]).err(), Some(DeserializeError::Truncated));
+    }
+
+    #[test]
+    fn read_ciphertexts_rejects_truncated_length_prefix() {
+        let mut bytes = 1u64.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&[0u8; 3]);
+        assert_eq!(read(&bytes).err(), Some(DeserializeError::Truncated));
+    }
+
+    #[test]
+    fn read_ciphertexts_does_not_trust_length_prefix() {
+        // Claims one huge message but carries no data
+        let mut bytes = 1u64.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(read(&bytes).err(), Some(DeserializeError::Truncated));
+
+        // Declared length is present but the bytes are garbage
+        let mut bytes = 1u64.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&4u64.to_le_bytes());
+        bytes.extend_from_slice(&[0xff; 4]);
+        assert_eq!(
+            read(&bytes).err(),
+            Some(DeserializeError::Message(TrinityError::DeserializeMessage))
+        );
     }
 }
@@ -1,27 +1,117 @@
+#[cfg(not(any(feature = "plain", feature = "halo2")))]
+compile_error!("at least one of the `plain` or `halo2` features must be enabled");
+
+#[cfg(feature = "tokio")]
+mod channel;
 mod commit;
+pub mod encoding;
 mod evaluate;
 mod garble;
 mod ot;
 mod two_pc;
 
-use std::sync::Arc;
-
-use commit::{KZGType, SerializableTrinityCom, TrinityCom, TrinityMsg};
-use evaluate::{ev_commit, evaluate_circuit};
-use garble::{generate_garbled_circuit, GarbledBundle};
-use itybity::IntoBitIterator;
-use mpz_circuits::{types::ValueType, Circuit};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use commit::{to_json, SerializableTrinityCom, TrinityCom};
+use encoding::{pack_inputs, value_type_for_width, Lsb0Bits};
+use evaluate::{
+    decode_outputs, ev_commit_with_progress, evaluate_circuit, evaluate_circuit_typed,
+    evaluate_circuit_with_macs, pack_lsb0,
+};
+use garble::generate_garbled_circuit;
+use mpz_circuits::{
+    types::{ValueRepr, ValueType},
+    Circuit, CircuitBuilder,
+};
 use mpz_garble_core::Delta;
 use ot::KZGOTReceiver;
 use rand::{rngs::StdRng, SeedableRng};
 use serde::{Deserialize, Serialize};
-use two_pc::{setup, u8_vec_to_vec_bool, SetupParams};
+use two_pc::{setup, setup_with_degree, SetupParams};
 
+use js_sys::Uint8Array;
 use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::commit::{TrinityChoice, TrinityInnerParams, TrinityParams};
+
+pub use commit::{
+    estimate_params_bytes, KZGType, ParseModeError, Randomness, TrinityError, TrinityMsg,
+    MAX_SETUP_DEGREE, MIN_SETUP_DEGREE,
+};
+pub use garble::{read_ciphertexts, write_ciphertexts, DeserializeError, GarbledBundle};
+pub use ot::{LaconicOtReceiver, LaconicOtSender};
+pub use two_pc::run_2pc;
+
+#[cfg(feature = "tokio")]
+pub use channel::{run_evaluator, run_garbler, MAX_FRAME_LEN};
+
+/// Internal deserializers exercised by the targets in `fuzz/`, not a stable API
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzzing {
+    pub use crate::commit::{Trinity, TrinityCom, TrinityError, TrinityMsg};
+}
+
+/// Most circuits kept by `parse_circuit`, the least recently used one is
+/// dropped to make room for a new one
+const CIRCUIT_CACHE_CAPACITY: usize = 16;
+
+/// Parsed circuits keyed by a blake3 hash of the source and sizes, least
+/// recently used first. Small enough that a linear scan beats a map.
+#[derive(Default)]
+struct CircuitCache {
+    entries: Vec<([u8; 32], Arc<Circuit>)>,
+}
+
+impl CircuitCache {
+    fn key(circuit_str: &str, sizes: &[usize]) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&(circuit_str.len() as u64).to_le_bytes());
+        hasher.update(circuit_str.as_bytes());
+        for size in sizes {
+            hasher.update(&(*size as u64).to_le_bytes());
+        }
+        *hasher.finalize().as_bytes()
+    }
+
+    /// Marks the entry as the most recently used one
+    fn get(&mut self, key: &[u8; 32]) -> Option<Arc<Circuit>> {
+        let position = self.entries.iter().position(|(k, _)| k == key)?;
+        let entry = self.entries.remove(position);
+        let circuit = entry.1.clone();
+        self.entries.push(entry);
+        Some(circuit)
+    }
+
+    fn insert(&mut self, key: [u8; 32], circuit: Arc<Circuit>) {
+        self.entries.retain(|(k, _)| *k != key);
+        if self.entries.len() >= CIRCUIT_CACHE_CAPACITY {
+            self.entries.remove(0);
+        }
+        self.entries.push((key, circuit));
+    }
+}
 
-use crate::commit::{TrinityInnerParams, TrinityParams};
+fn circuit_cache() -> &'static Mutex<CircuitCache> {
+    static CACHE: OnceLock<Mutex<CircuitCache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(CircuitCache::default()))
+}
+
+/// Drop every circuit cached by `parse_circuit`. Circuits still held by a
+/// `CircuitWrapper` stay alive until it is dropped.
+#[wasm_bindgen]
+pub fn clear_circuit_cache() {
+    circuit_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entries
+        .clear();
+}
 
-/// Parse a circuit from a string
+/// Parse a circuit from a string. Repeated calls with the same source and
+/// sizes share one parsed circuit, as long as it is among the
+/// `CIRCUIT_CACHE_CAPACITY` most recently used ones, see `clear_circuit_cache`.
 #[wasm_bindgen]
 pub fn parse_circuit(
     circuit_str: &str,
@@ -29,108 +119,613 @@ pub fn parse_circuit(
     garbler_input_size: usize,
     output_size: usize,
 ) -> Result<CircuitWrapper, JsError> {
-    let circuit = Circuit::parse_str(
+    parse_circuit_with_outputs(
+        circuit_str,
+        evaluator_input_size,
+        garbler_input_size,
+        vec![output_size],
+    )
+}
+
+/// Like `parse_circuit`, for a circuit with several output values: the
+/// Bristol output wires are split into consecutive values of the given
+/// sizes, which must add up to the circuit's output length. Evaluate with
+/// `TrinityEvaluator::evaluate_outputs` to get them back separately.
+#[wasm_bindgen]
+pub fn parse_circuit_with_outputs(
+    circuit_str: &str,
+    evaluator_input_size: usize,
+    garbler_input_size: usize,
+    output_sizes: Vec<usize>,
+) -> Result<CircuitWrapper, JsError> {
+    if output_sizes.is_empty() {
+        return Err(JsError::new("A circuit needs at least one output"));
+    }
+    // The parser takes the last output wires whatever the header says, so a
+    // wrong split would silently shift the outputs
+    let total: usize = output_sizes.iter().sum();
+    if let Some(declared) = declared_output_len(circuit_str) {
+        if declared != total {
+            return Err(JsError::new(&format!(
+                "Output sizes add up to {} bits, the circuit has {}",
+                total, declared
+            )));
+        }
+    }
+    let sizes: Vec<usize> = [evaluator_input_size, garbler_input_size]
+        .into_iter()
+        .chain(output_sizes.iter().copied())
+        .collect();
+    let key = CircuitCache::key(circuit_str, &sizes);
+
+    let cached = circuit_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&key);
+
+    let circuit = match cached {
+        Some(circuit) => circuit,
+        None => {
+            let (inputs, outputs) =
+                circuit_io(evaluator_input_size, garbler_input_size, &output_sizes);
+            let circuit = Arc::new(
+                Circuit::parse_str(circuit_str, &inputs, &outputs)
+                    .map_err(|e| JsError::new(&format!("Failed to parse circuit: {}", e)))?,
+            );
+            circuit_cache()
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(key, circuit.clone());
+            circuit
+        }
+    };
+
+    Ok(CircuitWrapper {
+        circuit,
+        evaluator_input_size,
+        garbler_input_size,
+        output_sizes,
+    })
+}
+
+/// Parse a circuit from the raw bytes of a Bristol file, e.g. a binary asset
+/// fetched in the browser
+#[wasm_bindgen]
+pub fn parse_circuit_bytes(
+    circuit_bytes: &[u8],
+    evaluator_input_size: usize,
+    garbler_input_size: usize,
+    output_size: usize,
+) -> Result<CircuitWrapper, JsError> {
+    let circuit_str = std::str::from_utf8(circuit_bytes)
+        .map_err(|e| JsError::new(&format!("Circuit is not valid UTF-8: {}", e)))?;
+    parse_circuit(
         circuit_str,
-        &[
-            ValueType::Array(Box::new(ValueType::Bit), evaluator_input_size),
-            ValueType::Array(Box::new(ValueType::Bit), garbler_input_size),
-        ],
-        &[ValueType::Array(Box::new(ValueType::Bit), output_size)],
+        evaluator_input_size,
+        garbler_input_size,
+        output_size,
     )
-    .map_err(|e| JsError::new(&format!("Failed to parse circuit: {}", e)))?;
+}
+
+/// Parse a mode name with `KZGType::from_str`, as a JS error
+fn parse_mode(mode_str: &str) -> Result<KZGType, JsError> {
+    mode_str
+        .parse::<KZGType>()
+        .map_err(|e| JsError::new(&e.to_string()))
+}
 
-    Ok(CircuitWrapper(Arc::new(circuit)))
+/// Output bits declared by a Bristol Fashion header, whose third line is the
+/// number of output values followed by their sizes. `None` for headers in
+/// another format.
+fn declared_output_len(circuit_str: &str) -> Option<usize> {
+    let mut fields = circuit_str
+        .lines()
+        .nth(2)?
+        .split_whitespace()
+        .map(|field| field.parse::<usize>().ok());
+    let count = fields.next()??;
+    let sizes = fields.collect::<Option<Vec<usize>>>()?;
+    (sizes.len() == count).then(|| sizes.iter().sum())
+}
+
+fn circuit_io(
+    evaluator_input_size: usize,
+    garbler_input_size: usize,
+    output_sizes: &[usize],
+) -> (Vec<ValueType>, Vec<ValueType>) {
+    // Garbling feeds the garbler's bits to the first input wires, so its
+    // value comes first. A party without input contributes no input value.
+    let inputs = [garbler_input_size, evaluator_input_size]
+        .into_iter()
+        .filter(|&size| size > 0)
+        .map(|size| ValueType::Array(Box::new(ValueType::Bit), size))
+        .collect();
+    let outputs = output_sizes
+        .iter()
+        .map(|&size| ValueType::Array(Box::new(ValueType::Bit), size))
+        .collect();
+    (inputs, outputs)
 }
 
 /// Wrapper for Circuit to expose to JavaScript
 #[wasm_bindgen]
-pub struct CircuitWrapper(Arc<Circuit>);
+pub struct CircuitWrapper {
+    circuit: Arc<Circuit>,
+    evaluator_input_size: usize,
+    garbler_input_size: usize,
+    /// Bits per output value, in output order
+    output_sizes: Vec<usize>,
+}
+
+#[wasm_bindgen]
+impl CircuitWrapper {
+    /// Number of evaluator input bits declared when parsing the circuit
+    #[wasm_bindgen(getter)]
+    pub fn evaluator_input_size(&self) -> usize {
+        self.evaluator_input_size
+    }
+
+    /// Number of garbler input bits declared when parsing the circuit
+    #[wasm_bindgen(getter)]
+    pub fn garbler_input_size(&self) -> usize {
+        self.garbler_input_size
+    }
+
+    /// Number of bits of each output value
+    #[wasm_bindgen(getter)]
+    pub fn output_sizes(&self) -> Vec<usize> {
+        self.output_sizes.clone()
+    }
+}
+
+impl CircuitWrapper {
+    /// Parse a Bristol circuit file from disk, for use on the Rust side
+    pub fn from_file(
+        path: &str,
+        evaluator_input_size: usize,
+        garbler_input_size: usize,
+        output_size: usize,
+    ) -> Result<CircuitWrapper, std::io::Error> {
+        let (inputs, outputs) =
+            circuit_io(evaluator_input_size, garbler_input_size, &[output_size]);
+        let circuit = Circuit::parse(path, &inputs, &outputs).map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Failed to parse circuit: {}", e),
+            )
+        })?;
+
+        Ok(CircuitWrapper {
+            circuit: Arc::new(circuit),
+            evaluator_input_size,
+            garbler_input_size,
+            output_sizes: vec![output_size],
+        })
+    }
+
+    /// Chain `other` after this circuit: each `(output, input)` pair in
+    /// `wiring` feeds this circuit's `output` value into `other`'s `input`
+    /// value, so both run in one garbling and one OT round.
+    ///
+    /// The composed circuit returns `other`'s outputs. The evaluator keeps
+    /// its input from this circuit, every unwired input of `other` is added
+    /// to the garbler's: its input is this circuit's garbler input followed
+    /// by the unwired inputs of `other`, in order.
+    pub fn compose(
+        &self,
+        other: &CircuitWrapper,
+        wiring: &[(usize, usize)],
+    ) -> Result<CircuitWrapper, TrinityError> {
+        let (first, second) = (&self.circuit, &other.circuit);
+
+        let mut wired = vec![None; second.inputs().len()];
+        for &(output, input) in wiring {
+            let output_type = first
+                .outputs()
+                .get(output)
+                .ok_or_else(|| {
+                    TrinityError::InvalidComposition(format!("No output value {} to wire", output))
+                })?
+                .value_type();
+            let input_type = second
+                .inputs()
+                .get(input)
+                .ok_or_else(|| {
+                    TrinityError::InvalidComposition(format!("No input value {} to wire", input))
+                })?
+                .value_type();
+            if output_type != input_type {
+                return Err(TrinityError::InvalidComposition(format!(
+                    "Cannot wire output {} ({:?}) to input {} ({:?})",
+                    output, output_type, input, input_type
+                )));
+            }
+            if wired[input].replace(output).is_some() {
+                return Err(TrinityError::InvalidComposition(format!(
+                    "Input {} is wired twice",
+                    input
+                )));
+            }
+        }
+
+        // All inputs first, in the order the garbling assigns wires: this
+        // circuit's garbler values, the added inputs, then the evaluator's
+        let builder = CircuitBuilder::new();
+        let mut offset = 0;
+        let mut garbler_inputs = Vec::with_capacity(first.inputs().len());
+        for value in first.inputs() {
+            let len = value.value_type().len();
+            if offset < self.garbler_input_size && offset + len > self.garbler_input_size {
+                return Err(TrinityError::InvalidComposition(
+                    "An input value holds both garbler and evaluator bits".to_string(),
+                ));
+            }
+            garbler_inputs.push(
+                (offset < self.garbler_input_size)
+                    .then(|| builder.add_input_by_type(value.value_type())),
+            );
+            offset += len;
+        }
+        let mut free_inputs: Vec<Option<ValueRepr>> = second
+            .inputs()
+            .iter()
+            .zip(&wired)
+            .map(|(value, wire)| {
+                wire.is_none()
+                    .then(|| builder.add_input_by_type(value.value_type()))
+            })
+            .collect();
+
+        let first_inputs: Vec<ValueRepr> = first
+            .inputs()
+            .iter()
+            .zip(garbler_inputs)
+            .map(|(value, input)| {
+                input.unwrap_or_else(|| builder.add_input_by_type(value.value_type()))
+            })
+            .collect();
+
+        let build_error =
+            |e: mpz_circuits::BuilderError| TrinityError::InvalidComposition(e.to_string());
+        let first_outputs = builder.append(first, &first_inputs).map_err(build_error)?;
+        let second_inputs: Vec<ValueRepr> = wired
+            .iter()
+            .zip(free_inputs.iter_mut())
+            .map(|(wire, free)| match wire {
+                Some(output) => first_outputs[*output].clone(),
+                None => free.take().expect("unwired inputs were added above"),
+            })
+            .collect();
+        for output in builder
+            .append(second, &second_inputs)
+            .map_err(build_error)?
+        {
+            builder.add_output(output);
+        }
+        let circuit = builder.build().map_err(build_error)?;
+
+        let garbler_input_size = circuit.input_len() - self.evaluator_input_size;
+        Ok(CircuitWrapper {
+            circuit: Arc::new(circuit),
+            evaluator_input_size: self.evaluator_input_size,
+            garbler_input_size,
+            output_sizes: other.output_sizes.clone(),
+        })
+    }
+
+    /// Specialize the circuit by fixing input wires to public constants.
+    /// Each `(wire, value)` pair is a flat input bit index, in the layout
+    /// the garbler uses (its own bits first, then the evaluator's).
+    ///
+    /// Fixed wires become constant gates, so the garbler encodes them and no
+    /// OT is spent on them. They are removed from the owning party's input:
+    /// the remaining bits keep their order, and `garbler_input_size` and
+    /// `evaluator_input_size` of the result shrink by the number of wires
+    /// fixed in each range, so the parties pass only their unfixed bits.
+    pub fn with_constants(
+        &self,
+        constants: &[(usize, bool)],
+    ) -> Result<CircuitWrapper, TrinityError> {
+        let circuit = &self.circuit;
+        let input_len = circuit.input_len();
+
+        let mut fixed = vec![None; input_len];
+        for &(wire, value) in constants {
+            let slot = fixed.get_mut(wire).ok_or_else(|| {
+                TrinityError::InvalidConstants(format!(
+                    "Input wire {} is out of range, the circuit has {} input bits",
+                    wire, input_len
+                ))
+            })?;
+            if slot.replace(value).is_some() {
+                return Err(TrinityError::InvalidConstants(format!(
+                    "Input wire {} is fixed twice",
+                    wire
+                )));
+            }
+        }
+        if self.garbler_input_size + self.evaluator_input_size != input_len {
+            return Err(TrinityError::InvalidConstants(
+                "Circuit input sizes do not cover its input wires".to_string(),
+            ));
+        }
+
+        // Free bits of each input value become one smaller array input
+        let builder = CircuitBuilder::new();
+        let mut offset = 0;
+        let mut free_inputs = Vec::with_capacity(circuit.inputs().len());
+        for value in circuit.inputs() {
+            let len = match value.value_type() {
+                ValueType::Bit => 1,
+                ValueType::Array(elem, len) if *elem == ValueType::Bit => len,
+                other => {
+                    return Err(TrinityError::InvalidConstants(format!(
+                        "Cannot fix wires of a {:?} input, only bit inputs are supported",
+                        other
+                    )))
+                }
+            };
+            let free = fixed[offset..offset + len]
+                .iter()
+                .filter(|value| value.is_none())
+                .count();
+            free_inputs.push((
+                len,
+                (free > 0).then(|| {
+                    builder.add_input_by_type(ValueType::Array(Box::new(ValueType::Bit), free))
+                }),
+            ));
+            offset += len;
+        }
+
+        let mut offset = 0;
+        let mut inputs = Vec::with_capacity(circuit.inputs().len());
+        for (value, (len, free)) in circuit.inputs().iter().zip(free_inputs) {
+            let mut free_bits = match free {
+                Some(ValueRepr::Array(bits)) => bits.into_iter(),
+                _ => Vec::new().into_iter(),
+            };
+            let mut bits: Vec<ValueRepr> = Vec::new();
+            for wire in &fixed[offset..offset + len] {
+                bits.push(match wire {
+                    Some(constant) => ValueRepr::Bit(builder.get_constant(*constant).to_inner()),
+                    None => free_bits
+                        .next()
+                        .expect("one free input bit per unfixed wire"),
+                });
+            }
+            offset += len;
+            inputs.push(match value.value_type() {
+                ValueType::Bit => bits.remove(0),
+                _ => ValueRepr::Array(bits),
+            });
+        }
+
+        let build_error =
+            |e: mpz_circuits::BuilderError| TrinityError::InvalidConstants(e.to_string());
+        for output in builder.append(circuit, &inputs).map_err(build_error)? {
+            builder.add_output(output);
+        }
+        let specialized = builder.build().map_err(build_error)?;
+
+        let fixed_garbler = fixed[..self.garbler_input_size]
+            .iter()
+            .filter(|value| value.is_some())
+            .count();
+        Ok(CircuitWrapper {
+            circuit: Arc::new(specialized),
+            evaluator_input_size: self.evaluator_input_size - (constants.len() - fixed_garbler),
+            garbler_input_size: self.garbler_input_size - fixed_garbler,
+            output_sizes: self.output_sizes.clone(),
+        })
+    }
+}
 
 /// This struct holds the setup parameters
 #[wasm_bindgen]
 pub struct TrinityWasmSetup {
     params: SetupParams,
+    /// Filled by the first `params_len` call, the params never change after
+    params_len: OnceLock<usize>,
+}
+
+impl From<SetupParams> for TrinityWasmSetup {
+    fn from(params: SetupParams) -> Self {
+        TrinityWasmSetup {
+            params,
+            params_len: OnceLock::new(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum SerializableTrinityMsg {
+    #[cfg(feature = "plain")]
     Plain(laconic_ot::SerializableMsg),
+    #[cfg(feature = "halo2")]
     Halo2(halo2_we_kzg::laconic_ot::SerializableMsg),
 }
 
-impl From<TrinityMsg> for SerializableTrinityMsg {
-    fn from(msg: TrinityMsg) -> Self {
+impl<const N: usize> From<TrinityMsg<N>> for SerializableTrinityMsg {
+    fn from(msg: TrinityMsg<N>) -> Self {
         match msg {
+            #[cfg(feature = "plain")]
             TrinityMsg::Plain(m) => Self::Plain(m.into()),
+            #[cfg(feature = "halo2")]
             TrinityMsg::Halo2(m) => Self::Halo2(m.into()),
         }
     }
 }
 
-impl TryFrom<SerializableTrinityMsg> for TrinityMsg {
-    type Error = &'static str;
+impl<const N: usize> TryFrom<SerializableTrinityMsg> for TrinityMsg<N> {
+    type Error = TrinityError;
 
     fn try_from(s: SerializableTrinityMsg) -> Result<Self, Self::Error> {
         match s {
+            #[cfg(feature = "plain")]
             SerializableTrinityMsg::Plain(m) => Ok(Self::Plain(
-                laconic_ot::Msg::try_from(m).map_err(|_| "deserialize plain failed")?,
+                laconic_ot::Msg::try_from(m).map_err(|_| TrinityError::DeserializeMessage)?,
             )),
+            #[cfg(feature = "halo2")]
             SerializableTrinityMsg::Halo2(m) => Ok(Self::Halo2(
-                halo2_we_kzg::Msg::try_from(m).map_err(|_| "deserialize halo2 failed")?,
+                halo2_we_kzg::Msg::try_from(m).map_err(|_| TrinityError::DeserializeMessage)?,
             )),
         }
     }
 }
 
-impl TrinityMsg {
+impl<const N: usize> TrinityMsg<N> {
     pub fn serialize(&self) -> Vec<u8> {
-        let serializable: SerializableTrinityMsg = self.clone().into();
-        serde_json::to_vec(&serializable).expect("Serialization failed")
+        self.try_serialize().expect("Serialization failed")
+    }
+
+    /// Like `serialize`, returning serde errors instead of panicking
+    pub fn try_serialize(&self) -> Result<Vec<u8>, TrinityError> {
+        to_json(&SerializableTrinityMsg::from(*self))
     }
 
-    pub fn deserialize(data: &[u8]) -> Result<Self, &'static str> {
+    pub fn deserialize(data: &[u8]) -> Result<Self, TrinityError> {
         let serializable: SerializableTrinityMsg =
-            serde_json::from_slice(data).map_err(|_| "JSON deserialization failed")?;
+            serde_json::from_slice(data).map_err(|_| TrinityError::DeserializeMessage)?;
+        TrinityMsg::try_from(serializable)
+    }
+
+    /// Compact binary encoding: a tag byte (0 for Plain, 1 for Halo2) followed
+    /// by both `(point, ciphertext)` pairs as raw point bytes and the `N`-byte
+    /// ciphertext. `serialize` keeps the readable JSON form for debugging.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (tag, pairs) = match SerializableTrinityMsg::from(*self) {
+            #[cfg(feature = "plain")]
+            SerializableTrinityMsg::Plain(m) => (0u8, m.h),
+            #[cfg(feature = "halo2")]
+            SerializableTrinityMsg::Halo2(m) => (1u8, m.h),
+        };
+
+        let mut bytes = vec![tag];
+        for (point, ciphertext) in pairs {
+            bytes.extend_from_slice(&point);
+            bytes.extend_from_slice(&ciphertext);
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TrinityError> {
+        let (&tag, body) = bytes.split_first().ok_or(TrinityError::EmptyBytes)?;
+
+        // Both pairs have the same size, so the point length follows from `N`
+        if body.len() % 2 != 0 || body.len() / 2 <= N {
+            return Err(TrinityError::DeserializeMessage);
+        }
+        let (first, second) = body.split_at(body.len() / 2);
+        let point_len = first.len() - N;
+        let pair = |half: &[u8]| (half[..point_len].to_vec(), half[point_len..].to_vec());
+        let h = [pair(first), pair(second)];
+
+        let serializable = match tag {
+            #[cfg(feature = "plain")]
+            0 => SerializableTrinityMsg::Plain(laconic_ot::SerializableMsg { h }),
+            #[cfg(feature = "halo2")]
+            1 => SerializableTrinityMsg::Halo2(halo2_we_kzg::laconic_ot::SerializableMsg { h }),
+            tag => return Err(TrinityError::InvalidTagByte(tag)),
+        };
         TrinityMsg::try_from(serializable)
     }
 }
 
 #[wasm_bindgen]
 impl TrinityWasmSetup {
+    /// Generate fresh setup parameters for the given mode.
+    ///
+    /// `mode_str` is matched case-insensitively against `"Plain"` and `"Halo2"`.
     #[wasm_bindgen(constructor)]
-    pub fn new(mode_str: &str) -> TrinityWasmSetup {
-        let mode = match mode_str {
-            "Plain" => KZGType::Plain,
-            "Halo2" => KZGType::Halo2,
-            _ => panic!("Invalid mode"),
-        };
-        TrinityWasmSetup {
-            params: setup(mode),
-        }
+    pub fn new(mode_str: &str) -> Result<TrinityWasmSetup, JsError> {
+        let mode = parse_mode(mode_str)?;
+        Ok(setup(mode).into())
+    }
+
+    /// Like `new`, but sized for `1 << k` evaluator input bits, `k` between
+    /// `MIN_SETUP_DEGREE` and `MAX_SETUP_DEGREE`
+    #[wasm_bindgen(static_method_of = TrinityWasmSetup)]
+    pub fn with_degree(mode_str: &str, k: u32) -> Result<TrinityWasmSetup, JsError> {
+        let mode = parse_mode(mode_str)?;
+        let params = setup_with_degree(mode, k)
+            .map_err(|e| JsError::new(&format!("Failed to generate parameters: {}", e)))?;
+        Ok(params.into())
+    }
+
+    /// Size in bytes `to_full_params_bytes` would have for a setup made
+    /// with `with_degree(mode_str, k)`, without generating it
+    #[wasm_bindgen(static_method_of = TrinityWasmSetup)]
+    pub fn estimate_params_bytes(mode_str: &str, k: u32) -> Result<usize, JsError> {
+        let mode = parse_mode(mode_str)?;
+        Ok(estimate_params_bytes(mode, k as usize))
     }
 
     pub fn to_sender_setup(&self) -> Vec<u8> {
         self.params.to_sender_bytes()
     }
 
+    /// Like `to_sender_setup`, about half the size in Plain mode at some
+    /// cost when loading. `from_sender_setup` reads either form.
+    pub fn to_sender_setup_compressed(&self) -> Vec<u8> {
+        self.params.to_sender_bytes_compressed()
+    }
+
     #[wasm_bindgen(static_method_of = TrinityWasmSetup)]
     pub fn from_sender_setup(bytes: &[u8]) -> Result<TrinityWasmSetup, JsError> {
-        let params = SetupParams::from_sender_bytes(bytes)
-            .map_err(|_| JsError::new("Failed to deserialize sender parameters"))?;
-        Ok(TrinityWasmSetup { params })
+        let params = SetupParams::from_sender_bytes(bytes).map_err(|e| {
+            JsError::new(&format!("Failed to deserialize sender parameters: {}", e))
+        })?;
+        Ok(params.into())
     }
 
-    /// Serializes the full setup parameters to bytes.
+    /// Serializes the full setup parameters to bytes: a magic and version
+    /// header, a tag byte (0 for Plain, 1 for Halo2), the session id, then
+    /// the backend's own encoding. Only available on setups created with
+    /// `new` or loaded from full params.
     pub fn to_full_params_bytes(&self) -> Vec<u8> {
         self.params.to_full_params_bytes()
     }
 
-    /// Deserializes the full setup parameters from bytes.
+    /// Deserializes the full setup parameters from bytes. Bytes written
+    /// before the header was added, a tag byte directly followed by the
+    /// backend encoding, are still accepted.
     #[wasm_bindgen(static_method_of = TrinityWasmSetup)]
     pub fn from_full_params_bytes(bytes: &[u8]) -> Result<TrinityWasmSetup, JsError> {
         let params = SetupParams::from_full_params_bytes(bytes)
             .map_err(|e| JsError::new(&format!("Failed to deserialize full parameters: {}", e)))?;
-        Ok(TrinityWasmSetup { params })
+        Ok(params.into())
+    }
+
+    /// Same as `from_full_params_bytes`, for params streamed in chunks, e.g.
+    /// read from a `fetch` response body. Chunks are copied into one buffer
+    /// once, so JS does not need to concatenate them first.
+    #[wasm_bindgen(static_method_of = TrinityWasmSetup)]
+    pub fn from_params_chunks(chunks: Vec<Uint8Array>) -> Result<TrinityWasmSetup, JsError> {
+        let total = chunks.iter().map(|chunk| chunk.length() as usize).sum();
+        let mut bytes = vec![0u8; total];
+        let mut offset = 0;
+        for chunk in &chunks {
+            let len = chunk.length() as usize;
+            chunk.copy_to(&mut bytes[offset..offset + len]);
+            offset += len;
+        }
+        Self::from_full_params_bytes(&bytes)
+    }
+
+    /// Size in bytes of the serialized parameters: the full params, or the
+    /// sender params for a garbler-only setup, e.g. to size a download
+    /// progress bar. Serialized once on the first call and cached.
+    #[wasm_bindgen(getter)]
+    pub fn params_len(&self) -> usize {
+        *self
+            .params_len
+            .get_or_init(|| match &self.params.trinity.params {
+                TrinityInnerParams::Full(_) => self.params.to_full_params_bytes().len(),
+                TrinityInnerParams::Sender(_) => self.params.to_sender_bytes().len(),
+                #[cfg(feature = "halo2")]
+                TrinityInnerParams::Verifier(params) => 1 + params.to_bytes().len(),
+            })
     }
 
     #[wasm_bindgen]
@@ -140,14 +735,49 @@ impl TrinityWasmSetup {
 
         match &self.params.trinity.params {
             TrinityInnerParams::Full(params) => match params {
+                #[cfg(feature = "plain")]
                 TrinityParams::Plain(p) => {
                     format!("Plain setup with domain size: {}", p.domain.size)
                 }
+                #[cfg(feature = "halo2")]
                 TrinityParams::Halo2(p) => format!("Halo2 setup with k = {}", p.k),
             },
             TrinityInnerParams::Sender(_) => "Sender-only parameters".to_string(),
+            #[cfg(feature = "halo2")]
+            TrinityInnerParams::Verifier(p) => {
+                format!("Halo2 verifier-only parameters with k = {}", p.k)
+            }
         }
     }
+
+    /// Check, before garbling, that `commitment_serialized` (as produced by
+    /// `TrinityEvaluator::commitment_serialized`) commits to `num_bits` bits.
+    /// An empty `proof` checks the proof attached to the commitment.
+    ///
+    /// Returns `false` for a malformed commitment, a bad proof, or a setup
+    /// that can't check it (Halo2 sender-only parameters).
+    #[wasm_bindgen]
+    pub fn verify_commitment(
+        &self,
+        commitment_serialized: &str,
+        proof: Vec<u8>,
+        num_bits: usize,
+    ) -> bool {
+        let (com, attached) =
+            match TrinityCom::deserialize_with_proof(commitment_serialized.as_bytes()) {
+                Ok(parsed) => parsed,
+                Err(_) => return false,
+            };
+        let proof = if proof.is_empty() {
+            attached.unwrap_or_default()
+        } else {
+            proof
+        };
+        self.params
+            .trinity
+            .verify_commitment_proof(&com, &proof, num_bits)
+            .is_ok()
+    }
 }
 
 /// WASM wrapper for evaluator commitment
@@ -157,34 +787,110 @@ pub struct WasmCommitment {
 }
 
 /// WASM wrapper for evaluator
+///
+/// One evaluator commitment can be reused across any number of garbled
+/// circuits. Each garbling draws fresh labels and OT randomness, and the
+/// receiver only ever learns the labels matching its committed bits, so
+/// evaluating several different circuits against the same input is safe.
+/// The OT receiver is therefore kept for the lifetime of the evaluator.
+///
+/// The private input bits, the copies made while evaluating and the choices
+/// held by the OT receiver are wiped from memory when they are dropped.
 #[wasm_bindgen]
 pub struct TrinityEvaluator {
     commitment: WasmCommitment,
-    ot_receiver: Option<KZGOTReceiver<'static, ()>>,
-    evaluator_bits: Vec<bool>,
+    ot_receiver: KZGOTReceiver<()>,
+    evaluator_bits: Zeroizing<Vec<bool>>,
 }
 
 #[wasm_bindgen]
 impl TrinityEvaluator {
     #[wasm_bindgen(constructor)]
-    pub fn new(setup: &TrinityWasmSetup, evaluator_input: Vec<u8>) -> TrinityEvaluator {
-        let evaluator_bits = u8_vec_to_vec_bool(evaluator_input.clone())
-            .into_iter_lsb0()
-            .collect::<Vec<bool>>();
+    pub fn new(
+        setup: &TrinityWasmSetup,
+        evaluator_input: Vec<u8>,
+    ) -> Result<TrinityEvaluator, JsError> {
+        Self::from_bits(setup, bytes_to_bits(evaluator_input), None)
+    }
+
+    /// Like `new`, but calls `on_progress` with the completed fraction
+    /// (a number between 0 and 1) while the commitment and openings are
+    /// computed, so callers can show progress for large inputs
+    #[wasm_bindgen(static_method_of = TrinityEvaluator)]
+    pub fn new_with_progress(
+        setup: &TrinityWasmSetup,
+        evaluator_input: Vec<u8>,
+        on_progress: &js_sys::Function,
+    ) -> Result<TrinityEvaluator, JsError> {
+        let progress = |fraction: f32| {
+            // A throwing callback must not abort the commitment
+            let _ = on_progress.call1(&JsValue::NULL, &JsValue::from_f64(fraction.into()));
+        };
+        Self::from_bits(setup, bytes_to_bits(evaluator_input), Some(&progress))
+    }
 
-        // Create static parameters
-        let params: &'static SetupParams = Box::leak(Box::new(setup.params.clone()));
+    /// Like `new`, but takes one integer per input value together with its
+    /// bit width (1, 8, 16, 32 or 64) instead of pre-packed bytes
+    #[wasm_bindgen(static_method_of = TrinityEvaluator)]
+    pub fn from_values(
+        setup: &TrinityWasmSetup,
+        values: Vec<u64>,
+        widths: Vec<usize>,
+    ) -> Result<TrinityEvaluator, JsError> {
+        let evaluator_bits = pack_values(&values, &widths)?;
+        Self::from_bits(setup, evaluator_bits, None)
+    }
 
-        // Generate commitment
-        let bundle = ev_commit(evaluator_bits.clone(), params).unwrap();
+    fn from_bits(
+        setup: &TrinityWasmSetup,
+        evaluator_bits: Lsb0Bits,
+        progress: Option<&dyn Fn(f32)>,
+    ) -> Result<TrinityEvaluator, JsError> {
+        // Generate commitment, the OT receiver owns its openings and does
+        // not borrow from the setup parameters. Fails for inputs over the
+        // setup's capacity and for sender-only setups
+        let evaluator_bits = Zeroizing::new(evaluator_bits.into_inner());
+        let bundle = ev_commit_with_progress(
+            Lsb0Bits::new(evaluator_bits.to_vec()),
+            &setup.params,
+            progress,
+        )
+        .map_err(|e| JsError::new(&format!("Failed to commit to evaluator input: {}", e)))?;
 
-        TrinityEvaluator {
+        Ok(TrinityEvaluator {
             commitment: WasmCommitment {
                 commitment: bundle.receiver_commitment,
             },
-            ot_receiver: Some(bundle.ot_receiver),
-            evaluator_bits: evaluator_bits.clone(),
+            ot_receiver: bundle.ot_receiver,
+            evaluator_bits,
+        })
+    }
+
+    /// Check that the committed input has as many bits as the circuit expects
+    /// from the evaluator
+    #[wasm_bindgen]
+    pub fn validate_against(&self, circuit: &CircuitWrapper) -> Result<(), JsError> {
+        if self.evaluator_bits.len() != circuit.evaluator_input_size {
+            return Err(JsError::new(&format!(
+                "Evaluator input has {} bits, circuit expects {}",
+                self.evaluator_bits.len(),
+                circuit.evaluator_input_size
+            )));
         }
+        Ok(())
+    }
+
+    /// The committed choice (0 or 1) at evaluator input position `i`, i.e.
+    /// the branch the OT receiver opens there. For checking which branch was
+    /// taken when a protocol run fails; the evaluator knows its bits anyway.
+    #[wasm_bindgen]
+    pub fn selected_branch(&self, i: usize) -> Result<u8, JsError> {
+        let choice = self
+            .ot_receiver
+            .trinity_receiver
+            .selected_branch(i)
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(u8::from(choice == TrinityChoice::One))
     }
 
     /// Get evaluator commitment
@@ -193,35 +899,186 @@ impl TrinityEvaluator {
         self.commitment.clone()
     }
 
-    /// Get serialized evaluator commitment
+    /// Get serialized evaluator commitment, including the bit proof
+    #[wasm_bindgen(getter)]
+    pub fn commitment_serialized(&self) -> Result<String, JsError> {
+        let (commitment, proof) = self.ot_receiver.trinity_receiver.commitment_with_proof();
+        let com = SerializableTrinityCom::with_proof(commitment, proof);
+        let json = to_json(&com).map_err(|e| JsError::new(&e.to_string()))?;
+        // serde_json only ever writes UTF-8
+        Ok(String::from_utf8(json).expect("JSON is UTF-8"))
+    }
+
+    /// Evaluator commitment as a hex string, without the bit proof
+    #[wasm_bindgen(getter)]
+    pub fn commitment_hex(&self) -> String {
+        self.ot_receiver.trinity_receiver.commitment().to_hex()
+    }
+
+    /// Evaluator commitment as unpadded URL-safe base64, without the bit
+    /// proof
     #[wasm_bindgen(getter)]
-    pub fn commitment_serialized(&self) -> String {
-        let com: SerializableTrinityCom = self.commitment.commitment.clone().into();
-        serde_json::to_string(&com).expect("Failed to serialize commitment")
+    pub fn commitment_base64url(&self) -> String {
+        self.ot_receiver
+            .trinity_receiver
+            .commitment()
+            .to_base64url()
+    }
+
+    /// Evaluate circuit and return one byte (0 or 1) per output bit. The
+    /// evaluator keeps its OT receiver, so it can be called again with the
+    /// same or another garbled circuit. A malformed bundle or a circuit that
+    /// does not match it is reported as an error rather than a panic.
+    #[wasm_bindgen]
+    pub fn evaluate(
+        &self,
+        garbled_data: &TrinityGarbler,
+        circuit: &CircuitWrapper,
+    ) -> Result<Vec<u8>, JsError> {
+        let bits = self.evaluate_bits(garbled_data, circuit)?;
+        Ok(bits.into_iter().map(u8::from).collect())
+    }
+
+    /// Evaluate circuit and pack the output bits into bytes (LSB0), e.g.
+    /// `[10, 0]` for a 16-bit output of 10. Unlike `evaluate`, which returns
+    /// one byte per bit.
+    #[wasm_bindgen]
+    pub fn evaluate_packed(
+        &self,
+        garbled_data: &TrinityGarbler,
+        circuit: &CircuitWrapper,
+    ) -> Result<Vec<u8>, JsError> {
+        let bits = self.evaluate_bits(garbled_data, circuit)?;
+        Ok(pack_lsb0(&bits))
     }
 
-    /// Evaluate circuit
+    /// Evaluate a circuit parsed with `parse_circuit_with_outputs` and return
+    /// one byte array per output value, packed like `evaluate_packed`
     #[wasm_bindgen]
-    pub fn evaluate(&mut self, garbled_data: &TrinityGarbler, circuit: &CircuitWrapper) -> Vec<u8> {
-        // Take OT receiver
-        let ot_receiver = self.ot_receiver.take().unwrap();
+    pub fn evaluate_outputs(
+        &self,
+        garbled_data: &TrinityGarbler,
+        circuit: &CircuitWrapper,
+    ) -> Result<js_sys::Array, JsError> {
+        let bits = self.evaluate_bits(garbled_data, circuit)?;
+        let mut rest = &bits[..];
+        Ok(circuit
+            .output_sizes
+            .iter()
+            .map(|&size| {
+                let (output, tail) = rest.split_at(size);
+                rest = tail;
+                Uint8Array::from(&pack_lsb0(output)[..])
+            })
+            .collect())
+    }
 
-        let received_bundle: GarbledBundle = bincode::deserialize(&garbled_data.bundle)
-            .expect("Failed to deserialize GarbledBundle");
+    /// Evaluate a circuit with at most 32 output bits and return them as one
+    /// integer (LSB0)
+    #[wasm_bindgen]
+    pub fn evaluate_u32(
+        &self,
+        garbled_data: &TrinityGarbler,
+        circuit: &CircuitWrapper,
+    ) -> Result<u32, JsError> {
+        let bits = self.evaluate_bits(garbled_data, circuit)?;
+        if bits.len() > 32 {
+            return Err(JsError::new(&format!(
+                "Circuit has {} output bits, evaluate_u32 supports at most 32",
+                bits.len()
+            )));
+        }
+        let values = decode_outputs(&bits, &[bits.len()])
+            .map_err(|e| JsError::new(&format!("Failed to decode outputs: {}", e)))?;
+        Ok(values[0] as u32)
+    }
 
-        // Evaluate garbled circuit
-        let result = evaluate_circuit(
-            circuit.0.clone(),
+    /// Evaluate circuit and decode the output bits into one integer per
+    /// entry of `output_widths`
+    #[wasm_bindgen]
+    pub fn evaluate_typed(
+        &self,
+        garbled_data: &TrinityGarbler,
+        circuit: &CircuitWrapper,
+        output_widths: Vec<usize>,
+    ) -> Result<Vec<u64>, JsError> {
+        let received_bundle = GarbledBundle::from_bytes(&garbled_data.bundle)
+            .map_err(|e| JsError::new(&format!("Failed to deserialize GarbledBundle: {}", e)))?;
+
+        evaluate_circuit_typed(
+            circuit.circuit.clone(),
             received_bundle,
-            self.evaluator_bits.clone(),
-            ot_receiver,
+            Lsb0Bits::new(self.evaluator_bits.to_vec()),
+            &self.ot_receiver,
+            &output_widths,
         )
-        .unwrap();
+        .map_err(|e| JsError::new(&format!("Failed to decode outputs: {}", e)))
+    }
+
+    /// Evaluate circuit and return the output MACs, 16 bytes per output bit,
+    /// that `evaluate` decodes with the bundle's decoding bits. Lets a third
+    /// party re-check the decoding in a dispute.
+    ///
+    /// The MACs are the evaluator's output labels and tie labels to values,
+    /// so only hand them out once the protocol has completed.
+    #[wasm_bindgen]
+    pub fn output_macs(
+        &self,
+        garbled_data: &TrinityGarbler,
+        circuit: &CircuitWrapper,
+    ) -> Result<Vec<u8>, JsError> {
+        let received_bundle = GarbledBundle::from_bytes(&garbled_data.bundle)
+            .map_err(|e| JsError::new(&format!("Failed to deserialize GarbledBundle: {}", e)))?;
+
+        let (_, macs) = evaluate_circuit_with_macs(
+            circuit.circuit.clone(),
+            received_bundle,
+            Lsb0Bits::new(self.evaluator_bits.to_vec()),
+            &self.ot_receiver,
+        )
+        .map_err(|e| JsError::new(&format!("Failed to evaluate circuit: {}", e)))?;
+        Ok(macs
+            .iter()
+            .flat_map(|mac| mac.as_block().to_bytes())
+            .collect())
+    }
+}
+
+impl TrinityEvaluator {
+    fn evaluate_bits(
+        &self,
+        garbled_data: &TrinityGarbler,
+        circuit: &CircuitWrapper,
+    ) -> Result<Vec<bool>, JsError> {
+        let received_bundle = GarbledBundle::from_bytes(&garbled_data.bundle)
+            .map_err(|e| JsError::new(&format!("Failed to deserialize GarbledBundle: {}", e)))?;
 
-        result.into_iter().map(u8::from).collect()
+        evaluate_circuit(
+            circuit.circuit.clone(),
+            received_bundle,
+            Lsb0Bits::new(self.evaluator_bits.to_vec()),
+            &self.ot_receiver,
+        )
+        .map_err(|e| JsError::new(&format!("Failed to evaluate circuit: {}", e)))
     }
 }
 
+fn bytes_to_bits(input: Vec<u8>) -> Lsb0Bits {
+    Lsb0Bits::from_bytes(&input)
+}
+
+/// Pack JS-provided integers, each described by its bit width
+fn pack_values(values: &[u64], widths: &[usize]) -> Result<Lsb0Bits, JsError> {
+    let types = widths
+        .iter()
+        .map(|&width| value_type_for_width(width))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| JsError::new(&format!("Invalid input widths: {}", e)))?;
+    pack_inputs(values, &types)
+        .map(Lsb0Bits::new)
+        .map_err(|e| JsError::new(&format!("Invalid inputs: {}", e)))
+}
+
 /// WASM wrapper for garbler
 #[wasm_bindgen]
 pub struct TrinityGarbler {
@@ -231,41 +1088,113 @@ pub struct TrinityGarbler {
 #[wasm_bindgen]
 impl TrinityGarbler {
     /// Generate garbled circuit with hardcoded inputs
+    ///
+    /// The garbling RNG is seeded from OS entropy, so every call produces fresh
+    /// wire labels and OT randomness. Use `new_with_seed` to supply entropy
+    /// from the JS side, or `new_deterministic` for reproducible tests.
     #[wasm_bindgen(constructor)]
     pub fn new(
         evaluator_commitment: String,
         setup: &TrinityWasmSetup,
         garbler_input: Vec<u8>,
         circuit: &CircuitWrapper,
-    ) -> TrinityGarbler {
-        let deserialized_commitment = TrinityCom::deserialize(evaluator_commitment.as_bytes())
-            .expect("Failed to deserialize commitment");
-        let garbler_bits = u8_vec_to_vec_bool(garbler_input)
-            .into_iter_lsb0()
-            .collect::<Vec<bool>>();
+    ) -> Result<TrinityGarbler, JsError> {
+        let mut rng = StdRng::from_entropy();
+        Self::garble(
+            evaluator_commitment,
+            setup,
+            bytes_to_bits(garbler_input),
+            circuit,
+            &mut rng,
+        )
+    }
 
-        // Create deterministic RNG
-        let mut rng = StdRng::seed_from_u64(42);
+    /// Like `new`, but takes one integer per input value together with its
+    /// bit width (1, 8, 16, 32 or 64) instead of pre-packed bytes
+    #[wasm_bindgen(static_method_of = TrinityGarbler)]
+    pub fn from_values(
+        evaluator_commitment: String,
+        setup: &TrinityWasmSetup,
+        values: Vec<u64>,
+        widths: Vec<usize>,
+        circuit: &CircuitWrapper,
+    ) -> Result<TrinityGarbler, JsError> {
+        let garbler_bits = pack_values(&values, &widths)?;
+        let mut rng = StdRng::from_entropy();
+        Self::garble(evaluator_commitment, setup, garbler_bits, circuit, &mut rng)
+    }
+
+    /// Generate garbled circuit from a caller-provided 32-byte seed,
+    /// e.g. obtained from `crypto.getRandomValues`.
+    #[wasm_bindgen(static_method_of = TrinityGarbler)]
+    pub fn new_with_seed(
+        evaluator_commitment: String,
+        setup: &TrinityWasmSetup,
+        garbler_input: Vec<u8>,
+        circuit: &CircuitWrapper,
+        seed: Vec<u8>,
+    ) -> Result<TrinityGarbler, JsError> {
+        let seed: [u8; 32] = seed.try_into().map_err(|s: Vec<u8>| {
+            JsError::new(&format!("Seed must be 32 bytes, got {}", s.len()))
+        })?;
+        let mut rng = StdRng::from_seed(seed);
+        Self::garble(
+            evaluator_commitment,
+            setup,
+            bytes_to_bits(garbler_input),
+            circuit,
+            &mut rng,
+        )
+    }
+
+    /// Generate garbled circuit from a fixed `u64` seed.
+    ///
+    /// The output is fully predictable: only use this for reproducible tests.
+    #[wasm_bindgen(static_method_of = TrinityGarbler)]
+    pub fn new_deterministic(
+        evaluator_commitment: String,
+        setup: &TrinityWasmSetup,
+        garbler_input: Vec<u8>,
+        circuit: &CircuitWrapper,
+        seed: u64,
+    ) -> Result<TrinityGarbler, JsError> {
+        let mut rng = Randomness::Deterministic { seed }.rng();
+        Self::garble(
+            evaluator_commitment,
+            setup,
+            bytes_to_bits(garbler_input),
+            circuit,
+            &mut rng,
+        )
+    }
+
+    fn garble(
+        evaluator_commitment: String,
+        setup: &TrinityWasmSetup,
+        garbler_bits: Lsb0Bits,
+        circuit: &CircuitWrapper,
+        rng: &mut StdRng,
+    ) -> Result<TrinityGarbler, JsError> {
+        let deserialized_commitment = TrinityCom::deserialize(evaluator_commitment.as_bytes())
+            .map_err(|e| JsError::new(&format!("Failed to deserialize commitment: {}", e)))?;
 
         // Generate random delta
-        let delta = Delta::random(&mut rng);
+        let delta = Delta::random(rng);
 
         // Generate garbled circuit
         let bundle = generate_garbled_circuit(
-            circuit.0.clone(),
+            circuit.circuit.clone(),
             garbler_bits,
-            &mut rng,
+            rng,
             delta,
             &setup.params.trinity,
             deserialized_commitment,
-        );
-
-        let serialized_bundle =
-            bincode::serialize(&bundle).expect("Failed to serialize GarbledBundle");
+        )
+        .map_err(|e| JsError::new(&e.to_string()))?;
 
-        TrinityGarbler {
-            bundle: serialized_bundle,
-        }
+        Ok(TrinityGarbler {
+            bundle: bundle.to_bytes(),
+        })
     }
 
     #[wasm_bindgen(getter)]
@@ -273,6 +1202,20 @@ impl TrinityGarbler {
         self.bundle.clone()
     }
 
+    /// Number of OT ciphertexts in the bundle, one per evaluator input bit.
+    /// Read from the bundle header, without deserializing the bundle.
+    #[wasm_bindgen(getter)]
+    pub fn ciphertext_count(&self) -> Result<usize, JsError> {
+        GarbledBundle::ciphertext_count_from_bytes(&self.bundle)
+            .ok_or_else(|| JsError::new("Garbled bundle is truncated"))
+    }
+
+    /// Size of the serialized bundle in bytes
+    #[wasm_bindgen(getter)]
+    pub fn bundle_size(&self) -> usize {
+        self.bundle.len()
+    }
+
     #[wasm_bindgen(static_method_of = TrinityGarbler)]
     pub fn from_bundle(bundle_bytes: &[u8]) -> TrinityGarbler {
         TrinityGarbler {
@@ -299,6 +1242,271 @@ impl WasmCommitment {
     }
 }
 
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn setup_with_unknown_mode_returns_js_error() {
+        let err = TrinityWasmSetup::new("Bogus")
+            .err()
+            .expect("Unknown mode should be rejected");
+
+        // The error crosses the boundary as a regular JS `Error`
+        let value: JsValue = err.into();
+        assert!(value.is_instance_of::<js_sys::Error>());
+        let message: String = js_sys::Error::from(value).message().into();
+        assert_eq!(message, "Unknown mode 'Bogus', expected 'Plain' or 'Halo2'");
+    }
+
+    #[cfg(feature = "plain")]
+    #[wasm_bindgen_test]
+    fn evaluator_rejects_oversized_input_and_sender_setup() {
+        let setup = TrinityWasmSetup::with_degree("Plain", MIN_SETUP_DEGREE).unwrap();
+        let oversized = vec![0u8; (1 << MIN_SETUP_DEGREE) / 8 + 1];
+        assert!(TrinityEvaluator::new(&setup, oversized).is_err());
+
+        let sender = TrinityWasmSetup::from_sender_setup(&setup.to_sender_setup()).unwrap();
+        assert!(TrinityEvaluator::new(&sender, vec![4]).is_err());
+        assert!(TrinityEvaluator::from_values(&sender, vec![4], vec![8]).is_err());
+    }
+
+    #[cfg(feature = "plain")]
+    #[wasm_bindgen_test]
+    fn setup_mode_is_case_insensitive() {
+        assert!(TrinityWasmSetup::new("plain").is_ok());
+    }
+
+    #[cfg(feature = "halo2")]
+    #[wasm_bindgen_test]
+    fn verify_commitment_accepts_valid_and_rejects_corrupted() {
+        let setup = TrinityWasmSetup::with_degree("Halo2", MIN_SETUP_DEGREE).unwrap();
+        let evaluator = TrinityEvaluator::new(&setup, vec![0b1011_0010]).unwrap();
+        let serialized = evaluator.commitment_serialized().unwrap();
+
+        assert!(setup.verify_commitment(&serialized, vec![], 8));
+
+        let (_, proof) = TrinityCom::deserialize_with_proof(serialized.as_bytes()).unwrap();
+        let mut tampered = proof.unwrap();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(!setup.verify_commitment(&serialized, tampered, 8));
+        assert!(!setup.verify_commitment("{}", vec![], 8));
+    }
+
+    #[cfg(feature = "plain")]
+    #[wasm_bindgen_test]
+    fn verify_commitment_checks_plain_bit_proof() {
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        let evaluator = TrinityEvaluator::new(&setup, vec![3]).unwrap();
+        let serialized = evaluator.commitment_serialized().unwrap();
+        assert!(setup.verify_commitment(&serialized, vec![], 8));
+
+        let (_, proof) = TrinityCom::deserialize_with_proof(serialized.as_bytes()).unwrap();
+        let mut tampered = proof.unwrap();
+        tampered[40] ^= 1;
+        assert!(!setup.verify_commitment(&serialized, tampered, 8));
+        // A bare commitment has no proof to check
+        let bare = String::from_utf8(evaluator.commitment.commitment.serialize()).unwrap();
+        assert!(!setup.verify_commitment(&bare, vec![], 8));
+    }
+
+    #[cfg(feature = "plain")]
+    #[wasm_bindgen_test]
+    fn selected_branch_reports_committed_bits() {
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        let evaluator = TrinityEvaluator::new(&setup, vec![0b0000_0101]).unwrap();
+
+        let branches: Vec<u8> = (0..8)
+            .map(|i| evaluator.selected_branch(i).unwrap())
+            .collect();
+        assert_eq!(branches, vec![1, 0, 1, 0, 0, 0, 0, 0]);
+        assert!(evaluator.selected_branch(8).is_err());
+    }
+
+    #[cfg(feature = "plain")]
+    #[wasm_bindgen_test]
+    fn commitment_hex_and_base64url_roundtrip() {
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        let evaluator = TrinityEvaluator::new(&setup, vec![7, 1]).unwrap();
+
+        let from_json =
+            TrinityCom::deserialize(evaluator.commitment_serialized().unwrap().as_bytes()).unwrap();
+        let from_hex = TrinityCom::from_hex(&evaluator.commitment_hex()).unwrap();
+        let from_base64 = TrinityCom::from_base64url(&evaluator.commitment_base64url()).unwrap();
+        assert!(from_hex == from_json);
+        assert!(from_base64 == from_json);
+
+        assert!(TrinityCom::from_hex("zz").is_err());
+        assert!(TrinityCom::from_hex(&format!("07{}", &evaluator.commitment_hex()[2..])).is_err());
+    }
+
+    #[cfg(feature = "plain")]
+    #[wasm_bindgen_test]
+    fn setup_loads_from_params_chunks() {
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        let bytes = setup.to_full_params_bytes();
+        assert_eq!(setup.params_len(), bytes.len());
+
+        let chunks = bytes.chunks(1000).map(Uint8Array::from).collect::<Vec<_>>();
+        let loaded = TrinityWasmSetup::from_params_chunks(chunks).unwrap();
+        assert_eq!(loaded.to_full_params_bytes(), bytes);
+
+        let sender_bytes = setup.to_sender_setup();
+        let sender = TrinityWasmSetup::from_sender_setup(&sender_bytes).unwrap();
+        assert_eq!(sender.params_len(), sender_bytes.len());
+        // Served from the cache the second time
+        assert_eq!(sender.params_len(), sender_bytes.len());
+    }
+
+    #[cfg(feature = "plain")]
+    #[wasm_bindgen_test]
+    fn evaluate_twice_and_reject_corrupted_bundle() {
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        let circuit =
+            parse_circuit(include_str!("../circuits/simple_16bit_add.txt"), 16, 16, 16).unwrap();
+        let evaluator = TrinityEvaluator::new(&setup, vec![4, 0]).unwrap();
+        let garbler = TrinityGarbler::new(
+            evaluator.commitment_serialized().unwrap(),
+            &setup,
+            vec![6, 0],
+            &circuit,
+        )
+        .unwrap();
+
+        // The receiver is not consumed by the first evaluation
+        let first = evaluator.evaluate(&garbler, &circuit).unwrap();
+        assert_eq!(evaluator.evaluate(&garbler, &circuit).unwrap(), first);
+
+        let mut bundle = garbler.bundle();
+        bundle.truncate(bundle.len() / 2);
+        let corrupted = TrinityGarbler::from_bundle(&bundle);
+        assert!(evaluator.evaluate(&corrupted, &circuit).is_err());
+        assert!(evaluator
+            .evaluate_typed(&corrupted, &circuit, vec![16])
+            .is_err());
+
+        // A bundle for another circuit does not evaluate either
+        let not = parse_circuit(include_str!("../circuits/8bit_not.txt"), 8, 0, 8).unwrap();
+        assert!(evaluator.evaluate(&garbler, &not).is_err());
+    }
+
+    #[cfg(feature = "plain")]
+    #[wasm_bindgen_test]
+    fn evaluate_outputs_splits_two_outputs() {
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        let circuit = parse_circuit_with_outputs(
+            include_str!("../circuits/2bit_and_xor.txt"),
+            2,
+            2,
+            vec![2, 2],
+        )
+        .unwrap();
+        assert_eq!(circuit.output_sizes(), vec![2, 2]);
+
+        let evaluator = TrinityEvaluator::from_values(&setup, vec![1, 1], vec![1, 1]).unwrap();
+        let garbler = TrinityGarbler::from_values(
+            evaluator.commitment_serialized().unwrap(),
+            &setup,
+            vec![0, 1],
+            vec![1, 1],
+            &circuit,
+        )
+        .unwrap();
+
+        // 0b11 & 0b10 and 0b11 ^ 0b10
+        let outputs = evaluator.evaluate_outputs(&garbler, &circuit).unwrap();
+        assert_eq!(outputs.length(), 2);
+        assert_eq!(Uint8Array::new(&outputs.get(0)).to_vec(), vec![0b10]);
+        assert_eq!(Uint8Array::new(&outputs.get(1)).to_vec(), vec![0b01]);
+
+        // The sizes have to cover the circuit's outputs
+        assert!(parse_circuit_with_outputs(
+            include_str!("../circuits/2bit_and_xor.txt"),
+            2,
+            2,
+            vec![2, 1],
+        )
+        .is_err());
+    }
+
+    #[cfg(feature = "plain")]
+    #[wasm_bindgen_test]
+    fn evaluate_packed_16bit_add() {
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        let circuit =
+            parse_circuit(include_str!("../circuits/simple_16bit_add.txt"), 16, 16, 16).unwrap();
+        let evaluator = TrinityEvaluator::new(&setup, vec![4, 0]).unwrap();
+        let garbler = TrinityGarbler::new(
+            evaluator.commitment_serialized().unwrap(),
+            &setup,
+            vec![6, 0],
+            &circuit,
+        )
+        .unwrap();
+
+        assert_eq!(
+            evaluator.evaluate_packed(&garbler, &circuit).unwrap(),
+            vec![10, 0]
+        );
+        assert_eq!(evaluator.evaluate_u32(&garbler, &circuit).unwrap(), 10);
+    }
+
+    #[cfg(feature = "plain")]
+    #[wasm_bindgen_test]
+    fn garbler_reports_ciphertext_count_and_size() {
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        let circuit =
+            parse_circuit(include_str!("../circuits/simple_16bit_add.txt"), 16, 16, 16).unwrap();
+        let evaluator = TrinityEvaluator::new(&setup, vec![4, 0]).unwrap();
+
+        let garbler = TrinityGarbler::new(
+            evaluator.commitment_serialized().unwrap(),
+            &setup,
+            vec![6, 0],
+            &circuit,
+        )
+        .unwrap();
+        assert_eq!(
+            garbler.ciphertext_count().unwrap(),
+            circuit.evaluator_input_size()
+        );
+        assert_eq!(garbler.bundle_size(), garbler.bundle().len());
+    }
+
+    #[wasm_bindgen_test]
+    fn parse_circuit_bytes_rejects_invalid_utf8() {
+        assert!(parse_circuit_bytes(&[0xff, 0xfe], 16, 16, 16).is_err());
+    }
+
+    #[cfg(feature = "plain")]
+    #[wasm_bindgen_test]
+    fn evaluator_reports_progress_to_js_callback() {
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        let on_progress = js_sys::Function::new_with_args("p", "globalThis.__trinityProgress = p");
+        let _evaluator =
+            TrinityEvaluator::new_with_progress(&setup, vec![16, 0], &on_progress).unwrap();
+
+        let last = js_sys::Reflect::get(&js_sys::global(), &"__trinityProgress".into()).unwrap();
+        assert_eq!(last.as_f64(), Some(1.0));
+    }
+
+    #[cfg(feature = "plain")]
+    #[wasm_bindgen_test]
+    fn evaluator_input_length_is_validated() {
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        let circuit =
+            parse_circuit(include_str!("../circuits/simple_16bit_add.txt"), 16, 16, 16).unwrap();
+
+        let evaluator = TrinityEvaluator::new(&setup, vec![4, 0]).unwrap();
+        assert!(evaluator.validate_against(&circuit).is_ok());
+
+        let evaluator = TrinityEvaluator::new(&setup, vec![4]).unwrap();
+        assert!(evaluator.validate_against(&circuit).is_err());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::commit::{Trinity, TrinityChoice};
@@ -306,29 +1514,81 @@ mod tests {
     use super::*;
 
     fn u16_vec_to_vec_bool(values: Vec<u16>) -> Vec<bool> {
-        let mut result = Vec::new();
-        for value in values {
-            // Convert each u16 to its bit representation (16 bits)
-            for i in 0..16 {
-                result.push(((value >> i) & 1) == 1);
-            }
+        let values: Vec<u64> = values.into_iter().map(u64::from).collect();
+        pack_inputs(&values, &vec![ValueType::U16; values.len()]).unwrap()
+    }
+
+    #[test]
+    fn test_parse_circuit_reuses_cached_circuit() {
+        let circuit_str = include_str!("../circuits/8bit_not.txt");
+        let first = parse_circuit(circuit_str, 8, 0, 8).unwrap();
+        let second = parse_circuit(circuit_str, 8, 0, 8).unwrap();
+        assert!(Arc::ptr_eq(&first.circuit, &second.circuit));
+
+        // Different sizes are a different entry
+        let other = parse_circuit(circuit_str, 0, 8, 8).unwrap();
+        assert!(!Arc::ptr_eq(&first.circuit, &other.circuit));
+
+        clear_circuit_cache();
+        let third = parse_circuit(circuit_str, 8, 0, 8).unwrap();
+        assert!(!Arc::ptr_eq(&first.circuit, &third.circuit));
+    }
+
+    #[test]
+    fn test_circuit_cache_evicts_least_recently_used() {
+        let circuit = parse_circuit(include_str!("../circuits/8bit_not.txt"), 8, 0, 8)
+            .unwrap()
+            .circuit;
+        let key = |i: usize| CircuitCache::key("circuit", &[i]);
+
+        let mut cache = CircuitCache::default();
+        for i in 0..CIRCUIT_CACHE_CAPACITY {
+            cache.insert(key(i), circuit.clone());
+        }
+        // Touch the oldest entry, so the next insert evicts the second one
+        assert!(cache.get(&key(0)).is_some());
+        cache.insert(key(CIRCUIT_CACHE_CAPACITY), circuit.clone());
+
+        assert_eq!(cache.entries.len(), CIRCUIT_CACHE_CAPACITY);
+        assert!(cache.get(&key(0)).is_some());
+        assert!(cache.get(&key(1)).is_none());
+        assert!(cache.get(&key(CIRCUIT_CACHE_CAPACITY)).is_some());
+        assert_ne!(key(0), CircuitCache::key("circuit", &[0, 0]));
+    }
+
+    #[test]
+    fn test_parse_circuit_from_str_bytes_and_file() {
+        let circuit_str = include_str!("../circuits/simple_16bit_add.txt");
+        let from_str = parse_circuit(circuit_str, 16, 16, 16).unwrap();
+        let from_bytes = parse_circuit_bytes(circuit_str.as_bytes(), 16, 16, 16).unwrap();
+        let from_file =
+            CircuitWrapper::from_file("circuits/simple_16bit_add.txt", 16, 16, 16).unwrap();
+
+        for circuit in [&from_bytes, &from_file] {
+            assert_eq!(circuit.circuit.input_len(), from_str.circuit.input_len());
+            assert_eq!(circuit.evaluator_input_size, from_str.evaluator_input_size);
+            assert_eq!(circuit.garbler_input_size, from_str.garbler_input_size);
         }
-        result
     }
 
+    #[cfg(feature = "plain")]
     #[test]
     fn test_trinity_com_serialization_roundtrip() {
         use crate::commit::TrinityCom;
 
-        let original = TrinityCom::Plain(ark_bn254::G1Affine::default().into());
+        let original = TrinityCom::Plain(ark_bn254::G1Affine::default().into(), [3; 32]);
         let json = original.serialize();
         let deserialized = TrinityCom::deserialize(&json).unwrap();
         match deserialized {
-            TrinityCom::Plain(g1) => assert_eq!(g1, ark_bn254::G1Affine::default()),
+            TrinityCom::Plain(g1, domain) => {
+                assert_eq!(g1, ark_bn254::G1Affine::default());
+                assert_eq!(domain, [3; 32]);
+            }
             _ => panic!("Expected Plain commitment"),
         }
     }
 
+    #[cfg(feature = "halo2")]
     #[test]
     fn test_trinity_msg_serialization_roundtrip_halo2() {
         use crate::commit::TrinityMsg;
@@ -341,7 +1601,7 @@ mod tests {
         });
 
         let serialized = original_msg.serialize();
-        let deserialized = TrinityMsg::deserialize(&serialized).unwrap();
+        let deserialized: TrinityMsg = TrinityMsg::deserialize(&serialized).unwrap();
 
         if let TrinityMsg::Halo2(m2) = deserialized {
             assert_eq!(m2.h[0].1, [1u8; 16]);
@@ -353,6 +1613,38 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "halo2")]
+    #[test]
+    fn test_trinity_msg_binary_roundtrip_is_compact() {
+        use crate::commit::TrinityMsg;
+        use halo2_we_kzg::laconic_ot::Msg;
+        use halo2curves::bn256::G2Affine;
+
+        let g2 = G2Affine::generator();
+        let original_msg = TrinityMsg::Halo2(Msg {
+            h: [(g2, [1u8; 16]), (g2, [2u8; 16])],
+        });
+
+        let bytes = original_msg.to_bytes();
+        match TrinityMsg::<16>::from_bytes(&bytes).unwrap() {
+            TrinityMsg::Halo2(m) => assert_eq!(m.h, [(g2, [1u8; 16]), (g2, [2u8; 16])]),
+            #[allow(unreachable_patterns)]
+            _ => panic!("Expected Halo2 message"),
+        }
+
+        let json = original_msg.serialize();
+        assert!(
+            bytes.len() * 2 < json.len(),
+            "binary {} bytes, JSON {} bytes",
+            bytes.len(),
+            json.len()
+        );
+
+        assert!(TrinityMsg::<16>::from_bytes(&[]).is_err());
+        assert!(TrinityMsg::<16>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[cfg(feature = "halo2")]
     #[test]
     fn two_pc_serialization_flow_halo2() {
         // Setup RNG
@@ -371,10 +1663,8 @@ mod tests {
         let arc_circuit = Arc::new(circ.clone());
 
         // Define inputs and expected output
-        let garbler_input = [6u16];
-        let garbler_bits = garbler_input.into_iter_lsb0().collect::<Vec<bool>>();
-        let evaluator_input = [4u16];
-        let evaluator_bits = evaluator_input.into_iter_lsb0().collect::<Vec<bool>>();
+        let garbler_bits = Lsb0Bits::from_u16(6);
+        let evaluator_bits = Lsb0Bits::from_u16(4);
         let expected: [u16; 1] = [10u16];
 
         // === EVALUATOR SETUP (SERVER) ===
@@ -383,18 +1673,10 @@ mod tests {
 
         // Create OT receiver and commitment
         let ot_receiver = evaluator_trinity
-            .create_ot_receiver::<()>(
-                &evaluator_bits
-                    .iter()
-                    .map(|&b| {
-                        if b {
-                            TrinityChoice::One
-                        } else {
-                            TrinityChoice::Zero
-                        }
-                    })
-                    .collect::<Vec<_>>(),
-            )
+            .create_ot_receiver::<()>(&TrinityChoice::unpack(
+                &evaluator_bits.to_bytes(),
+                evaluator_bits.len(),
+            ))
             .expect("Failed to create receiver");
 
         let commitment = ot_receiver.trinity_receiver.commitment();
@@ -424,16 +1706,298 @@ mod tests {
             delta,
             &garbler_trinity, // Note: we'd need to adjust this to use garbler_trinity
             commitment,
-        );
+        )
+        .unwrap();
 
         // === BACK TO EVALUATOR ===
         // Evaluate garbled circuit
-        let result = evaluate_circuit(arc_circuit, garbled, evaluator_bits, ot_receiver).unwrap();
+        let result = evaluate_circuit(arc_circuit, garbled, evaluator_bits, &ot_receiver).unwrap();
 
         // Verify result
         assert_eq!(result, u16_vec_to_vec_bool(expected.to_vec()));
     }
 
+    #[cfg(feature = "plain")]
+    #[test]
+    fn garbler_seeding_controls_determinism() {
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        let circuit_str = std::fs::read_to_string("circuits/simple_16bit_add.txt").unwrap();
+        let circuit = parse_circuit(&circuit_str, 16, 16, 16).unwrap();
+
+        let evaluator = TrinityEvaluator::new(&setup, vec![4, 0]).unwrap();
+        let commitment = evaluator.commitment_serialized().unwrap();
+
+        // Default constructor draws fresh entropy on every call
+        let first = TrinityGarbler::new(commitment.clone(), &setup, vec![6, 0], &circuit).unwrap();
+        let second = TrinityGarbler::new(commitment.clone(), &setup, vec![6, 0], &circuit).unwrap();
+        assert_ne!(first.bundle(), second.bundle());
+
+        // Fixed seed reproduces the exact same bundle
+        let first =
+            TrinityGarbler::new_deterministic(commitment.clone(), &setup, vec![6, 0], &circuit, 42)
+                .unwrap();
+        let second =
+            TrinityGarbler::new_deterministic(commitment, &setup, vec![6, 0], &circuit, 42)
+                .unwrap();
+        assert_eq!(first.bundle(), second.bundle());
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn evaluator_commitment_is_reusable_across_circuits() {
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        let circuit_str = std::fs::read_to_string("circuits/simple_16bit_add.txt").unwrap();
+        let circuit = parse_circuit(&circuit_str, 16, 16, 16).unwrap();
+
+        let evaluator = TrinityEvaluator::new(&setup, vec![4, 0]).unwrap();
+        let commitment = evaluator.commitment_serialized().unwrap();
+
+        for (garbler_input, expected) in [(6u16, 10u16), (1u16, 5u16)] {
+            let garbler = TrinityGarbler::new(
+                commitment.clone(),
+                &setup,
+                garbler_input.to_le_bytes().to_vec(),
+                &circuit,
+            )
+            .unwrap();
+            let result = evaluator.evaluate(&garbler, &circuit).unwrap();
+            let expected_bits = u16_vec_to_vec_bool(vec![expected])
+                .into_iter()
+                .map(u8::from)
+                .collect::<Vec<u8>>();
+            assert_eq!(result, expected_bits);
+        }
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn output_macs_decode_to_the_output() {
+        use crate::garble::decode_output_pointers;
+        use mpz_core::Block;
+        use mpz_garble_core::Mac;
+
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        let circuit_str = std::fs::read_to_string("circuits/simple_16bit_add.txt").unwrap();
+        let circuit = parse_circuit(&circuit_str, 16, 16, 16).unwrap();
+        let evaluator = TrinityEvaluator::new(&setup, vec![4, 0]).unwrap();
+        let garbler = TrinityGarbler::new(
+            evaluator.commitment_serialized().unwrap(),
+            &setup,
+            vec![6, 0],
+            &circuit,
+        )
+        .unwrap();
+
+        let macs = evaluator.output_macs(&garbler, &circuit).unwrap();
+        assert_eq!(macs.len(), 16 * 16);
+
+        // A third party holding the MACs and the decoding bits gets the same
+        let pointers: Vec<bool> = macs
+            .chunks_exact(16)
+            .map(|chunk| Mac::from(Block::new(chunk.try_into().unwrap())).pointer())
+            .collect();
+        let bundle = GarbledBundle::from_bytes(&garbler.bundle).unwrap();
+        let output: Vec<u8> = decode_output_pointers(&pointers, bundle.decoding_bits())
+            .into_iter()
+            .map(u8::from)
+            .collect();
+        assert_eq!(output, evaluator.evaluate(&garbler, &circuit).unwrap());
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn parties_can_pass_integer_inputs() {
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        let circuit_str = std::fs::read_to_string("circuits/simple_16bit_add.txt").unwrap();
+        let circuit = parse_circuit(&circuit_str, 16, 16, 16).unwrap();
+
+        let evaluator = TrinityEvaluator::from_values(&setup, vec![4], vec![16]).unwrap();
+        let garbler = TrinityGarbler::from_values(
+            evaluator.commitment_serialized().unwrap(),
+            &setup,
+            vec![6],
+            vec![16],
+            &circuit,
+        )
+        .unwrap();
+
+        let result = evaluator
+            .evaluate_typed(&garbler, &circuit, vec![16])
+            .unwrap();
+        assert_eq!(result, vec![10]);
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn composed_adders_sum_three_inputs() {
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        let adder =
+            parse_circuit(include_str!("../circuits/simple_16bit_add.txt"), 16, 16, 16).unwrap();
+
+        // (a + b) + c, the third input is the garbler's
+        let circuit = adder.compose(&adder, &[(0, 0)]).unwrap();
+        assert_eq!(circuit.evaluator_input_size(), 16);
+        assert_eq!(circuit.garbler_input_size(), 32);
+
+        let evaluator = TrinityEvaluator::from_values(&setup, vec![4], vec![16]).unwrap();
+        let garbler = TrinityGarbler::from_values(
+            evaluator.commitment_serialized().unwrap(),
+            &setup,
+            vec![6, 5],
+            vec![16, 16],
+            &circuit,
+        )
+        .unwrap();
+
+        let result = evaluator
+            .evaluate_typed(&garbler, &circuit, vec![16])
+            .unwrap();
+        assert_eq!(result, vec![15]);
+    }
+
+    #[test]
+    fn compose_rejects_bad_wiring() {
+        let adder =
+            parse_circuit(include_str!("../circuits/simple_16bit_add.txt"), 16, 16, 16).unwrap();
+        let not = parse_circuit(include_str!("../circuits/8bit_not.txt"), 8, 0, 8).unwrap();
+
+        // Out of range on either side
+        assert!(adder.compose(&adder, &[(1, 0)]).is_err());
+        assert!(adder.compose(&adder, &[(0, 2)]).is_err());
+        // 8-bit output into a 16-bit input
+        assert!(not.compose(&adder, &[(0, 0)]).is_err());
+        // Same input wired twice
+        assert!(adder.compose(&adder, &[(0, 1), (0, 1)]).is_err());
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn composed_subtractors_keep_party_roles() {
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        // garbler - evaluator
+        let sub =
+            parse_circuit(include_str!("../circuits/simple_16bit_sub.txt"), 16, 16, 16).unwrap();
+
+        // (g - e) - g2: the evaluator keeps its input, g2 is the garbler's
+        let circuit = sub.compose(&sub, &[(0, 0)]).unwrap();
+        assert_eq!(circuit.evaluator_input_size(), 16);
+        assert_eq!(circuit.garbler_input_size(), 32);
+
+        let evaluator = TrinityEvaluator::from_values(&setup, vec![4], vec![16]).unwrap();
+        for (circuit, garbler_values, expected) in [(&sub, vec![10], 6), (&circuit, vec![10, 3], 3)]
+        {
+            let widths = vec![16; garbler_values.len()];
+            let garbler = TrinityGarbler::from_values(
+                evaluator.commitment_serialized().unwrap(),
+                &setup,
+                garbler_values,
+                widths,
+                circuit,
+            )
+            .unwrap();
+            let result = evaluator
+                .evaluate_typed(&garbler, circuit, vec![16])
+                .unwrap();
+            assert_eq!(result, vec![expected]);
+        }
+    }
+
+    #[test]
+    fn with_constants_rejects_bad_wires() {
+        let adder =
+            parse_circuit(include_str!("../circuits/simple_16bit_add.txt"), 16, 16, 16).unwrap();
+
+        assert!(matches!(
+            adder.with_constants(&[(32, true)]),
+            Err(TrinityError::InvalidConstants(_))
+        ));
+        assert!(matches!(
+            adder.with_constants(&[(3, true), (3, false)]),
+            Err(TrinityError::InvalidConstants(_))
+        ));
+
+        // Only bit inputs can be split into fixed and free wires
+        let bytes = CircuitWrapper {
+            circuit: Arc::new(
+                Circuit::parse_str(
+                    include_str!("../circuits/simple_8bit_add.txt"),
+                    &[ValueType::U8, ValueType::U8],
+                    &[ValueType::U8],
+                )
+                .unwrap(),
+            ),
+            evaluator_input_size: 8,
+            garbler_input_size: 8,
+            output_sizes: vec![8],
+        };
+        assert!(matches!(
+            bytes.with_constants(&[(0, true)]),
+            Err(TrinityError::InvalidConstants(_))
+        ));
+
+        // Fixing one bit of each party shrinks both inputs
+        let specialized = adder.with_constants(&[(0, true), (16, false)]).unwrap();
+        assert_eq!(specialized.garbler_input_size(), 15);
+        assert_eq!(specialized.evaluator_input_size(), 15);
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn constant_inputs_match_full_circuit() {
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        let adder =
+            parse_circuit(include_str!("../circuits/simple_16bit_add.txt"), 16, 16, 16).unwrap();
+
+        // Fix the garbler's addend to the public value 6
+        let constants: Vec<(usize, bool)> = (0..16).map(|i| (i, (6 >> i) & 1 == 1)).collect();
+        let specialized = adder.with_constants(&constants).unwrap();
+        assert_eq!(specialized.garbler_input_size(), 0);
+        assert_eq!(specialized.evaluator_input_size(), 16);
+
+        let evaluator = TrinityEvaluator::from_values(&setup, vec![4], vec![16]).unwrap();
+        let full = TrinityGarbler::from_values(
+            evaluator.commitment_serialized().unwrap(),
+            &setup,
+            vec![6],
+            vec![16],
+            &adder,
+        )
+        .unwrap();
+        let fixed = TrinityGarbler::from_values(
+            evaluator.commitment_serialized().unwrap(),
+            &setup,
+            vec![],
+            vec![],
+            &specialized,
+        )
+        .unwrap();
+
+        let expected = evaluator.evaluate_typed(&full, &adder, vec![16]).unwrap();
+        assert_eq!(expected, vec![10]);
+        assert_eq!(
+            evaluator
+                .evaluate_typed(&fixed, &specialized, vec![16])
+                .unwrap(),
+            expected
+        );
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn evaluators_do_not_retain_setup_params() {
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        assert_eq!(Arc::strong_count(&setup.params.trinity), 1);
+
+        for _ in 0..1000 {
+            let evaluator = TrinityEvaluator::new(&setup, vec![4, 0]).unwrap();
+            drop(evaluator);
+        }
+
+        // Leaking a cloned SetupParams would keep an extra reference alive
+        assert_eq!(Arc::strong_count(&setup.params.trinity), 1);
+    }
+
+    #[cfg(feature = "halo2")]
     #[test]
     fn test_wasm_evaluator_creation_with_serialization_halo2() {
         // 1. Load the pre-generated `halo2params.bin` file from disk.
@@ -461,7 +2025,7 @@ mod tests {
 
         let result = std::panic::catch_unwind(|| {
             println!("Calling TrinityEvaluator::new with deserialized setup...");
-            let _evaluator = TrinityEvaluator::new(&deserialized_setup, evaluator_input);
+            let _evaluator = TrinityEvaluator::new(&deserialized_setup, evaluator_input).unwrap();
             println!("TrinityEvaluator created successfully with deserialized setup.");
         });
 
@@ -471,4 +2035,50 @@ mod tests {
             "TrinityEvaluator::new panicked with deserialized setup!"
         );
     }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn test_evaluator_private_state_is_wiped() {
+        use crate::commit::{TrinityError, TrinityReceiver};
+        use rand::rngs::OsRng;
+        use zeroize::{Zeroize, ZeroizeOnDrop};
+
+        fn wiped_on_drop<T: ZeroizeOnDrop>(_: &T) {}
+
+        let setup = TrinityWasmSetup::new("Plain").unwrap();
+        let mut evaluator = TrinityEvaluator::new(&setup, vec![4, 0]).unwrap();
+        let commitment = evaluator.ot_receiver.trinity_receiver.commitment();
+
+        // The copies handed to `ev_commit` and `evaluate_circuit`
+        let mut copy = Lsb0Bits::new(evaluator.evaluator_bits.to_vec());
+        wiped_on_drop(&copy);
+        copy.zeroize();
+        assert!(copy.is_empty());
+
+        // The evaluator's own bits and the choices its receiver holds
+        wiped_on_drop(&evaluator.evaluator_bits);
+        evaluator.evaluator_bits.zeroize();
+        assert!(evaluator.evaluator_bits.is_empty());
+        match &mut evaluator.ot_receiver.trinity_receiver {
+            TrinityReceiver::Plain(recv) => {
+                wiped_on_drop(recv);
+                recv.zeroize();
+            }
+            #[allow(unreachable_patterns)]
+            _ => unreachable!(),
+        }
+        let sender = setup
+            .params
+            .trinity
+            .create_ot_sender::<()>(commitment)
+            .unwrap();
+        let msg = sender
+            .trinity_sender
+            .send(&mut OsRng, 0, [0u8; 16], [1u8; 16])
+            .unwrap();
+        assert_eq!(
+            evaluator.ot_receiver.trinity_receiver.recv(0, msg).err(),
+            Some(TrinityError::IndexOutOfRange { index: 0, len: 0 })
+        );
+    }
 }
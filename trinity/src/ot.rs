@@ -1,4 +1,9 @@
-use crate::commit::{TrinityReceiver, TrinitySender};
+use crate::commit::{TrinityError, TrinityMsg, TrinityReceiver, TrinitySender};
+#[cfg(feature = "plain")]
+use ark_bn254::{Bn254, Fr};
+#[cfg(feature = "plain")]
+use ark_poly::Radix2EvaluationDomain;
+use rand::Rng;
 use std::marker::PhantomData;
 
 #[allow(dead_code)]
@@ -8,7 +13,174 @@ pub struct KZGOTSender<'a, Ctx> {
 }
 
 #[allow(dead_code)]
-pub struct KZGOTReceiver<'a, Ctx> {
-    pub(crate) trinity_receiver: TrinityReceiver<'a>,
+pub struct KZGOTReceiver<Ctx> {
+    pub(crate) trinity_receiver: TrinityReceiver,
     pub(crate) _phantom: PhantomData<Ctx>,
 }
+
+/// Sender side of a 1-out-of-2 laconic OT over `N`-byte messages.
+///
+/// Implemented by the Plain and Halo2 backends and by `TrinitySender`, which
+/// dispatches to either, so generic code can be written once for all of them.
+pub trait LaconicOtSender<const N: usize> {
+    type Msg;
+    type Error;
+
+    /// Encrypt `m0` and `m1` for position `i`, the receiver can only open the
+    /// one its committed bit selects
+    fn send<R: Rng>(
+        &self,
+        rng: &mut R,
+        i: usize,
+        m0: [u8; N],
+        m1: [u8; N],
+    ) -> Result<Self::Msg, Self::Error>;
+}
+
+/// Receiver side of `LaconicOtSender`
+pub trait LaconicOtReceiver<const N: usize> {
+    type Msg;
+    type Error;
+
+    /// Decrypt the message for position `i`. Compare results with the
+    /// backends' `ct_eq`.
+    fn recv(&self, i: usize, msg: Self::Msg) -> Result<[u8; N], Self::Error>;
+}
+
+#[cfg(feature = "plain")]
+impl<'a, const N: usize> LaconicOtSender<N>
+    for laconic_ot::LaconicOTSender<'a, Bn254, Radix2EvaluationDomain<Fr>>
+{
+    type Msg = laconic_ot::Msg<Bn254, N>;
+    type Error = laconic_ot::SendError;
+
+    fn send<R: Rng>(
+        &self,
+        rng: &mut R,
+        i: usize,
+        m0: [u8; N],
+        m1: [u8; N],
+    ) -> Result<Self::Msg, Self::Error> {
+        laconic_ot::LaconicOTSender::send(self, rng, i, m0, m1)
+    }
+}
+
+#[cfg(feature = "plain")]
+impl<const N: usize> LaconicOtReceiver<N>
+    for laconic_ot::LaconicOTRecv<Bn254, Radix2EvaluationDomain<Fr>>
+{
+    type Msg = laconic_ot::Msg<Bn254, N>;
+    type Error = laconic_ot::RecvError;
+
+    fn recv(&self, i: usize, msg: Self::Msg) -> Result<[u8; N], Self::Error> {
+        laconic_ot::LaconicOTRecv::recv(self, i, msg)
+    }
+}
+
+#[cfg(feature = "halo2")]
+impl<const N: usize> LaconicOtSender<N> for halo2_we_kzg::LaconicOTSender {
+    type Msg = halo2_we_kzg::Msg<N>;
+    type Error = halo2_we_kzg::SendError;
+
+    fn send<R: Rng>(
+        &self,
+        rng: &mut R,
+        i: usize,
+        m0: [u8; N],
+        m1: [u8; N],
+    ) -> Result<Self::Msg, Self::Error> {
+        halo2_we_kzg::LaconicOTSender::send(self, rng, i, m0, m1)
+    }
+}
+
+#[cfg(feature = "halo2")]
+impl<const N: usize> LaconicOtReceiver<N> for halo2_we_kzg::LaconicOTRecv {
+    type Msg = halo2_we_kzg::Msg<N>;
+    type Error = halo2_we_kzg::RecvError;
+
+    fn recv(&self, i: usize, msg: Self::Msg) -> Result<[u8; N], Self::Error> {
+        halo2_we_kzg::LaconicOTRecv::recv(self, i, msg)
+    }
+}
+
+impl<'a, const N: usize> LaconicOtSender<N> for TrinitySender<'a> {
+    type Msg = TrinityMsg<N>;
+    type Error = TrinityError;
+
+    fn send<R: Rng>(
+        &self,
+        rng: &mut R,
+        i: usize,
+        m0: [u8; N],
+        m1: [u8; N],
+    ) -> Result<Self::Msg, Self::Error> {
+        TrinitySender::send(self, rng, i, m0, m1)
+    }
+}
+
+impl<const N: usize> LaconicOtReceiver<N> for TrinityReceiver {
+    type Msg = TrinityMsg<N>;
+    type Error = TrinityError;
+
+    fn recv(&self, i: usize, msg: Self::Msg) -> Result<[u8; N], Self::Error> {
+        TrinityReceiver::recv(self, i, msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commit::{KZGType, Trinity, TrinityChoice};
+    use rand::rngs::OsRng;
+    use std::fmt::Debug;
+
+    /// Written once against the traits, run over every backend below
+    fn transfer<S, R>(sender: &S, receiver: &R, bits: &[bool])
+    where
+        S: LaconicOtSender<32>,
+        R: LaconicOtReceiver<32, Msg = S::Msg>,
+        S::Error: Debug,
+        R::Error: Debug,
+    {
+        for (i, &bit) in bits.iter().enumerate() {
+            let (m0, m1) = ([i as u8; 32], [i as u8 + 100; 32]);
+            let msg = sender.send(&mut OsRng, i, m0, m1).unwrap();
+            assert_eq!(receiver.recv(i, msg).unwrap(), if bit { m1 } else { m0 });
+        }
+    }
+
+    #[test]
+    fn test_generic_transfer_over_all_backends() {
+        let bits = [true, false, false, true];
+        let choices = TrinityChoice::unpack(&[0b1001], bits.len());
+
+        let modes = [
+            #[cfg(feature = "plain")]
+            KZGType::Plain,
+            #[cfg(feature = "halo2")]
+            KZGType::Halo2,
+        ];
+        for mode in modes {
+            let trinity = Trinity::setup(mode, 4);
+            let receiver = trinity.create_ot_receiver::<()>(&choices).unwrap();
+            let sender = trinity
+                .create_ot_sender::<()>(receiver.trinity_receiver.commitment())
+                .unwrap();
+            transfer(&sender.trinity_sender, &receiver.trinity_receiver, &bits);
+
+            // The backend types directly, without the Trinity dispatch
+            match (&sender.trinity_sender, &receiver.trinity_receiver) {
+                #[cfg(feature = "plain")]
+                (TrinitySender::Plain(sender), TrinityReceiver::Plain(receiver)) => {
+                    transfer(sender, receiver, &bits)
+                }
+                #[cfg(feature = "halo2")]
+                (TrinitySender::Halo2(sender, _), TrinityReceiver::Halo2(receiver)) => {
+                    transfer(sender, receiver, &bits)
+                }
+                #[allow(unreachable_patterns)]
+                _ => unreachable!("sender and receiver come from the same params"),
+            }
+        }
+    }
+}
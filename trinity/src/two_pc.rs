@@ -1,29 +1,36 @@
 use std::sync::Arc;
 
-use crate::commit::{KZGType, Trinity};
+use mpz_circuits::Circuit;
 
-const MSG_SIZE: usize = 16;
-
-pub fn u8_vec_to_vec_bool(input: Vec<u8>) -> Vec<bool> {
-    let mut result = Vec::with_capacity(input.len() * 8);
-
-    for &byte in &input {
-        // Extract all 8 bits from each byte (LSB0 order)
-        for i in 0..8 {
-            result.push((byte >> i) & 1 == 1);
-        }
-    }
+use crate::commit::{KZGType, Randomness, Trinity, TrinityCom, TrinityError, TrinityParams};
+use crate::encoding::Lsb0Bits;
+use crate::evaluate::{ev_commit, evaluate_circuit};
+use crate::garble::generate_garbled_circuit_with_randomness;
 
-    result
-}
+const MSG_SIZE: usize = 16;
 
-#[derive(Clone)]
+/// Setup shared between garbler and evaluator.
+///
+/// Cloning only bumps the `Arc`, and the type is `Send + Sync`, so one setup
+/// can be handed to many worker threads.
+#[derive(Clone, Debug)]
 pub struct SetupParams {
     pub trinity: Arc<Trinity>,
 }
 
+// Compile-time check that setup and commitments can be shared across threads
+#[allow(dead_code)]
+fn _assert_send_sync<T: Send + Sync>() {}
+
+const _: fn() = || {
+    _assert_send_sync::<Trinity>();
+    _assert_send_sync::<SetupParams>();
+    _assert_send_sync::<TrinityCom>();
+    _assert_send_sync::<TrinityParams>();
+};
+
 impl SetupParams {
-    pub fn from_sender_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+    pub fn from_sender_bytes(bytes: &[u8]) -> Result<Self, TrinityError> {
         let trinity = Trinity::from_sender_bytes(bytes)?;
         let arc_trinity = Arc::new(trinity);
         Ok(Self {
@@ -35,7 +42,11 @@ impl SetupParams {
         self.trinity.to_sender_bytes()
     }
 
-    pub fn from_full_params_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+    pub fn to_sender_bytes_compressed(&self) -> Vec<u8> {
+        self.trinity.to_sender_bytes_compressed()
+    }
+
+    pub fn from_full_params_bytes(bytes: &[u8]) -> Result<Self, TrinityError> {
         let trinity = Trinity::from_full_params_bytes(bytes)?;
         let arc_trinity = Arc::new(trinity);
         Ok(Self {
@@ -46,6 +57,14 @@ impl SetupParams {
     pub fn to_full_params_bytes(&self) -> Vec<u8> {
         self.trinity.to_full_params_bytes()
     }
+
+    /// The same params under session `id`, see `Trinity::with_session_id`.
+    /// Only the `Arc`s are cloned, so one setup can serve many sessions.
+    pub fn with_session_id(&self, id: [u8; 32]) -> Self {
+        Self {
+            trinity: Arc::new(self.trinity.as_ref().clone().with_session_id(id)),
+        }
+    }
 }
 
 pub fn setup(mode: KZGType) -> SetupParams {
@@ -54,6 +73,46 @@ pub fn setup(mode: KZGType) -> SetupParams {
     SetupParams { trinity }
 }
 
+pub fn setup_with_degree(mode: KZGType, k: u32) -> Result<SetupParams, TrinityError> {
+    let trinity = Arc::new(Trinity::setup_with_degree(mode, k)?);
+
+    Ok(SetupParams { trinity })
+}
+
+/// Run the whole protocol locally: setup, evaluator commitment, garbling and
+/// evaluation, returning the output bits. For tests and demos, the parties
+/// never leave this process.
+///
+/// Panics if the inputs don't match the circuit.
+pub fn run_2pc(
+    circuit: Arc<Circuit>,
+    garbler_bits: Lsb0Bits,
+    evaluator_bits: Lsb0Bits,
+    mode: KZGType,
+) -> Vec<bool> {
+    let setup_params = setup(mode);
+
+    let evaluator_commitment =
+        ev_commit(evaluator_bits.clone(), &setup_params).expect("Failed to commit");
+
+    let garbled = generate_garbled_circuit_with_randomness(
+        circuit.clone(),
+        garbler_bits,
+        Randomness::Secure,
+        &setup_params.trinity,
+        evaluator_commitment.receiver_commitment,
+    )
+    .expect("Failed to garble circuit");
+
+    evaluate_circuit(
+        circuit,
+        garbled,
+        evaluator_bits,
+        &evaluator_commitment.ot_receiver,
+    )
+    .expect("Failed to evaluate circuit")
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -65,19 +124,23 @@ mod tests {
 
     use crate::{
         commit::KZGType,
-        evaluate::{ev_commit, evaluate_circuit},
-        garble::generate_garbled_circuit,
-        two_pc::setup,
+        encoding::{pack_inputs, Lsb0Bits},
+        evaluate::{
+            ev_commit, evaluate_circuit, evaluate_circuit_typed, evaluate_circuit_with_macs,
+        },
+        garble::{decode_output_pointers, generate_garbled_circuit, GarbledBundle},
+        two_pc::{run_2pc, setup},
     };
 
     pub fn u16_to_vec_bool(input: Vec<u16>) -> Vec<bool> {
-        (0..16).map(|i| (input[0] >> i) & 1 == 1).collect() // LSB0
+        pack_inputs(&[u64::from(input[0])], &[ValueType::U16]).unwrap()
     }
 
     // pub fn u8_to_vec_bool(input: Vec<u8>) -> Vec<bool> {
     //     (0..8).map(|i| (input[0] >> i) & 1 == 1).collect() // LSB0
     // }
 
+    #[cfg(feature = "plain")]
     #[test]
     fn two_pc_e2e_plain() {
         let mut rng = StdRng::seed_from_u64(0);
@@ -94,10 +157,8 @@ mod tests {
         let setup_bundle = setup(KZGType::Plain);
         let trinity = setup_bundle.clone().trinity;
 
-        let garbler_input = [6u16];
-        let garbler_bits = garbler_input.into_iter_lsb0().collect::<Vec<bool>>();
-        let evaluator_input = [4u16];
-        let evaluator_bits = evaluator_input.into_iter_lsb0().collect::<Vec<bool>>();
+        let garbler_bits = Lsb0Bits::from_u16(6);
+        let evaluator_bits = Lsb0Bits::from_u16(4);
         let expected: [u16; 1] = [10u16];
 
         let delta = Delta::random(&mut rng);
@@ -113,19 +174,63 @@ mod tests {
             delta,
             &trinity,
             evaluator_commitment.receiver_commitment,
-        );
+        )
+        .unwrap();
 
         let result = evaluate_circuit(
             arc_circuit,
             garbled,
             evaluator_bits,
-            evaluator_commitment.ot_receiver,
+            &evaluator_commitment.ot_receiver,
         )
         .unwrap();
 
         assert!(result == u16_to_vec_bool(expected.to_vec()));
     }
 
+    #[cfg(feature = "plain")]
+    #[test]
+    fn two_pc_output_macs_decode_to_output() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let circ = Arc::new(
+            Circuit::parse(
+                "circuits/simple_16bit_add.txt",
+                &[
+                    ValueType::Array(Box::new(ValueType::Bit), 16),
+                    ValueType::Array(Box::new(ValueType::Bit), 16),
+                ],
+                &[ValueType::Array(Box::new(ValueType::Bit), 16)],
+            )
+            .unwrap(),
+        );
+        let setup_bundle = setup(KZGType::Plain);
+
+        let delta = Delta::random(&mut rng);
+
+        let evaluator_bits = Lsb0Bits::from_u16(4);
+        let commitment = ev_commit(evaluator_bits.clone(), &setup_bundle).unwrap();
+        let garbled = generate_garbled_circuit(
+            circ.clone(),
+            Lsb0Bits::from_u16(6),
+            &mut rng,
+            delta,
+            &setup_bundle.trinity,
+            commitment.receiver_commitment,
+        )
+        .unwrap();
+        let decoding_bits = garbled.decoding_bits().to_vec();
+
+        let (output, macs) =
+            evaluate_circuit_with_macs(circ, garbled, evaluator_bits, &commitment.ot_receiver)
+                .unwrap();
+        assert_eq!(output, u16_to_vec_bool(vec![10]));
+
+        // A third party holding the MACs and the decoding bits gets the same
+        let pointers: Vec<bool> = macs.iter().map(|mac| mac.pointer()).collect();
+        assert_eq!(decode_output_pointers(&pointers, &decoding_bits), output);
+    }
+
+    #[cfg(feature = "halo2")]
     #[test]
     fn two_pc_e2e_halo2() {
         let mut rng = StdRng::seed_from_u64(0);
@@ -142,10 +247,8 @@ mod tests {
         let setup_bundle = setup(KZGType::Halo2);
         let trinity = setup_bundle.clone().trinity;
 
-        let garbler_input = [6u16];
-        let garbler_bits = garbler_input.into_iter_lsb0().collect::<Vec<bool>>();
-        let evaluator_input = [4u16];
-        let evaluator_bits = evaluator_input.into_iter_lsb0().collect::<Vec<bool>>();
+        let garbler_bits = Lsb0Bits::from_u16(6);
+        let evaluator_bits = Lsb0Bits::from_u16(4);
         let expected: [u16; 1] = [10u16];
 
         let delta = Delta::random(&mut rng);
@@ -161,16 +264,287 @@ mod tests {
             delta,
             &trinity,
             evaluator_commitment.receiver_commitment,
-        );
+        )
+        .unwrap();
 
         let result = evaluate_circuit(
             arc_circuit,
             garbled,
             evaluator_bits,
-            evaluator_commitment.ot_receiver,
+            &evaluator_commitment.ot_receiver,
         )
         .unwrap();
 
         assert!(result == u16_to_vec_bool(expected.to_vec()));
     }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn two_pc_e2e_bundle_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let circ = Circuit::parse(
+            "circuits/simple_16bit_add.txt",
+            &[
+                ValueType::Array(Box::new(ValueType::Bit), 16),
+                ValueType::Array(Box::new(ValueType::Bit), 16),
+            ],
+            &[ValueType::Array(Box::new(ValueType::Bit), 16)],
+        )
+        .unwrap();
+        let setup_bundle = setup(KZGType::Plain);
+        let trinity = setup_bundle.clone().trinity;
+
+        let garbler_bits = Lsb0Bits::from_u16(6);
+        let evaluator_bits = Lsb0Bits::from_u16(4);
+        let expected: [u16; 1] = [10u16];
+
+        let delta = Delta::random(&mut rng);
+        let arc_circuit = Arc::new(circ);
+
+        let evaluator_commitment = ev_commit(evaluator_bits.clone(), &setup_bundle).unwrap();
+
+        let garbled = generate_garbled_circuit(
+            arc_circuit.clone(),
+            garbler_bits,
+            &mut rng,
+            delta,
+            &trinity,
+            evaluator_commitment.receiver_commitment,
+        )
+        .unwrap();
+
+        // Ship the bundle through its wire format before evaluating
+        let bytes = garbled.to_bytes();
+        let received = GarbledBundle::from_bytes(&bytes).unwrap();
+        assert_eq!(received.to_bytes(), bytes);
+
+        let result = evaluate_circuit(
+            arc_circuit,
+            received,
+            evaluator_bits,
+            &evaluator_commitment.ot_receiver,
+        )
+        .unwrap();
+
+        assert!(result == u16_to_vec_bool(expected.to_vec()));
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn two_pc_e2e_typed_outputs() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let circ = Circuit::parse(
+            "circuits/simple_16bit_add.txt",
+            &[
+                ValueType::Array(Box::new(ValueType::Bit), 16),
+                ValueType::Array(Box::new(ValueType::Bit), 16),
+            ],
+            &[ValueType::Array(Box::new(ValueType::Bit), 16)],
+        )
+        .unwrap();
+        let setup_bundle = setup(KZGType::Plain);
+        let trinity = setup_bundle.clone().trinity;
+
+        let garbler_bits = Lsb0Bits::from_u16(6);
+        let evaluator_bits = Lsb0Bits::from_u16(4);
+
+        let arc_circuit = Arc::new(circ);
+        let evaluator_commitment = ev_commit(evaluator_bits.clone(), &setup_bundle).unwrap();
+
+        // Same garbled output decoded as a single u16 and as two bytes
+        for (widths, expected) in [(vec![16], vec![10u64]), (vec![8, 8], vec![10u64, 0])] {
+            let delta = Delta::random(&mut rng);
+            let garbled = generate_garbled_circuit(
+                arc_circuit.clone(),
+                garbler_bits.clone(),
+                &mut rng,
+                delta,
+                &trinity,
+                evaluator_commitment.receiver_commitment,
+            )
+            .unwrap();
+
+            let result = evaluate_circuit_typed(
+                arc_circuit.clone(),
+                garbled,
+                evaluator_bits.clone(),
+                &evaluator_commitment.ot_receiver,
+                &widths,
+            )
+            .unwrap();
+
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn run_2pc_adds_in_every_mode() {
+        let circ = Circuit::parse(
+            "circuits/simple_16bit_add.txt",
+            &[
+                ValueType::Array(Box::new(ValueType::Bit), 16),
+                ValueType::Array(Box::new(ValueType::Bit), 16),
+            ],
+            &[ValueType::Array(Box::new(ValueType::Bit), 16)],
+        )
+        .unwrap();
+        let circ = Arc::new(circ);
+
+        let modes = [
+            #[cfg(feature = "plain")]
+            KZGType::Plain,
+            #[cfg(feature = "halo2")]
+            KZGType::Halo2,
+        ];
+        for mode in modes {
+            let result = run_2pc(
+                circ.clone(),
+                Lsb0Bits::from_u16(6),
+                Lsb0Bits::from_u16(4),
+                mode,
+            );
+            assert_eq!(result, u16_to_vec_bool(vec![10]));
+        }
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn deterministic_randomness_is_reported() {
+        use crate::{
+            commit::{Randomness, Trinity},
+            garble::generate_garbled_circuit_with_randomness,
+            two_pc::SetupParams,
+        };
+
+        let circ = Arc::new(
+            Circuit::parse(
+                "circuits/simple_16bit_add.txt",
+                &[
+                    ValueType::Array(Box::new(ValueType::Bit), 16),
+                    ValueType::Array(Box::new(ValueType::Bit), 16),
+                ],
+                &[ValueType::Array(Box::new(ValueType::Bit), 16)],
+            )
+            .unwrap(),
+        );
+
+        for randomness in [Randomness::Secure, Randomness::Deterministic { seed: 3 }] {
+            let trinity = Trinity::setup_with_randomness(randomness, KZGType::Plain, 16);
+            assert_eq!(trinity.is_deterministic(), randomness.is_deterministic());
+            let setup_bundle = SetupParams {
+                trinity: Arc::new(trinity),
+            };
+
+            let evaluator_bits = Lsb0Bits::from_u16(4);
+            let commitment = ev_commit(evaluator_bits.clone(), &setup_bundle).unwrap();
+            let garbled = generate_garbled_circuit_with_randomness(
+                circ.clone(),
+                Lsb0Bits::from_u16(6),
+                randomness,
+                &setup_bundle.trinity,
+                commitment.receiver_commitment,
+            )
+            .unwrap();
+            let result = evaluate_circuit(
+                circ.clone(),
+                garbled,
+                evaluator_bits,
+                &commitment.ot_receiver,
+            )
+            .unwrap();
+            assert_eq!(result, u16_to_vec_bool(vec![10]));
+
+            // Still reported after the params are shared through the setup
+            assert_eq!(
+                setup_bundle.trinity.is_deterministic(),
+                randomness.is_deterministic()
+            );
+        }
+
+        // The flag is not part of the wire format
+        let trinity = Trinity::setup_with_randomness(
+            Randomness::Deterministic { seed: 3 },
+            KZGType::Plain,
+            4,
+        );
+        let garbler = Trinity::from_sender_bytes(&trinity.to_sender_bytes()).unwrap();
+        assert!(!garbler.is_deterministic());
+    }
+
+    fn parse_8bit_not() -> Arc<Circuit> {
+        let circ = Circuit::parse(
+            "circuits/8bit_not.txt",
+            &[ValueType::Array(Box::new(ValueType::Bit), 8)],
+            &[ValueType::Array(Box::new(ValueType::Bit), 8)],
+        )
+        .unwrap();
+        Arc::new(circ)
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn two_pc_e2e_no_garbler_input() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let circ = parse_8bit_not();
+        let setup_bundle = setup(KZGType::Plain);
+
+        let evaluator_bits = Lsb0Bits::from_u8(0b1010_0101);
+        let expected = [0b0101_1010u8].into_iter_lsb0().collect::<Vec<bool>>();
+
+        let evaluator_commitment = ev_commit(evaluator_bits.clone(), &setup_bundle).unwrap();
+        let garbled = generate_garbled_circuit(
+            circ.clone(),
+            Lsb0Bits::default(),
+            &mut rng,
+            Delta::random(&mut rng),
+            &setup_bundle.trinity,
+            evaluator_commitment.receiver_commitment,
+        )
+        .unwrap();
+        assert_eq!(garbled.num_ciphertexts(), 8);
+
+        let result = evaluate_circuit(
+            circ,
+            garbled,
+            evaluator_bits,
+            &evaluator_commitment.ot_receiver,
+        )
+        .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[cfg(feature = "plain")]
+    #[test]
+    fn two_pc_e2e_no_evaluator_input() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let circ = parse_8bit_not();
+        let setup_bundle = setup(KZGType::Plain);
+
+        let garbler_bits = Lsb0Bits::from_u8(0b1100_0011);
+        let expected = [0b0011_1100u8].into_iter_lsb0().collect::<Vec<bool>>();
+
+        let evaluator_commitment = ev_commit(Lsb0Bits::default(), &setup_bundle).unwrap();
+        let garbled = generate_garbled_circuit(
+            circ.clone(),
+            garbler_bits,
+            &mut rng,
+            Delta::random(&mut rng),
+            &setup_bundle.trinity,
+            evaluator_commitment.receiver_commitment,
+        )
+        .unwrap();
+        // No evaluator bits, so no OT ciphertexts
+        assert_eq!(garbled.num_ciphertexts(), 0);
+
+        let result = evaluate_circuit(
+            circ,
+            garbled,
+            Lsb0Bits::default(),
+            &evaluator_commitment.ot_receiver,
+        )
+        .unwrap();
+        assert_eq!(result, expected);
+    }
 }